@@ -1,6 +1,3 @@
-mod net_ffi;
-pub use net_ffi::*;
-
 mod requests;
 pub use requests::*;
 
@@ -1,5 +1,5 @@
 use super::server::{ConnectionError, ConnectionResult};
-use regex::Regex;
+use regex::bytes::Regex;
 
 pub enum Request {
     Store(StoreRequest),
@@ -27,10 +27,10 @@ impl LoadRequest {
     }
 }
 
-static STORE: &str = "STORE$";
-static LOAD: &str = "LOAD$";
+static STORE: &[u8] = b"STORE$";
+static LOAD: &[u8] = b"LOAD$";
 
-fn match_regex(message: &str, pattern: &str) -> ConnectionResult<bool> {
+fn match_regex(message: &[u8], pattern: &str) -> ConnectionResult<bool> {
     match Regex::new(pattern) {
         Ok(regex) => Ok(regex.is_match(message)),
         Err(_) => Err(ConnectionError),
@@ -39,19 +39,19 @@ fn match_regex(message: &str, pattern: &str) -> ConnectionResult<bool> {
 
 // Returns true if there exists a prefix of a message parameter
 // that is a correct STORE request.
-fn is_store_request(message: &str) -> ConnectionResult<bool> {
+fn is_store_request(message: &[u8]) -> ConnectionResult<bool> {
     match_regex(message, r#"^STORE\$[a-z]*\$[a-z]*\n"#)
 }
 
 // Returns true if there exists a prefix of a message parameter
 // that is a correct LOAD request.
-fn is_load_request(message: &str) -> ConnectionResult<bool> {
+fn is_load_request(message: &[u8]) -> ConnectionResult<bool> {
     match_regex(message, r#"^LOAD\$[a-z]*\n"#)
 }
 
 // Returns true if message could become a correct STORE request
-// after appending more chars.
-fn could_become_store_request(message: &str) -> ConnectionResult<bool> {
+// after appending more bytes.
+fn could_become_store_request(message: &[u8]) -> ConnectionResult<bool> {
     if message.len() <= STORE.len() {
         return Ok(message == &STORE[..message.len()]);
     }
@@ -61,8 +61,8 @@ fn could_become_store_request(message: &str) -> ConnectionResult<bool> {
 }
 
 // Returns true if message could become a correct LOAD request
-// after appending more chars.
-fn could_become_load_request(message: &str) -> ConnectionResult<bool> {
+// after appending more bytes.
+fn could_become_load_request(message: &[u8]) -> ConnectionResult<bool> {
     if message.len() <= LOAD.len() {
         return Ok(message == &LOAD[..message.len()]);
     }
@@ -70,31 +70,44 @@ fn could_become_load_request(message: &str) -> ConnectionResult<bool> {
     match_regex(message, r"^LOAD\$[a-z]*$")
 }
 
+fn position(message: &[u8], byte: u8) -> usize {
+    message.iter().position(|&b| b == byte).unwrap()
+}
+
+// Keys and values of correct requests consist of lowercase ASCII letters only.
+fn to_string(bytes: &[u8]) -> String {
+    String::from_utf8(bytes.to_vec()).unwrap()
+}
+
 // Splits a message with a prefix that is a correct STORE request
 // from STORE$key$value\nrest to (key, value, rest).
-fn split_store_request(message: &str) -> (String, String, String) {
+fn split_store_request(message: &[u8]) -> (String, String, Vec<u8>) {
     let message = &message[STORE.len()..];
-    let dollar = message.find('$').unwrap();
-    let newline = message.find('\n').unwrap();
-    let key = message[..dollar].to_string();
-    let value = message[dollar + 1..newline].to_string();
-    let rest = message[newline + 1..].to_string();
+    let dollar = position(message, b'$');
+    let newline = position(message, b'\n');
+    let key = to_string(&message[..dollar]);
+    let value = to_string(&message[dollar + 1..newline]);
+    let rest = message[newline + 1..].to_vec();
     (key, value, rest)
 }
 
 // Splits a message with a prefix that is a correct LOAD request
 // from LOAD$key\nrest to (key, rest).
-fn split_load_request(message: &str) -> (String, String) {
-    let newline = message.find('\n').unwrap();
-    let key = message[LOAD.len()..newline].to_string();
-    let rest = message[newline + 1..].to_string();
+fn split_load_request(message: &[u8]) -> (String, Vec<u8>) {
+    let newline = position(message, b'\n');
+    let key = to_string(&message[LOAD.len()..newline]);
+    let rest = message[newline + 1..].to_vec();
     (key, rest)
 }
 
 // If a message contains a prefix that is a correct request, returns
 // Some(request). If the message is incorrect, returns ConnectionError.
 // Otherwise, returns None. Removes the request part from the message.
-pub fn try_parse_request(message: &mut String) -> ConnectionResult<Option<Request>> {
+//
+// The message is made of raw bytes received from the client, so a chunk
+// ending in the middle of a multi-byte character is handled like any
+// other incomplete request.
+pub fn try_parse_request(message: &mut Vec<u8>) -> ConnectionResult<Option<Request>> {
     if is_store_request(message)? {
         let (key, value, rest) = split_store_request(message);
         *message = rest;
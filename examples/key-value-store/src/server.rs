@@ -1,9 +1,11 @@
 use super::*;
-use cxx::UniquePtr;
+use seastar::metrics::{Counter, MetricGroup};
+use seastar::{InputStream, OutputStream, TcpConnection, TcpListener};
 use std::cell::RefCell;
 use std::collections::hash_map::DefaultHasher;
 use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
+use std::net::{Ipv4Addr, SocketAddr};
 
 // An error returned when something fails while handling requests.
 // We do not care what really happened because in every case we just
@@ -14,35 +16,47 @@ pub struct ConnectionError;
 pub type ConnectionResult<T> = Result<T, ConnectionError>;
 
 pub struct Connection {
-    _socket: UniquePtr<ConnectedSocket>,
-    input: UniquePtr<InputStream>,
-    output: UniquePtr<OutputStream>,
-    message: String, // Unprocessed fragment of the message.
+    input: InputStream,
+    output: OutputStream,
+    message: Vec<u8>,       // Unprocessed fragment of the message.
+    _socket: TcpConnection, // Dropped last, as the streams use it.
 }
 
 impl Connection {
-    fn new(
-        _socket: UniquePtr<ConnectedSocket>,
-        input: UniquePtr<InputStream>,
-        output: UniquePtr<OutputStream>,
-    ) -> Self {
+    fn new(socket: TcpConnection) -> Self {
         Self {
-            _socket,
-            input,
-            output,
-            message: String::new(),
+            input: socket.input(),
+            output: socket.output(),
+            message: Vec::new(),
+            _socket: socket,
         }
     }
 }
 
 pub struct Server {
+    // The service is borrowed immutably by every connection it serves, so the store
+    // is mutated through a `RefCell`. Borrows never last across an await.
     db: RefCell<HashMap<String, String>>,
+    hits: Counter,
+    misses: Counter,
+    _metrics: MetricGroup,
 }
 
 impl Server {
     pub fn new() -> Self {
+        let mut metrics = MetricGroup::new("kv_store");
+        // Each shard creates a single server, so the metrics are registered once per shard.
+        let hits = metrics
+            .add_counter("hits", "Number of LOAD requests that found a key")
+            .unwrap();
+        let misses = metrics
+            .add_counter("misses", "Number of LOAD requests that missed a key")
+            .unwrap();
         Server {
             db: RefCell::new(HashMap::new()),
+            hits,
+            misses,
+            _metrics: metrics,
         }
     }
 }
@@ -60,15 +74,14 @@ pub struct ShardedServer<'a>(pub seastar::PeeringShardedService<'a, Server>);
 impl ShardedServer<'_> {
     #[allow(unused_must_use)]
     pub async fn run(self, port: u16) {
-        let listener = listen(port);
+        let listener = TcpListener::bind(SocketAddr::from((Ipv4Addr::UNSPECIFIED, port)))
+            .unwrap_or_else(|e| panic!("Failed to listen on port {port}: {e}"));
 
         loop {
-            if let Ok(socket) = accept(&listener).await {
+            if let Ok((socket, _)) = listener.accept().await {
                 self.0.container.map_current(|sharded| async move {
-                    let input = get_input_stream(&socket);
-                    let output = get_output_stream(&socket);
                     Self(sharded)
-                        .handle_connection(Connection::new(socket, input, output))
+                        .handle_connection(Connection::new(socket))
                         .await;
                 });
             }
@@ -77,11 +90,13 @@ impl ShardedServer<'_> {
 
     async fn handle_connection(self, mut conn: Connection) {
         loop {
-            match read(&conn.input).await {
+            match conn.input.read().await {
                 Err(_) => break,
                 Ok(buffer) if buffer.is_empty() => break,
                 Ok(buffer) => {
-                    buffer.chars().for_each(|c| conn.message.push(c));
+                    // Chunks may end in the middle of a multi-byte character,
+                    // so they are only interpreted once a whole request is received.
+                    conn.message.extend_from_slice(&buffer);
 
                     if self.process_message(&mut conn).await.is_err() {
                         break;
@@ -90,7 +105,7 @@ impl ShardedServer<'_> {
             }
         }
 
-        let _ = close_output_stream(&conn.output).await;
+        let _ = conn.output.close().await;
     }
 
     // Processes message until it has no prefix being a complete STORE or LOAD request.
@@ -108,15 +123,14 @@ impl ShardedServer<'_> {
 
     async fn process_store_request(
         &self,
-        conn: &Connection,
+        conn: &mut Connection,
         req: StoreRequest,
     ) -> ConnectionResult<()> {
         let storage_id = Self::get_storing_shard_id(&req.key);
         self.0
             .container
             .map_single(storage_id, |sharded| async move {
-                let mut db = sharded.instance.db.borrow_mut();
-                db.insert(req.key, req.value);
+                sharded.instance.db.borrow_mut().insert(req.key, req.value);
             })
             .await;
 
@@ -125,7 +139,7 @@ impl ShardedServer<'_> {
 
     async fn process_load_request(
         &self,
-        conn: &Connection,
+        conn: &mut Connection,
         req: LoadRequest,
     ) -> ConnectionResult<()> {
         let storage_id = Self::get_storing_shard_id(&req.key);
@@ -133,8 +147,12 @@ impl ShardedServer<'_> {
             .0
             .container
             .map_single(storage_id, |sharded| async move {
-                let db = sharded.instance.db.borrow();
-                db.get(&req.key).cloned()
+                let value = sharded.instance.db.borrow().get(&req.key).cloned();
+                match value {
+                    Some(_) => sharded.instance.hits.increment(),
+                    None => sharded.instance.misses.increment(),
+                }
+                value
             })
             .await;
 
@@ -144,11 +162,9 @@ impl ShardedServer<'_> {
         }
     }
 
-    async fn respond(&self, conn: &Connection, message: &str) -> ConnectionResult<()> {
-        write(&conn.output, message)
-            .await
-            .map_err(|_| ConnectionError)?;
-        flush_output(&conn.output)
+    async fn respond(&self, conn: &mut Connection, message: &str) -> ConnectionResult<()> {
+        conn.output
+            .write_all_and_flush(message.as_bytes())
             .await
             .map_err(|_| ConnectionError)
     }
@@ -156,6 +172,6 @@ impl ShardedServer<'_> {
     fn get_storing_shard_id(key: &String) -> u32 {
         let mut hasher = DefaultHasher::new();
         key.hash(&mut hasher);
-        hasher.finish() as u32 % seastar::get_count()
+        hasher.finish() as u32 % seastar::smp::cached_count()
     }
 }
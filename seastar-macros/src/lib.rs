@@ -26,6 +26,8 @@ pub fn test(
         #[test]
         #(#attrs)*
         fn #name() {
+            // The guard is taken on a new thread, which can't tell that this one runs a test.
+            seastar::assert_not_in_seastar_test();
             std::thread::spawn(|| {
                 let _guard = seastar::acquire_guard_for_seastar_test();
                 let mut app = seastar::AppTemplate::default();
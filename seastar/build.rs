@@ -13,6 +13,15 @@ static CXX_BRIDGES: &[&str] = &[
     "src/distributed.rs",
     "src/file.rs",
     "src/logger.rs",
+    "src/reactor.rs",
+    "src/iostream.rs",
+    "src/metrics.rs",
+    "src/scheduling_group.rs",
+    "src/semaphore.rs",
+    "src/queue.rs",
+    "src/memory.rs",
+    "src/thread.rs",
+    "src/net.rs",
 ];
 
 static CXX_CPP_SOURCES: &[&str] = &[
@@ -28,6 +37,15 @@ static CXX_CPP_SOURCES: &[&str] = &[
     "src/distributed.cc",
     "src/file.cc",
     "src/logger.cc",
+    "src/reactor.cc",
+    "src/iostream.cc",
+    "src/metrics.cc",
+    "src/scheduling_group.cc",
+    "src/semaphore.cc",
+    "src/queue.cc",
+    "src/memory.cc",
+    "src/thread.cc",
+    "src/net.cc",
 ];
 
 fn main() {
@@ -10,9 +10,45 @@ mod ffi {
 
 pub use ffi::engine_is_ready;
 
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static RUNTIME_IS_RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// Marks the Seastar runtime as running for as long as it's alive.
+/// Held by [`seastar::AppTemplate`] while it runs an app.
+pub(crate) struct RuntimeRunningGuard;
+
+impl RuntimeRunningGuard {
+    pub(crate) fn new() -> Self {
+        RUNTIME_IS_RUNNING.store(true, Ordering::Release);
+        RuntimeRunningGuard
+    }
+}
+
+impl Drop for RuntimeRunningGuard {
+    fn drop(&mut self) {
+        RUNTIME_IS_RUNNING.store(false, Ordering::Release);
+    }
+}
+
+/// Checks whether a Seastar runtime is running in the process, on any thread.
+pub fn runtime_is_running() -> bool {
+    RUNTIME_IS_RUNNING.load(Ordering::Acquire)
+}
+
+/// Checks whether the current thread is one of the reactor threads of a running Seastar runtime.
+///
+/// Unlike [`engine_is_ready`], it returns `false` on a thread whose runtime has already finished.
+/// Together with [`runtime_is_running`], it tells apart threads that may call reactor APIs
+/// from foreign threads (e.g. ones spawned with `std::thread`) which must not.
+pub fn is_reactor_thread() -> bool {
+    runtime_is_running() && engine_is_ready()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate as seastar;
     use crate::AppTemplate;
     use std::thread;
 
@@ -43,6 +79,26 @@ mod tests {
 
         assert!(!engine_is_ready());
     }
+
+    #[test]
+    fn test_is_reactor_thread_outside_runtime() {
+        thread::spawn(|| assert!(!is_reactor_thread()))
+            .join()
+            .unwrap();
+    }
+
+    #[seastar::test]
+    async fn test_is_reactor_thread_in_runtime() {
+        assert!(is_reactor_thread());
+        assert!(runtime_is_running());
+
+        thread::spawn(|| {
+            assert!(runtime_is_running());
+            assert!(!is_reactor_thread());
+        })
+        .join()
+        .unwrap();
+    }
 }
 
 /// Intended to be used in a runtime-dependent function.
@@ -0,0 +1,147 @@
+use crate::{need_preempt, Clock, Duration, Instant, Logger, SteadyClock};
+
+/// A debugging aid warning when a code region holds the reactor for too long.
+///
+/// Synchronous code never yields to the reactor, so blocking calls (e.g. ones ported from
+/// `std` code) stall all other tasks on the shard. A guard measures the time from its creation
+/// until it's dropped, and emits a `warn` level message with the given logger if it exceeded
+/// the threshold. The message also tells whether the task exhausted its time quota
+/// (see [`need_preempt`]).
+///
+/// See [`assert_non_blocking!`](crate::assert_non_blocking!) for a more convenient way to use it.
+///
+/// # Examples
+///
+/// ```rust
+/// use seastar::{BlockingGuard, Duration, Logger};
+///
+/// fn parse(logger: &Logger, input: &str) -> usize {
+///     let _guard = BlockingGuard::new("parse", Duration::from_millis(1), logger);
+///     input.split_whitespace().count()
+/// }
+/// ```
+pub struct BlockingGuard<'a> {
+    region: &'a str,
+    threshold: Duration<SteadyClock>,
+    start: Instant<SteadyClock>,
+    logger: &'a Logger,
+}
+
+impl<'a> BlockingGuard<'a> {
+    /// Starts measuring the time a code region named `region` holds the reactor for.
+    pub fn new(region: &'a str, threshold: Duration<SteadyClock>, logger: &'a Logger) -> Self {
+        BlockingGuard {
+            region,
+            threshold,
+            start: SteadyClock::now(),
+            logger,
+        }
+    }
+
+    /// Returns the time elapsed since the guard was created.
+    pub fn elapsed(&self) -> Duration<SteadyClock> {
+        SteadyClock::now() - self.start
+    }
+}
+
+impl Drop for BlockingGuard<'_> {
+    fn drop(&mut self) {
+        let elapsed = self.elapsed();
+        if elapsed > self.threshold {
+            let region = self.region;
+            let elapsed = elapsed.as_micros();
+            let threshold = self.threshold.as_micros();
+            let quota = if need_preempt() {
+                "exceeded"
+            } else {
+                "not exceeded"
+            };
+            crate::warn!(
+                self.logger,
+                "{} held the reactor for {}us without yielding (threshold: {}us, task quota {})",
+                region,
+                elapsed,
+                threshold,
+                quota
+            );
+        }
+    }
+}
+
+/// Warns if a block of code holds the reactor for longer than a threshold.
+///
+/// Wraps the block in a [`BlockingGuard`] named after the location of the macro call.
+/// Only checked when debug assertions are enabled - otherwise the block is just run.
+/// Evaluates to the value of the block.
+///
+/// # Examples
+///
+/// ```rust
+/// use seastar::{Duration, Logger};
+///
+/// fn checksum(logger: &Logger, data: &[u8]) -> u32 {
+///     seastar::assert_non_blocking!(logger, Duration::from_millis(1), {
+///         data.iter().map(|&b| b as u32).sum()
+///     })
+/// }
+/// ```
+#[macro_export]
+macro_rules! assert_non_blocking {
+    ($logger:expr, $threshold:expr, $body:block) => {{
+        #[cfg(debug_assertions)]
+        let _guard = $crate::BlockingGuard::new(
+            std::concat!(std::file!(), ":", std::line!()),
+            $threshold,
+            &$logger,
+        );
+        $body
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as seastar;
+
+    fn busy_loop(duration: std::time::Duration) {
+        let start = std::time::Instant::now();
+        while start.elapsed() < duration {
+            std::hint::spin_loop();
+        }
+    }
+
+    #[seastar::test]
+    async fn test_blocking_guard_warns_on_long_region() {
+        let (logger, sink) = Logger::with_test_sink();
+        {
+            let _guard = BlockingGuard::new("busy loop", Duration::from_millis(1), &logger);
+            busy_loop(std::time::Duration::from_millis(5));
+        }
+        let lines = sink.lines();
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].starts_with("busy loop held the reactor for"));
+    }
+
+    #[seastar::test]
+    async fn test_blocking_guard_quiet_on_short_region() {
+        let (logger, sink) = Logger::with_test_sink();
+        {
+            let _guard = BlockingGuard::new("short", Duration::from_secs(1), &logger);
+        }
+        assert!(sink.lines().is_empty());
+    }
+
+    #[cfg(debug_assertions)]
+    #[seastar::test]
+    async fn test_assert_non_blocking() {
+        let (logger, sink) = Logger::with_test_sink();
+        let ret = crate::assert_non_blocking!(logger, Duration::from_millis(1), {
+            busy_loop(std::time::Duration::from_millis(5));
+            42
+        });
+        assert_eq!(ret, 42);
+        let lines = sink.lines();
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].starts_with(file!()));
+    }
+}
@@ -6,6 +6,7 @@ namespace clocks {
 using sc = seastar::steady_clock_type;
 using lc = seastar::lowres_clock;
 using mc = seastar::manual_clock;
+using sys = std::chrono::system_clock;
 
 using std::chrono::duration_cast;
 
@@ -34,6 +35,11 @@ void manual_clock_advance(int64_t duration) {
     mc::advance(to_mc_duration(duration));
 }
 
+int64_t system_clock_now() {
+    sys::duration d = sys::now().time_since_epoch();
+    return to_nanos(d).count();
+}
+
 sc::duration to_sc_duration(int64_t duration) {
     return duration_cast<sc::duration>(nanos(duration));
 }
@@ -14,6 +14,8 @@ int64_t manual_clock_now();
 
 void manual_clock_advance(int64_t duration);
 
+int64_t system_clock_now();
+
 using nanos = std::chrono::nanoseconds;
 
 template<typename Duration>
@@ -24,6 +24,8 @@ mod ffi {
         fn manual_clock_now() -> i64;
 
         fn manual_clock_advance(duration: i64);
+
+        fn system_clock_now() -> i64;
     }
 
     #[namespace = "seastar_ffi::sleep"]
@@ -120,6 +122,34 @@ mod ffi {
 
         fn mct_get_timeout(timer: &manual_clock_timer) -> i64;
     }
+
+    #[namespace = "seastar_ffi::timer::system_clock"]
+    unsafe extern "C++" {
+        include!("seastar/src/timer.hh");
+
+        type system_clock_timer;
+
+        fn new_sysct() -> UniquePtr<system_clock_timer>;
+
+        unsafe fn sysct_set_callback(
+            timer: Pin<&mut system_clock_timer>,
+            callback: *mut u8, // u8 is a substitute for c_void that isn't supported by cxx.
+            caller: unsafe fn(*mut u8),
+            dropper: unsafe fn(*mut u8),
+        );
+
+        fn sysct_arm_at(timer: Pin<&mut system_clock_timer>, at: i64);
+        fn sysct_arm_at_periodic(timer: Pin<&mut system_clock_timer>, at: i64, period: i64);
+
+        fn sysct_rearm_at(timer: Pin<&mut system_clock_timer>, at: i64);
+        fn sysct_rearm_at_periodic(timer: Pin<&mut system_clock_timer>, at: i64, period: i64);
+
+        fn sysct_armed(timer: &system_clock_timer) -> bool;
+
+        fn sysct_cancel(timer: Pin<&mut system_clock_timer>) -> bool;
+
+        fn sysct_get_timeout(timer: &system_clock_timer) -> i64;
+    }
 }
 
 use ffi::*;
@@ -185,6 +215,21 @@ impl<ClockType> fmt::Debug for Duration<ClockType> {
     }
 }
 
+/// Serializes the duration as the number of nanoseconds.
+#[cfg(feature = "serde")]
+impl<ClockType> serde::Serialize for Duration<ClockType> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i64(self.nanos)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, ClockType> serde::Deserialize<'de> for Duration<ClockType> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        <i64 as serde::Deserialize>::deserialize(deserializer).map(Self::from_nanos)
+    }
+}
+
 impl<ClockType> Duration<ClockType> {
     pub const MAX: Self = Self::from_nanos(i64::MAX);
     pub const MIN: Self = Self::from_nanos(i64::MIN);
@@ -288,6 +333,24 @@ impl<ClockType> Duration<ClockType> {
             None => None,
         }
     }
+
+    /// Saturating duration addition. Computes `self + rhs`,
+    /// returning [`Duration::MAX`] or [`Duration::MIN`] if overflow occurred.
+    pub const fn saturating_add(self, rhs: Self) -> Self {
+        Self::from_nanos(self.nanos.saturating_add(rhs.nanos))
+    }
+
+    /// Saturating duration substraction. Computes `self - rhs`,
+    /// returning [`Duration::MAX`] or [`Duration::MIN`] if overflow occurred.
+    pub const fn saturating_sub(self, rhs: Self) -> Self {
+        Self::from_nanos(self.nanos.saturating_sub(rhs.nanos))
+    }
+
+    /// Saturating duration multiplication. Computes `self * rhs`,
+    /// returning [`Duration::MAX`] or [`Duration::MIN`] if overflow occurred.
+    pub const fn saturating_mul(self, rhs: i64) -> Self {
+        Self::from_nanos(self.nanos.saturating_mul(rhs))
+    }
 }
 
 impl<ClockType> Add for Duration<ClockType> {
@@ -446,6 +509,21 @@ impl<ClockType> fmt::Debug for Instant<ClockType> {
     }
 }
 
+/// Serializes the instant as the number of nanoseconds since the `ClockType`'s epoch.
+#[cfg(feature = "serde")]
+impl<ClockType> serde::Serialize for Instant<ClockType> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i64(self.nanos)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, ClockType> serde::Deserialize<'de> for Instant<ClockType> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        <i64 as serde::Deserialize>::deserialize(deserializer).map(Self::new)
+    }
+}
+
 impl<ClockType> Instant<ClockType> {
     pub(crate) const fn new(nanos: i64) -> Self {
         Self {
@@ -472,6 +550,16 @@ impl<ClockType> Instant<ClockType> {
         }
     }
 
+    /// Returns `self + duration`, clamped to the bounds of the underlying data structure.
+    pub const fn saturating_add(&self, duration: Duration<ClockType>) -> Self {
+        Self::new(self.nanos.saturating_add(duration.nanos))
+    }
+
+    /// Returns `self - duration`, clamped to the bounds of the underlying data structure.
+    pub const fn saturating_sub(&self, duration: Duration<ClockType>) -> Self {
+        Self::new(self.nanos.saturating_sub(duration.nanos))
+    }
+
     /// Returns the amount of time elapsed from another instant to this one.
     /// If `other` is later that `&self` the returned value is negative.
     ///
@@ -551,6 +639,60 @@ impl<ClockType> Sub for Instant<ClockType> {
     }
 }
 
+// Converts `instant` to the `To` clock, keeping its offset from both clocks' current time.
+fn convert_instant<From: Clock, To: Clock>(instant: Instant<From>) -> Instant<To> {
+    let offset = instant - From::now();
+    To::now() + Duration::from_nanos(offset.nanos)
+}
+
+impl Instant<SteadyClock> {
+    /// Converts the instant to a [`LowresClock`] one, keeping its distance from the present.
+    ///
+    /// The clocks have different epochs, so the conversion is computed by offsetting from
+    /// each clock's [`Clock::now`]. Thus the result is approximate: it's off by up to
+    /// the resolution of [`LowresClock`] (~10 ms) plus the time between reading both clocks.
+    pub fn to_lowres(&self) -> Instant<LowresClock> {
+        convert_instant(*self)
+    }
+}
+
+impl Instant<LowresClock> {
+    /// Converts the instant to a [`SteadyClock`] one, keeping its distance from the present.
+    ///
+    /// Approximate for the same reasons as `Instant<SteadyClock>::to_lowres`.
+    pub fn to_steady(&self) -> Instant<SteadyClock> {
+        convert_instant(*self)
+    }
+}
+
+/// [`SystemClock`]'s epoch is the Unix epoch, so the conversion is exact.
+impl From<Instant<SystemClock>> for std::time::SystemTime {
+    fn from(instant: Instant<SystemClock>) -> Self {
+        let nanos = instant.nanos;
+        let since_epoch = std::time::Duration::from_nanos(nanos.unsigned_abs());
+        if nanos >= 0 {
+            std::time::UNIX_EPOCH + since_epoch
+        } else {
+            std::time::UNIX_EPOCH - since_epoch
+        }
+    }
+}
+
+impl Instant<SystemClock> {
+    /// Converts a [`std::time::SystemTime`] to an instant. The inverse of the
+    /// `From<Instant<SystemClock>>` conversion for [`std::time::SystemTime`].
+    ///
+    /// Returns `None` if `time` is further than ~292 years from the Unix epoch,
+    /// which can't be represented by the underlying data structure.
+    pub fn from_system_time(time: std::time::SystemTime) -> Option<Self> {
+        let nanos = match time.duration_since(std::time::UNIX_EPOCH) {
+            Ok(since_epoch) => i64::try_from(since_epoch.as_nanos()).ok()?,
+            Err(e) => i64::try_from(e.duration().as_nanos()).ok()?.checked_neg()?,
+        };
+        Some(Self::new(nanos))
+    }
+}
+
 mod clock_implementation {
     use super::*;
 
@@ -645,10 +787,17 @@ macro_rules! timer_impl {
     };
 }
 
-/// Trait implemented by: [`SteadyClock`], [`LowresClock`], [`ManualClock`].
+/// Trait implemented by: [`SteadyClock`], [`LowresClock`], [`ManualClock`], [`SystemClock`].
 pub trait Clock: clock_implementation::ClockImpl {
     /// Returns an instant representing the current value of the clock.
     fn now() -> Instant<Self>;
+
+    /// Returns the time elapsed since the clock's epoch.
+    ///
+    /// Shorthand for `Self::now().duration_since_epoch()`.
+    fn now_since_epoch() -> Duration<Self> {
+        Self::now().duration_since_epoch()
+    }
 }
 
 /// Wrapper on `std::chrono::steady_clock`.
@@ -722,11 +871,65 @@ impl ManualClock {
     pub fn advance(duration: Duration<ManualClock>) {
         manual_clock_advance(duration.nanos);
     }
+
+    /// Advances `ManualClock` by `step` every `interval` of real time,
+    /// until the returned guard is dropped.
+    ///
+    /// Saves tests from interleaving [`ManualClock::advance`] with yields to the reactor by hand.
+    ///
+    /// This function must be called from the context of a Seastar runtime.
+    /// Available with the `test-util` feature.
+    #[cfg(any(test, feature = "test-util"))]
+    pub fn auto_advance(
+        step: Duration<ManualClock>,
+        interval: Duration<SteadyClock>,
+    ) -> AutoAdvanceGuard {
+        let task = crate::spawn_periodic(interval, move || async move {
+            ManualClock::advance(step);
+        });
+        AutoAdvanceGuard { _task: task }
+    }
+}
+
+/// A guard returned by [`ManualClock::auto_advance`], stopping the advancing when dropped.
+#[cfg(any(test, feature = "test-util"))]
+pub struct AutoAdvanceGuard {
+    _task: crate::PeriodicTask,
+}
+
+/// Wall clock, i.e. the system-wide real time clock.
+///
+/// Wrapper on `std::chrono::system_clock`. Its epoch is the Unix epoch, so instants
+/// of this clock can be correlated with timestamps coming from external systems.
+///
+/// Unlike the other clocks, `SystemClock` is not monotonic: it can jump backwards
+/// or forwards when the system time is adjusted. Use [`SteadyClock`] to measure
+/// time intervals.
+///
+/// Seastar can't run timers on the system clock, so `Timer<SystemClock>` is a steady clock
+/// timer armed at the steady clock's counterpart of the given instant at the moment of arming.
+/// Adjusting the system time afterwards doesn't move the expiration time. Likewise,
+/// sleeping with `SystemClock` is equivalent to sleeping with [`SteadyClock`].
+pub struct SystemClock;
+
+impl clock_implementation::ClockImpl for SystemClock {
+    fn sleep(nanos: i64) -> VoidFuture {
+        steady_sleep(nanos)
+    }
+
+    timer_impl!(system_clock_timer, sysct);
+}
+
+impl Clock for SystemClock {
+    fn now() -> Instant<SystemClock> {
+        Instant::new(system_clock_now())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate as seastar;
     use std::panic::catch_unwind;
 
     #[test]
@@ -736,6 +939,70 @@ mod tests {
         let _ = LowresClock::now();
         let _ = ManualClock::now();
         ManualClock::advance(Duration::from_nanos(1000));
+        let _ = SystemClock::now();
+    }
+
+    #[test]
+    fn test_system_clock_is_wall_clock() {
+        let before = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap();
+        let now = SystemClock::now_since_epoch();
+        let after = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap();
+        assert!(before.as_nanos() as i64 <= now.as_nanos());
+        assert!(now.as_nanos() <= after.as_nanos() as i64);
+    }
+
+    #[test]
+    fn test_system_clock_instant_into_system_time() {
+        let now: std::time::SystemTime = SystemClock::now().into();
+        let diff = match std::time::SystemTime::now().duration_since(now) {
+            Ok(diff) => diff,
+            Err(e) => e.duration(),
+        };
+        assert!(diff < std::time::Duration::from_secs(1));
+
+        let before_epoch = Instant::<SystemClock>::new(-1_500_000_000);
+        assert_eq!(
+            std::time::SystemTime::from(before_epoch),
+            std::time::UNIX_EPOCH - std::time::Duration::from_millis(1500)
+        );
+    }
+
+    #[test]
+    fn test_system_time_round_trip() {
+        // 2022-10-16T12:00:00.123456789Z
+        let time = std::time::UNIX_EPOCH + std::time::Duration::new(1_665_921_600, 123_456_789);
+        let instant = Instant::<SystemClock>::from_system_time(time).unwrap();
+        assert_eq!(
+            instant.duration_since_epoch().as_nanos(),
+            1_665_921_600_123_456_789
+        );
+        assert_eq!(std::time::SystemTime::from(instant), time);
+
+        let before_epoch = std::time::UNIX_EPOCH - std::time::Duration::from_secs(86400);
+        let instant = Instant::<SystemClock>::from_system_time(before_epoch).unwrap();
+        assert_eq!(
+            instant.duration_since_epoch().as_nanos(),
+            -86_400_000_000_000
+        );
+        assert_eq!(std::time::SystemTime::from(instant), before_epoch);
+
+        let far_future = std::time::UNIX_EPOCH + std::time::Duration::from_secs(300 * 366 * 86400);
+        assert!(Instant::<SystemClock>::from_system_time(far_future).is_none());
+    }
+
+    #[seastar::test]
+    async fn test_instant_steady_lowres_round_trip() {
+        let lowres_granularity = Duration::<SteadyClock>::from_millis(10);
+        let instant = SteadyClock::now() + Duration::from_secs(1);
+        let round_trip = instant.to_lowres().to_steady();
+        assert!((round_trip - instant).as_nanos().abs() <= lowres_granularity.as_nanos());
+
+        let lowres = instant.to_lowres();
+        assert!(lowres - LowresClock::now() <= Duration::from_secs(1));
     }
 
     // Tests below test only `Instant<SteadyClock>` and `Duration<SteadyClock>`.
@@ -767,6 +1034,21 @@ mod tests {
         assert!(i2.checked_sub(d2).is_none()); // -2 - i64::MAX (overflow)
     }
 
+    #[test]
+    fn test_instant_saturating_operations() {
+        let (i1, i2, i3) = get_instants(); // (2, -2, i64::MAX)
+        let d1 = Duration::from_nanos(2);
+        let d2 = Duration::from_nanos(i64::MAX);
+
+        assert_eq!(4, i1.saturating_add(d1).nanos);
+        assert_eq!(i64::MAX, i1.saturating_add(d2).nanos); // 2 + i64::MAX (overflow)
+        assert_eq!(i64::MAX, i3.saturating_add(d1).nanos); // i64::MAX + 2 (overflow)
+
+        assert_eq!(-4, i2.saturating_sub(d1).nanos);
+        assert_eq!(i64::MIN, i2.saturating_sub(d2).nanos); // -2 - i64::MAX (overflow)
+        assert_eq!(i64::MIN, i2.saturating_sub(d2).saturating_sub(d1).nanos);
+    }
+
     #[test]
     fn test_instant_since_methods() {
         let (i1, i2, i3) = get_instants(); // (2, -2, i64::MAX)
@@ -885,6 +1167,23 @@ mod tests {
         assert!(d1.checked_div(0).is_none()); // division by 0
     }
 
+    #[test]
+    fn test_duration_saturating_operations() {
+        let (d1, d2, d3) = get_durations(); // (1, -2, i64::MAX)
+
+        assert_eq!(-1, d1.saturating_add(d2).nanos);
+        assert_eq!(Duration::MAX, d1.saturating_add(d3)); // 1 + i64::MAX (overflow)
+        assert_eq!(Duration::MIN, Duration::MIN.saturating_add(d2)); // i64::MIN - 2 (overflow)
+
+        assert_eq!(3, d1.saturating_sub(d2).nanos);
+        assert_eq!(Duration::MIN, d2.saturating_sub(d3)); // -2 - i64::MAX (overflow)
+        assert_eq!(Duration::MAX, d3.saturating_sub(d2)); // i64::MAX + 2 (overflow)
+
+        assert_eq!(2, d1.saturating_mul(2).nanos);
+        assert_eq!(Duration::MAX, d3.saturating_mul(2)); // i64::MAX * 2 (overflow)
+        assert_eq!(Duration::MIN, d3.saturating_mul(-2)); // i64::MAX * -2 (overflow)
+    }
+
     #[test]
     fn test_duration_arithmetical_add() {
         let (d1, d2, d3) = get_durations(); // (1, -2, i64::MAX)
@@ -967,4 +1266,24 @@ mod tests {
         let d = Duration::<SteadyClock>::MIN;
         assert!(catch_unwind(|| -d).is_err()); // -i64::MIN == i64::MAX + 1 (overflow)
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let d = Duration::<SteadyClock>::from_nanos(-42);
+        let json = serde_json::to_string(&d).unwrap();
+        assert_eq!(json, "-42");
+        assert_eq!(
+            serde_json::from_str::<Duration<SteadyClock>>(&json).unwrap(),
+            d
+        );
+
+        let i = Instant::<LowresClock>::new(1_000_000_007);
+        let json = serde_json::to_string(&i).unwrap();
+        assert_eq!(json, "1000000007");
+        assert_eq!(
+            serde_json::from_str::<Instant<LowresClock>>(&json).unwrap(),
+            i
+        );
+    }
 }
@@ -1,4 +1,5 @@
 #include "config_and_start_seastar.hh"
+#include <seastar/core/prometheus.hh>
 
 namespace seastar_ffi {
 namespace config_and_start_seastar {
@@ -23,6 +24,26 @@ uint32_t get_smp(const seastar_options& opts) {
     }
 }
 
+uint32_t get_blocked_reactor_notify_ms(const seastar_options& opts) {
+    return (uint32_t)opts.reactor_opts.blocked_reactor_notify_ms.get_value();
+}
+
+double get_task_quota_ms(const seastar_options& opts) {
+    return opts.reactor_opts.task_quota_ms.get_value();
+}
+
+bool get_poll_mode(const seastar_options& opts) {
+    return opts.reactor_opts.poll_mode.defined();
+}
+
+uint32_t get_idle_poll_time_us(const seastar_options& opts) {
+    return (uint32_t)opts.reactor_opts.idle_poll_time_us.get_value();
+}
+
+bool get_unsafe_bypass_fsync(const seastar_options& opts) {
+    return opts.reactor_opts.unsafe_bypass_fsync.get_value();
+}
+
 void set_name(seastar_options& opts, const rust::Str name) {
     opts.name = seastar::sstring(name.begin(), name.size());
 }
@@ -35,6 +56,30 @@ void set_smp(seastar_options& opts, const uint32_t smp) {
     opts.smp_opts.smp.set_value((unsigned)smp);
 }
 
+void set_blocked_reactor_notify_ms(seastar_options& opts, const uint32_t ms) {
+    opts.reactor_opts.blocked_reactor_notify_ms.set_value((unsigned)ms);
+}
+
+void set_task_quota_ms(seastar_options& opts, const double ms) {
+    opts.reactor_opts.task_quota_ms.set_value(ms);
+}
+
+void set_poll_mode(seastar_options& opts, const bool poll_mode) {
+    if (poll_mode) {
+        opts.reactor_opts.poll_mode.set_value();
+    } else {
+        opts.reactor_opts.poll_mode.unset_value();
+    }
+}
+
+void set_idle_poll_time_us(seastar_options& opts, const uint32_t us) {
+    opts.reactor_opts.idle_poll_time_us.set_value((unsigned)us);
+}
+
+void set_unsafe_bypass_fsync(seastar_options& opts, const bool bypass) {
+    opts.reactor_opts.unsafe_bypass_fsync.set_value(bypass);
+}
+
 std::unique_ptr<app_template> new_app_template_from_options(seastar_options& opts) {
     return std::make_unique<app_template>(std::move(opts));
 }
@@ -53,5 +98,28 @@ int32_t run_int(app_template& app, int32_t argc, char** argv, IntFuture fut) {
     return exit_value;
 }
 
+VoidFuture start_prometheus_server(std::unique_ptr<http_server_control>& server, uint16_t port) {
+    auto new_server = std::make_unique<http_server_control>();
+    seastar::prometheus::config conf;
+    co_await new_server->start("prometheus");
+    // The server has to be stopped before it's destroyed, also when it fails to listen.
+    std::exception_ptr ex;
+    try {
+        co_await seastar::prometheus::start(*new_server, conf);
+        co_await new_server->listen(seastar::socket_address(seastar::ipv4_addr(port)));
+    } catch (...) {
+        ex = std::current_exception();
+    }
+    if (ex) {
+        co_await new_server->stop();
+        std::rethrow_exception(ex);
+    }
+    server = std::move(new_server);
+}
+
+VoidFuture stop_prometheus_server(const std::unique_ptr<http_server_control>& server) {
+    co_await server->stop();
+}
+
 } // namespace config_and_start_seastar
 } // namespace seastar
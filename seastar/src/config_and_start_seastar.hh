@@ -2,12 +2,14 @@
 
 #include "cxx_async_futures.hh"
 #include <seastar/core/app-template.hh>
+#include <seastar/http/httpd.hh>
 
 namespace seastar_ffi {
 namespace config_and_start_seastar {
 
 using app_template = seastar::app_template;
 using seastar_options = app_template::seastar_options;
+using http_server_control = seastar::httpd::http_server_control;
 
 using VoidFuture = seastar_ffi::VoidFuture;
 using IntFuture = seastar_ffi::IntFuture;
@@ -20,17 +22,41 @@ rust::Str get_description(const seastar_options& opts);
 
 uint32_t get_smp(const seastar_options& opts);
 
+uint32_t get_blocked_reactor_notify_ms(const seastar_options& opts);
+
+double get_task_quota_ms(const seastar_options& opts);
+
+bool get_poll_mode(const seastar_options& opts);
+
+uint32_t get_idle_poll_time_us(const seastar_options& opts);
+
+bool get_unsafe_bypass_fsync(const seastar_options& opts);
+
 void set_name(seastar_options& opts, const rust::Str name);
 
 void set_description(seastar_options& opts, const rust::Str description);
 
 void set_smp(seastar_options& opts, const uint32_t smp);
 
+void set_blocked_reactor_notify_ms(seastar_options& opts, const uint32_t ms);
+
+void set_task_quota_ms(seastar_options& opts, const double ms);
+
+void set_poll_mode(seastar_options& opts, const bool poll_mode);
+
+void set_idle_poll_time_us(seastar_options& opts, const uint32_t us);
+
+void set_unsafe_bypass_fsync(seastar_options& opts, const bool bypass);
+
 std::unique_ptr<app_template> new_app_template_from_options(seastar_options& opts);
 
 int32_t run_void(app_template& app, int argc, char** args, VoidFuture fut);
 
 int32_t run_int(app_template& app, int argc, char** args, IntFuture fut);
 
+VoidFuture start_prometheus_server(std::unique_ptr<http_server_control>& server, uint16_t port);
+
+VoidFuture stop_prometheus_server(const std::unique_ptr<http_server_control>& server);
+
 } // namespace config_and_start_seastar
 } // namespace seastar
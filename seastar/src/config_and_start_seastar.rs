@@ -1,4 +1,5 @@
 use std::{
+    cell::Cell,
     ffi::{c_char, CString, OsString},
     future::Future,
 };
@@ -7,6 +8,14 @@ use cxx::UniquePtr;
 use ffi::*;
 
 use crate::cxx_async_local_future::IntoCxxAsyncLocalFuture;
+use crate::{Duration, SteadyClock};
+use thiserror::Error;
+
+/// Error returned by [`AppTemplate::try_run_void`] and [`AppTemplate::try_run_int`]
+/// when an app has already been run in the current thread.
+#[derive(Error, Debug)]
+#[error("AlreadyRanError: a Seastar app has already been run in this thread")]
+pub struct AlreadyRanError;
 
 #[cxx::bridge]
 mod ffi {
@@ -22,6 +31,7 @@ mod ffi {
 
         type seastar_options;
         type app_template;
+        type http_server_control;
 
         // Returns a pointer to default `seastar_options`
         fn new_options() -> UniquePtr<seastar_options>;
@@ -29,10 +39,20 @@ mod ffi {
         fn get_name(opts: &seastar_options) -> &str;
         fn get_description(opts: &seastar_options) -> &str;
         fn get_smp(opts: &seastar_options) -> u32;
+        fn get_blocked_reactor_notify_ms(opts: &seastar_options) -> u32;
+        fn get_task_quota_ms(opts: &seastar_options) -> f64;
+        fn get_poll_mode(opts: &seastar_options) -> bool;
+        fn get_idle_poll_time_us(opts: &seastar_options) -> u32;
+        fn get_unsafe_bypass_fsync(opts: &seastar_options) -> bool;
         // Setters
         fn set_name(opts: Pin<&mut seastar_options>, name: &str);
         fn set_description(opts: Pin<&mut seastar_options>, description: &str);
         fn set_smp(opts: Pin<&mut seastar_options>, smp: u32);
+        fn set_blocked_reactor_notify_ms(opts: Pin<&mut seastar_options>, ms: u32);
+        fn set_task_quota_ms(opts: Pin<&mut seastar_options>, ms: f64);
+        fn set_poll_mode(opts: Pin<&mut seastar_options>, poll_mode: bool);
+        fn set_idle_poll_time_us(opts: Pin<&mut seastar_options>, us: u32);
+        fn set_unsafe_bypass_fsync(opts: Pin<&mut seastar_options>, bypass: bool);
 
         // Returns a pointer to an `app_template` instance
         fn new_app_template_from_options(
@@ -51,6 +71,13 @@ mod ffi {
             args: *mut *mut c_char,
             fut: IntFuture,
         ) -> i32;
+
+        // Starts and stops seastar's built-in Prometheus server
+        fn start_prometheus_server(
+            server: &mut UniquePtr<http_server_control>,
+            port: u16,
+        ) -> VoidFuture;
+        fn stop_prometheus_server(server: &UniquePtr<http_server_control>) -> VoidFuture;
     }
 }
 
@@ -58,6 +85,8 @@ mod ffi {
 /// Some of the options are just metadata, others affect the app's performance.
 pub struct Options {
     opts: UniquePtr<seastar_options>,
+    prometheus_port: Option<u16>,
+    raw_options: Vec<(String, String)>,
 }
 
 impl Options {
@@ -67,7 +96,8 @@ impl Options {
     ///
     /// - `name` - "App",
     /// - `description` - "" (empty),
-    /// - `smp` - number of threads/cores.
+    /// - `smp` - number of threads/cores,
+    /// - Prometheus server - disabled.
     ///
     /// # Examples
     ///
@@ -80,6 +110,8 @@ impl Options {
     pub fn new() -> Self {
         Options {
             opts: new_options(),
+            prometheus_port: None,
+            raw_options: Vec::new(),
         }
     }
 
@@ -178,6 +210,278 @@ impl Options {
     pub fn set_smp(&mut self, smp: u32) {
         set_smp(self.opts.pin_mut(), smp);
     }
+
+    /// Gets the threshold above which a task running without yielding to the reactor
+    /// is reported as a reactor stall.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use seastar::Options;
+    ///
+    /// let opts = Options::new();
+    ///
+    /// assert!(opts.get_blocked_reactor_notify().as_millis() > 0);
+    /// ```
+    pub fn get_blocked_reactor_notify(&self) -> Duration<SteadyClock> {
+        Duration::from_nanos(get_blocked_reactor_notify_ms(&self.opts) as i64 * 1_000_000)
+    }
+
+    /// Sets the threshold above which a task running without yielding to the reactor
+    /// is reported as a reactor stall (with a backtrace, in Seastar's log).
+    ///
+    /// Stalls usually come from accidentally blocking calls in async code.
+    /// The threshold has a millisecond granularity - it is rounded down.
+    /// Corresponds to the `--blocked-reactor-notify-ms` command line option.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `threshold` is shorter than a millisecond or doesn't fit in `u32` milliseconds.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use seastar::{Duration, Options, SteadyClock};
+    ///
+    /// let mut opts = Options::new();
+    /// opts.set_blocked_reactor_notify(Duration::<SteadyClock>::from_millis(100));
+    ///
+    /// assert_eq!(opts.get_blocked_reactor_notify().as_millis(), 100);
+    /// ```
+    pub fn set_blocked_reactor_notify(&mut self, threshold: Duration<SteadyClock>) {
+        let ms = u32::try_from(threshold.as_millis())
+            .ok()
+            .filter(|&ms| ms > 0)
+            .expect("the blocked reactor notify threshold must be between 1ms and u32::MAX ms");
+        set_blocked_reactor_notify_ms(self.opts.pin_mut(), ms);
+    }
+
+    /// Effectively disables reactor stall reports, by setting their threshold
+    /// (see [`Options::set_blocked_reactor_notify`]) to the maximum value.
+    pub fn disable_blocked_reactor_notify(&mut self) {
+        set_blocked_reactor_notify_ms(self.opts.pin_mut(), u32::MAX);
+    }
+
+    /// Gets the task quota, i.e. the maximum time a task should run before yielding
+    /// (see [`need_preempt`](crate::need_preempt)).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use seastar::{Duration, Options};
+    ///
+    /// let opts = Options::new();
+    ///
+    /// assert_eq!(opts.get_task_quota(), Duration::from_micros(500));
+    /// ```
+    pub fn get_task_quota(&self) -> Duration<SteadyClock> {
+        Duration::from_nanos((get_task_quota_ms(&self.opts) * 1_000_000.0).round() as i64)
+    }
+
+    /// Sets the task quota, i.e. the maximum time a task should run before yielding
+    /// (see [`need_preempt`](crate::need_preempt)).
+    ///
+    /// A shorter quota makes the reactor switch between tasks more often, which lowers
+    /// latency at the cost of throughput. Corresponds to the `--task-quota-ms` command line option.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `quota` isn't positive.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use seastar::{Duration, Options, SteadyClock};
+    ///
+    /// let mut opts = Options::new();
+    /// opts.set_task_quota(Duration::<SteadyClock>::from_millis(1));
+    ///
+    /// assert_eq!(opts.get_task_quota().as_millis(), 1);
+    /// ```
+    pub fn set_task_quota(&mut self, quota: Duration<SteadyClock>) {
+        assert!(quota.as_nanos() > 0, "the task quota must be positive");
+        set_task_quota_ms(self.opts.pin_mut(), quota.as_nanos() as f64 / 1_000_000.0);
+    }
+
+    /// Checks whether the reactor busy-polls instead of sleeping when idle.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use seastar::Options;
+    ///
+    /// let opts = Options::new();
+    ///
+    /// assert!(!opts.get_poll_mode());
+    /// ```
+    pub fn get_poll_mode(&self) -> bool {
+        get_poll_mode(&self.opts)
+    }
+
+    /// Sets whether the reactor busy-polls instead of sleeping when idle.
+    ///
+    /// Poll mode lowers latency at the cost of keeping every core fully busy.
+    /// Corresponds to the `--poll-mode` command line option.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use seastar::Options;
+    ///
+    /// let mut opts = Options::new();
+    /// opts.set_poll_mode(true);
+    ///
+    /// assert!(opts.get_poll_mode());
+    /// ```
+    pub fn set_poll_mode(&mut self, poll_mode: bool) {
+        set_poll_mode(self.opts.pin_mut(), poll_mode);
+    }
+
+    /// Gets the time the reactor busy-polls for before going to sleep when idle.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use seastar::Options;
+    ///
+    /// let opts = Options::new();
+    ///
+    /// assert!(opts.get_idle_poll_time().as_micros() > 0);
+    /// ```
+    pub fn get_idle_poll_time(&self) -> Duration<SteadyClock> {
+        Duration::from_nanos(get_idle_poll_time_us(&self.opts) as i64 * 1_000)
+    }
+
+    /// Sets the time the reactor busy-polls for before going to sleep when idle.
+    ///
+    /// The time has a microsecond granularity - it is rounded down.
+    /// Corresponds to the `--idle-poll-time-us` command line option.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `time` is negative or doesn't fit in `u32` microseconds.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use seastar::{Duration, Options, SteadyClock};
+    ///
+    /// let mut opts = Options::new();
+    /// opts.set_idle_poll_time(Duration::<SteadyClock>::from_micros(100));
+    ///
+    /// assert_eq!(opts.get_idle_poll_time().as_micros(), 100);
+    /// ```
+    pub fn set_idle_poll_time(&mut self, time: Duration<SteadyClock>) {
+        let us = u32::try_from(time.as_micros())
+            .expect("the idle poll time must be between 0 and u32::MAX us");
+        set_idle_poll_time_us(self.opts.pin_mut(), us);
+    }
+
+    /// Checks whether flushing files skips syncing them to the storage.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use seastar::Options;
+    ///
+    /// let opts = Options::new();
+    ///
+    /// assert!(!opts.get_unsafe_bypass_fsync());
+    /// ```
+    pub fn get_unsafe_bypass_fsync(&self) -> bool {
+        get_unsafe_bypass_fsync(&self.opts)
+    }
+
+    /// Sets whether flushing files (e.g. with [`File::flush`](crate::File::flush))
+    /// skips syncing them to the storage.
+    ///
+    /// **Meant for tests only**, where it speeds up file operations a lot:
+    /// flushed data isn't guaranteed to survive a crash.
+    /// Corresponds to the `--unsafe-bypass-fsync` command line option.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use seastar::Options;
+    ///
+    /// let mut opts = Options::new();
+    /// opts.set_unsafe_bypass_fsync(true);
+    ///
+    /// assert!(opts.get_unsafe_bypass_fsync());
+    /// ```
+    pub fn set_unsafe_bypass_fsync(&mut self, bypass: bool) {
+        set_unsafe_bypass_fsync(self.opts.pin_mut(), bypass);
+    }
+
+    /// Gets the port on which the Prometheus server will listen, if it is enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use seastar::Options;
+    ///
+    /// let opts = Options::new();
+    ///
+    /// assert_eq!(opts.get_prometheus_port(), None);
+    /// ```
+    pub fn get_prometheus_port(&self) -> Option<u16> {
+        self.prometheus_port
+    }
+
+    /// Enables seastar's built-in Prometheus server.
+    ///
+    /// When the app runs, metrics are exposed under `/metrics` on the given `port`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use seastar::Options;
+    ///
+    /// let mut opts = Options::new();
+    /// opts.enable_prometheus(9180);
+    ///
+    /// assert_eq!(opts.get_prometheus_port(), Some(9180));
+    /// ```
+    pub fn enable_prometheus(&mut self, port: u16) {
+        self.prometheus_port = Some(port);
+    }
+
+    /// Disables seastar's built-in Prometheus server.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use seastar::Options;
+    ///
+    /// let mut opts = Options::new();
+    /// opts.enable_prometheus(9180);
+    /// opts.disable_prometheus();
+    ///
+    /// assert_eq!(opts.get_prometheus_port(), None);
+    /// ```
+    pub fn disable_prometheus(&mut self) {
+        self.prometheus_port = None;
+    }
+
+    /// Sets a Seastar option which doesn't have a dedicated setter.
+    ///
+    /// `key` is the name of a Seastar command line option without the leading dashes
+    /// (e.g. `"task-quota-ms"`), and `value` is its value. The option is forwarded as
+    /// `--key=value` to the app when it's run, after the program arguments,
+    /// so it's validated by Seastar at parse time - invalid options make the app exit with an error.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use seastar::{AppTemplate, Options};
+    ///
+    /// let mut opts = Options::new();
+    /// opts.set_raw("idle-poll-time-us", "100");
+    /// let app = AppTemplate::new_from_options(opts);
+    /// ```
+    pub fn set_raw(&mut self, key: &str, value: &str) {
+        self.raw_options.push((key.to_owned(), value.to_owned()));
+    }
 }
 
 impl Default for Options {
@@ -190,6 +494,8 @@ impl Default for Options {
 /// Configurable through [`Options`].
 pub struct AppTemplate {
     app: UniquePtr<app_template>,
+    prometheus_port: Option<u16>,
+    raw_options: Vec<(String, String)>,
 }
 
 impl AppTemplate {
@@ -205,12 +511,19 @@ impl AppTemplate {
     pub fn new_from_options(mut opts: Options) -> Self {
         AppTemplate {
             app: new_app_template_from_options(opts.opts.pin_mut()),
+            prometheus_port: opts.prometheus_port,
+            raw_options: opts.raw_options,
         }
     }
 
     /// Runs an app with a void callback (the output of which is always 0) and program arguments (argv).
     ///
     /// Currently, this function can only be called once in a single thread.
+    /// Use [`AppTemplate::try_run_void`] to handle that case gracefully.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an app has already been run in the current thread.
     ///
     /// # Examples
     ///
@@ -233,23 +546,62 @@ impl AppTemplate {
         I: IntoIterator<Item = Arg>,
         Arg: Into<OsString>,
     {
-        let args = get_c_args(args);
+        self.try_run_void(args, fut)
+            .unwrap_or_else(|_| panic!("{}", ALREADY_RAN_MESSAGE))
+    }
+
+    /// Runs an app with a void callback like [`AppTemplate::run_void`], unless an app
+    /// has already been run in the current thread.
+    ///
+    /// Seastar doesn't support running an app more than once in a single thread,
+    /// so every call after the first one returns [`AlreadyRanError`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use seastar::AppTemplate;
+    ///
+    /// let mut app = AppTemplate::default();
+    /// let args = vec!["hello"];
+    ///
+    /// assert_eq!(app.try_run_void(&args[..], async { Ok(()) }).unwrap(), 0);
+    /// assert!(app.try_run_void(&args[..], async { Ok(()) }).is_err());
+    /// ```
+    pub fn try_run_void<I, Arg>(
+        &mut self,
+        args: I,
+        fut: impl Future<Output = cxx_async::CxxAsyncResult<()>> + 'static,
+    ) -> Result<i32, AlreadyRanError>
+    where
+        I: IntoIterator<Item = Arg>,
+        Arg: Into<OsString>,
+    {
+        check_app_can_run()?;
+        crate::smp::reset_cached_count();
+        let fut = with_prometheus(self.prometheus_port, fut);
+        let args = get_c_args(args.into_iter().map(Into::into).chain(self.raw_args()));
         let argc = args.len() as i32;
         let mut args: Vec<_> = args.iter().map(|s| s.as_ptr() as *mut c_char).collect();
         args.push(std::ptr::null_mut());
-        unsafe {
+        let _running = crate::api_safety::RuntimeRunningGuard::new();
+        Ok(unsafe {
             run_void(
                 self.app.pin_mut(),
                 argc,
                 args.as_mut_ptr(),
                 VoidFuture::fallible_local(fut),
             )
-        }
+        })
     }
 
     /// Runs an app with an int (status code) callback and program arguments (argv).
     ///
     /// Currently, this function can only be called once in a single thread.
+    /// Use [`AppTemplate::try_run_int`] to handle that case gracefully.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an app has already been run in the current thread.
     ///
     /// # Examples
     ///
@@ -272,18 +624,46 @@ impl AppTemplate {
         I: IntoIterator<Item = Arg>,
         Arg: Into<OsString>,
     {
-        let args = get_c_args(args);
+        self.try_run_int(args, fut)
+            .unwrap_or_else(|_| panic!("{}", ALREADY_RAN_MESSAGE))
+    }
+
+    /// Runs an app with an int callback like [`AppTemplate::run_int`], unless an app
+    /// has already been run in the current thread.
+    ///
+    /// See [`AppTemplate::try_run_void`] for details.
+    pub fn try_run_int<I, Arg>(
+        &mut self,
+        args: I,
+        fut: impl Future<Output = cxx_async::CxxAsyncResult<i32>> + 'static,
+    ) -> Result<i32, AlreadyRanError>
+    where
+        I: IntoIterator<Item = Arg>,
+        Arg: Into<OsString>,
+    {
+        check_app_can_run()?;
+        crate::smp::reset_cached_count();
+        let fut = with_prometheus(self.prometheus_port, fut);
+        let args = get_c_args(args.into_iter().map(Into::into).chain(self.raw_args()));
         let argc = args.len() as i32;
         let mut args: Vec<_> = args.iter().map(|s| s.as_ptr() as *mut c_char).collect();
         args.push(std::ptr::null_mut());
-        unsafe {
+        let _running = crate::api_safety::RuntimeRunningGuard::new();
+        Ok(unsafe {
             run_int(
                 self.app.pin_mut(),
                 argc,
                 args.as_mut_ptr(),
                 IntFuture::fallible_local(fut),
             )
-        }
+        })
+    }
+
+    // Options set with `Options::set_raw`, in the command line format.
+    fn raw_args(&self) -> impl Iterator<Item = OsString> + '_ {
+        self.raw_options
+            .iter()
+            .map(|(key, value)| format!("--{key}={value}").into())
     }
 }
 
@@ -293,6 +673,47 @@ impl Default for AppTemplate {
     }
 }
 
+thread_local! {
+    static APP_ALREADY_RAN: Cell<bool> = Cell::new(false);
+}
+
+const ALREADY_RAN_MESSAGE: &str =
+    "Attempting to run a Seastar app in a thread that has already run one";
+
+// Seastar doesn't support running an app more than once in a single thread
+// and crashes without a meaningful message when it happens.
+fn check_app_can_run() -> Result<(), AlreadyRanError> {
+    if APP_ALREADY_RAN.with(|ran| ran.replace(true)) {
+        return Err(AlreadyRanError);
+    }
+    crate::assert_runtime_is_not_running();
+    Ok(())
+}
+
+// Runs `fut` with the Prometheus server listening on `port` (if it's set).
+//
+// The server is stopped however `fut` finishes, also when it panics. An error returned
+// by the app takes precedence over one from stopping the server.
+async fn with_prometheus<T>(
+    port: Option<u16>,
+    fut: impl Future<Output = cxx_async::CxxAsyncResult<T>>,
+) -> cxx_async::CxxAsyncResult<T> {
+    use futures::FutureExt;
+    use std::panic::{resume_unwind, AssertUnwindSafe};
+
+    let Some(port) = port else {
+        return fut.await;
+    };
+
+    let mut server = UniquePtr::null();
+    start_prometheus_server(&mut server, port).await?;
+    let ret = AssertUnwindSafe(fut).catch_unwind().await;
+    let stopped = stop_prometheus_server(&server).await;
+    let ret = ret.unwrap_or_else(|panic| resume_unwind(panic))?;
+    stopped?;
+    Ok(ret)
+}
+
 fn get_c_args<I, Arg>(iter: I) -> Vec<CString>
 where
     I: IntoIterator<Item = Arg>,
@@ -315,6 +736,11 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::sleep;
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+    use std::panic;
+    use std::sync::{Arc, Mutex};
     use std::thread;
 
     #[test]
@@ -349,6 +775,146 @@ mod tests {
         assert_eq!(opts.get_smp(), smp);
     }
 
+    #[test]
+    fn test_set_get_blocked_reactor_notify() {
+        let mut opts = Options::new();
+        opts.set_blocked_reactor_notify(Duration::from_millis(42));
+        assert_eq!(opts.get_blocked_reactor_notify(), Duration::from_millis(42));
+        opts.set_blocked_reactor_notify(Duration::from_micros(42_500));
+        assert_eq!(opts.get_blocked_reactor_notify(), Duration::from_millis(42));
+        opts.disable_blocked_reactor_notify();
+        assert_eq!(
+            opts.get_blocked_reactor_notify().as_millis(),
+            u32::MAX as i64
+        );
+    }
+
+    #[test]
+    fn test_set_get_task_quota() {
+        let mut opts = Options::new();
+        opts.set_task_quota(Duration::from_millis(1));
+        assert_eq!(opts.get_task_quota(), Duration::from_millis(1));
+        opts.set_task_quota(Duration::from_micros(250));
+        assert_eq!(opts.get_task_quota(), Duration::from_micros(250));
+    }
+
+    #[test]
+    fn test_set_get_poll_mode() {
+        let mut opts = Options::new();
+        assert!(!opts.get_poll_mode());
+        opts.set_poll_mode(true);
+        assert!(opts.get_poll_mode());
+        opts.set_poll_mode(false);
+        assert!(!opts.get_poll_mode());
+    }
+
+    #[test]
+    fn test_set_get_idle_poll_time() {
+        let mut opts = Options::new();
+        opts.set_idle_poll_time(Duration::from_micros(100));
+        assert_eq!(opts.get_idle_poll_time(), Duration::from_micros(100));
+    }
+
+    #[test]
+    fn test_run_with_poll_mode() {
+        thread::spawn(|| {
+            let _guard = crate::acquire_guard_for_seastar_test();
+            let mut opts = Options::default();
+            opts.set_poll_mode(true);
+            opts.set_idle_poll_time(Duration::from_micros(50));
+            let mut app = AppTemplate::new_from_options(opts);
+            let args = vec!["test"];
+            let fut = async {
+                sleep(Duration::<SteadyClock>::from_millis(1)).await;
+                Ok(())
+            };
+            assert_eq!(app.run_void(&args[..], fut), 0);
+        })
+        .join()
+        .unwrap();
+    }
+
+    #[test]
+    fn test_set_get_unsafe_bypass_fsync() {
+        let mut opts = Options::new();
+        assert!(!opts.get_unsafe_bypass_fsync());
+        opts.set_unsafe_bypass_fsync(true);
+        assert!(opts.get_unsafe_bypass_fsync());
+        opts.set_unsafe_bypass_fsync(false);
+        assert!(!opts.get_unsafe_bypass_fsync());
+    }
+
+    #[test]
+    fn test_run_with_unsafe_bypass_fsync() {
+        thread::spawn(|| {
+            let _guard = crate::acquire_guard_for_seastar_test();
+            let mut opts = Options::default();
+            opts.set_unsafe_bypass_fsync(true);
+            let mut app = AppTemplate::new_from_options(opts);
+            let args = vec!["test"];
+            let mut path = std::env::temp_dir();
+            path.push(format!("unsafe-bypass-fsync-{}", std::process::id()));
+            let path_clone = path.clone();
+            let fut = async move {
+                let file = crate::OpenOptions::new()
+                    .create(true)
+                    .write(true)
+                    .open(&path_clone)
+                    .await
+                    .unwrap();
+                let buffer = crate::DmaBuffer::from_slice(&[42u8; 4096]);
+                file.write_dma(buffer, 0).await.unwrap();
+                file.flush().await.unwrap();
+                file.close().await.unwrap();
+                Ok(())
+            };
+            assert_eq!(app.run_void(&args[..], fut), 0);
+            assert_eq!(std::fs::read(&path).unwrap(), [42u8; 4096]);
+            std::fs::remove_file(&path).unwrap();
+        })
+        .join()
+        .unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_set_blocked_reactor_notify_below_millisecond_panics() {
+        Options::new().set_blocked_reactor_notify(Duration::from_micros(500));
+    }
+
+    #[test]
+    fn test_run_with_blocked_reactor_notify() {
+        thread::spawn(|| {
+            let _guard = crate::acquire_guard_for_seastar_test();
+            let mut opts = Options::default();
+            opts.set_blocked_reactor_notify(Duration::from_millis(500));
+            let mut app = AppTemplate::new_from_options(opts);
+            let args = vec!["test"];
+            let fut = async {
+                sleep(Duration::<SteadyClock>::from_millis(1)).await;
+                Ok(())
+            };
+            assert_eq!(app.run_void(&args[..], fut), 0);
+        })
+        .join()
+        .unwrap();
+    }
+
+    #[test]
+    fn test_run_with_raw_option() {
+        thread::spawn(|| {
+            let _guard = crate::acquire_guard_for_seastar_test();
+            let mut opts = Options::default();
+            opts.set_raw("task-quota-ms", "1");
+            let mut app = AppTemplate::new_from_options(opts);
+            let args = vec!["test"];
+            let fut = async { Ok(()) };
+            assert_eq!(app.run_void(&args[..], fut), 0);
+        })
+        .join()
+        .unwrap();
+    }
+
     #[test]
     fn test_new_app_template_from_options_gets_created() {
         let mut opts = Options::default();
@@ -384,6 +950,90 @@ mod tests {
         .unwrap();
     }
 
+    #[test]
+    fn test_run_twice_panics() {
+        thread::spawn(|| {
+            let _guard = crate::acquire_guard_for_seastar_test();
+            let mut app = AppTemplate::default();
+            let args = vec!["test"];
+            assert_eq!(app.run_void(&args[..], async { Ok(()) }), 0);
+            let res = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+                app.run_void(&args[..], async { Ok(()) })
+            }));
+            assert!(res.is_err());
+        })
+        .join()
+        .unwrap();
+    }
+
+    #[test]
+    fn test_try_run_twice_returns_error() {
+        thread::spawn(|| {
+            let _guard = crate::acquire_guard_for_seastar_test();
+            let mut app = AppTemplate::default();
+            let args = vec!["test"];
+            assert_eq!(app.try_run_int(&args[..], async { Ok(7) }).unwrap(), 7);
+            assert!(app.try_run_int(&args[..], async { Ok(7) }).is_err());
+            // A fresh app template can't be run either.
+            let mut app = AppTemplate::default();
+            assert!(app.try_run_void(&args[..], async { Ok(()) }).is_err());
+        })
+        .join()
+        .unwrap();
+    }
+
+    #[test]
+    fn test_set_get_prometheus_port() {
+        let mut opts = Options::new();
+        assert_eq!(opts.get_prometheus_port(), None);
+        opts.enable_prometheus(42);
+        assert_eq!(opts.get_prometheus_port(), Some(42));
+        opts.disable_prometheus();
+        assert_eq!(opts.get_prometheus_port(), None);
+    }
+
+    // Finds a port nothing listens on, by letting the OS pick one.
+    fn free_port() -> u16 {
+        let listener = std::net::TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        listener.local_addr().unwrap().port()
+    }
+
+    #[test]
+    fn test_prometheus_endpoint() {
+        thread::spawn(|| {
+            let _guard = crate::acquire_guard_for_seastar_test();
+            let port = free_port();
+            let mut opts = Options::default();
+            opts.enable_prometheus(port);
+            let mut app = AppTemplate::new_from_options(opts);
+            let args = vec!["test"];
+            let fut = async move {
+                // The request is sent from a separate thread so that the reactor
+                // is free to serve it in the meantime.
+                let response: Arc<Mutex<Option<String>>> = Default::default();
+                let response_clone = response.clone();
+                let client = thread::spawn(move || {
+                    let mut stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+                    stream.write_all(b"GET /metrics HTTP/1.0\r\n\r\n").unwrap();
+                    let mut buf = String::new();
+                    stream.read_to_string(&mut buf).unwrap();
+                    *response_clone.lock().unwrap() = Some(buf);
+                });
+                while response.lock().unwrap().is_none() {
+                    sleep(Duration::<SteadyClock>::from_millis(10)).await;
+                }
+                client.join().unwrap();
+
+                let response = response.lock().unwrap().take().unwrap();
+                assert_eq!(response.split_whitespace().nth(1), Some("200"));
+                Ok(())
+            };
+            assert_eq!(app.run_void(&args[..], fut), 0);
+        })
+        .join()
+        .unwrap();
+    }
+
     // Note: this is not a test case that is supposed to be run. It is only
     // supposed to verify that run_void and run_int work with std::env::args().
     // and std::env::args_os().
@@ -15,6 +15,11 @@ rust_service::rust_service(
 }
 
 seastar::future<> rust_service::stop() {
+    if (_stopped) {
+        co_return;
+    }
+    _stopped = true;
+    co_await _gate.close();
     co_await _stop_caller(_inner);
 }
 
@@ -30,6 +35,11 @@ const uint8_t* local(const distributed& distr) {
     return distr.local()._inner;
 }
 
+std::unique_ptr<seastar_ffi::gate::gate_holder> enter_local(const distributed& distr) {
+    auto& service = const_cast<distributed&>(distr).local();
+    return std::make_unique<seastar_ffi::gate::gate_holder>(service._gate);
+}
+
 VoidFuture start(
     const distributed& distr,
     const uint8_t* raw_service_maker,
@@ -62,5 +72,20 @@ VoidFuture stop(const distributed& distr) {
     co_await const_cast<distributed&>(distr).stop();
 }
 
+static seastar::future<> stop_instance(rust_service& service) {
+    co_await service.stop();
+}
+
+VoidFuture stop_ordered(const distributed& distr, rust::Slice<const uint32_t> order) {
+    // Copied before the first suspension point, as the slice is owned by the caller.
+    std::vector<uint32_t> shards(order.begin(), order.end());
+    auto& d = const_cast<distributed&>(distr);
+    for (auto shard : shards) {
+        co_await d.invoke_on(shard, stop_instance);
+    }
+    // Stops the instances which weren't listed and frees all of them.
+    co_await d.stop();
+}
+
 } // namespace distributed
 } // namespace seastar_ffi
@@ -1,6 +1,7 @@
 #pragma once
 
 #include "cxx_async_futures.hh"
+#include "gate.hh"
 #include <seastar/core/distributed.hh>
 
 namespace seastar_ffi {
@@ -10,8 +11,11 @@ class rust_service {
 private:
     rust::Fn<VoidFuture(uint8_t*)> _stop_caller;
     rust::Fn<void(uint8_t*)> _dropper;
+    bool _stopped = false;
 public:
     uint8_t* _inner;
+    // Entered by maps running on the instance, so that stopping it waits for them.
+    seastar::gate _gate;
 
     rust_service(
         const uint8_t* raw_service_maker,
@@ -21,6 +25,7 @@ public:
     );
     ~rust_service();
 
+    // Stops the instance once, later calls are no-ops.
     seastar::future<> stop();
 };
 
@@ -30,6 +35,8 @@ std::shared_ptr<distributed> new_distributed();
 
 const uint8_t* local(const distributed& distr);
 
+std::unique_ptr<seastar_ffi::gate::gate_holder> enter_local(const distributed& distr);
+
 VoidFuture start(
     const distributed& distr,
     const uint8_t* raw_service_maker,
@@ -50,5 +57,7 @@ VoidFuture start_single(
 
 VoidFuture stop(const distributed &distr);
 
+VoidFuture stop_ordered(const distributed& distr, rust::Slice<const uint32_t> order);
+
 } // namespace distributed
 } // namespace seastar_ffi
@@ -3,15 +3,21 @@ use crate::{
     ffi_utils::{get_dropper_const, get_dropper_noarg, get_fn_caller, PtrWrapper},
     get_count, spawn,
     submit_to::submit_to,
-    this_shard_id,
+    this_shard_id, Semaphore,
 };
 use core::marker::PhantomData;
-use cxx::SharedPtr;
+use cxx::{SharedPtr, UniquePtr};
+use futures::future::{join_all, ready};
+use std::ops::{Deref, DerefMut, Range};
 use std::pin::Pin;
 use std::{
     future::Future,
-    sync::{Arc, RwLock},
+    sync::{
+        atomic::{AtomicBool, AtomicIsize, Ordering},
+        Arc,
+    },
 };
+use thiserror::Error;
 
 #[cxx::bridge(namespace = "seastar_ffi::distributed")]
 mod ffi {
@@ -23,10 +29,15 @@ mod ffi {
         #[namespace = "seastar_ffi"]
         type VoidFuture = crate::cxx_async_futures::VoidFuture;
 
+        #[namespace = "seastar_ffi::gate"]
+        type gate_holder = crate::gate::ffi::gate_holder;
+
         fn new_distributed() -> SharedPtr<distributed>;
 
         fn local(distr: &distributed) -> *const u8;
 
+        fn enter_local(distr: &distributed) -> Result<UniquePtr<gate_holder>>;
+
         unsafe fn start(
             distr: &distributed,
             raw_service_maker: *const u8,
@@ -46,6 +57,8 @@ mod ffi {
         ) -> VoidFuture;
 
         fn stop(distr: &distributed) -> VoidFuture;
+
+        fn stop_ordered(distr: &distributed, order: &[u32]) -> VoidFuture;
     }
 }
 
@@ -60,11 +73,92 @@ const fn get_stop_caller<S: Service>() -> fn(*mut u8) -> VoidFuture {
     stop_caller::<S>
 }
 
-use ffi::{distributed, VoidFuture};
+use ffi::{distributed, gate_holder, VoidFuture};
+
+// Enters the gate of the instance on the current shard.
+// Stopping the instance waits until the returned holder is dropped.
+fn enter_local(distr: &SharedPtr<distributed>) -> UniquePtr<gate_holder> {
+    match ffi::enter_local(distr.as_ref().unwrap()) {
+        Ok(holder) => holder,
+        Err(_) => panic!("instance {} already stopped", this_shard_id()),
+    }
+}
 
 unsafe impl Send for distributed {}
 unsafe impl Sync for distributed {}
 
+/// Error returned by the `try_map_*` functions of [`Distributed`] when the instance
+/// is already borrowed in a conflicting way.
+#[derive(Error, Debug)]
+#[error("BorrowError: instance {shard_id} already borrowed")]
+pub struct BorrowError {
+    shard_id: u32,
+}
+
+impl BorrowError {
+    /// Returns the id of the shard whose instance was already borrowed.
+    pub fn shard_id(&self) -> u32 {
+        self.shard_id
+    }
+}
+
+// Tracks the borrows of a single instance, much like `RefCell` does.
+// A positive value is the number of shared borrows, -1 marks a mutable borrow.
+#[derive(Default)]
+struct BorrowFlag(AtomicIsize);
+
+// Releases a borrow of an instance when dropped.
+// Unlike lock guards, it can be sent to the shard of the instance.
+struct BorrowGuard {
+    flag: Arc<BorrowFlag>,
+    mutable: bool,
+}
+
+impl Drop for BorrowGuard {
+    fn drop(&mut self) {
+        if self.mutable {
+            self.flag.0.store(0, Ordering::Release);
+        } else {
+            self.flag.0.fetch_sub(1, Ordering::Release);
+        }
+    }
+}
+
+impl BorrowFlag {
+    fn try_borrow(self: &Arc<Self>) -> Option<BorrowGuard> {
+        let mut borrows = self.0.load(Ordering::Acquire);
+        loop {
+            if borrows < 0 {
+                return None;
+            }
+            match self.0.compare_exchange_weak(
+                borrows,
+                borrows + 1,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => {
+                    return Some(BorrowGuard {
+                        flag: self.clone(),
+                        mutable: false,
+                    })
+                }
+                Err(current) => borrows = current,
+            }
+        }
+    }
+
+    fn try_borrow_mut(self: &Arc<Self>) -> Option<BorrowGuard> {
+        self.0
+            .compare_exchange(0, -1, Ordering::AcqRel, Ordering::Acquire)
+            .ok()
+            .map(|_| BorrowGuard {
+                flag: self.clone(),
+                mutable: true,
+            })
+    }
+}
+
 /// A trait which a service inside `Distributed` must implement.
 ///
 /// Because of Rust not yet supporting `async` trait methods,
@@ -86,6 +180,16 @@ unsafe impl Sync for distributed {}
 /// }
 /// ```
 pub trait Service {
+    /// The place to define what (possibly asynchronous) initialization must be done for the service,
+    /// e.g. opening files or binding sockets.
+    ///
+    /// `Distributed` awaits it on each instance right after constructing it, before any map can run on it.
+    ///
+    /// If not implemented, defaults to a no-op.
+    fn start(&mut self) -> Box<dyn Future<Output = ()> + '_> {
+        Box::new(async {})
+    }
+
     /// The place to define what (possibly asynchronous) cleanup must be done for the service.
     ///
     /// If not implemented, defaults to a no-op.
@@ -104,14 +208,84 @@ where
 {
     pub instance: &'a S,
     pub container: &'a mut Distributed<S>,
+    /// The id of the shard the instance lives on, i.e. the one the function runs on.
+    pub shard_id: u32,
 }
 
+/// An object on which `Distributed`'s mutating mapping functions operate.
+///
+/// Like [`PeeringShardedService`], but gives exclusive access to the local instance,
+/// so it can be mutated directly, without interior mutability (e.g. `RefCell`) in the service.
+/// Exclusivity is checked at runtime - see the `# Panics` section of [`Distributed`].
 pub struct PeeringShardedServiceMut<'a, S>
 where
     S: Service,
 {
     pub instance: &'a mut S,
     pub container: &'a mut Distributed<S>,
+    /// The id of the shard the instance lives on, i.e. the one the function runs on.
+    pub shard_id: u32,
+}
+
+/// An owning handle to the local instance of a service, passed to `Distributed::map_single_owned`.
+///
+/// Unlike [`PeeringShardedService`], it isn't tied to a borrow of the `Distributed`,
+/// so the future returned by the map function can hold it across await points.
+/// The instance can't be stopped or mutably borrowed while the handle is alive.
+pub struct OwnedPeeringService<S: Service> {
+    instance: *const S,
+    shard_id: u32,
+    _holder: UniquePtr<gate_holder>,
+    _guard: BorrowGuard,
+}
+
+impl<S: Service> OwnedPeeringService<S> {
+    /// Returns the id of the shard the instance lives on, i.e. the one the function runs on.
+    pub fn shard_id(&self) -> u32 {
+        self.shard_id
+    }
+}
+
+impl<S: Service> Deref for OwnedPeeringService<S> {
+    type Target = S;
+
+    fn deref(&self) -> &S {
+        // The gate holder keeps the instance alive and the guard keeps it from being mutated.
+        unsafe { &*self.instance }
+    }
+}
+
+/// An owning handle giving exclusive access to the local instance of a service,
+/// passed to `Distributed::map_single_mut_owned`.
+///
+/// Like [`OwnedPeeringService`], but the instance can be mutated.
+pub struct OwnedPeeringServiceMut<S: Service> {
+    instance: *mut S,
+    shard_id: u32,
+    _holder: UniquePtr<gate_holder>,
+    _guard: BorrowGuard,
+}
+
+impl<S: Service> OwnedPeeringServiceMut<S> {
+    /// Returns the id of the shard the instance lives on, i.e. the one the function runs on.
+    pub fn shard_id(&self) -> u32 {
+        self.shard_id
+    }
+}
+
+impl<S: Service> Deref for OwnedPeeringServiceMut<S> {
+    type Target = S;
+
+    fn deref(&self) -> &S {
+        unsafe { &*self.instance }
+    }
+}
+
+impl<S: Service> DerefMut for OwnedPeeringServiceMut<S> {
+    fn deref_mut(&mut self) -> &mut S {
+        // The guard is the only mutable borrow of the instance.
+        unsafe { &mut *self.instance }
+    }
 }
 
 /// A service distributed amongst all shards of a Seastar app.
@@ -125,15 +299,19 @@ where
 /// One can only delegate a "mutating map" on an instance if all other "mutating maps" on it have ceased.
 /// In other words, you must ensure sequential acquisition of mutable borrows to an instance.
 /// Failing to comply with this rule will lead to a `panic!`, much in the same way that breaking the rule for
-/// a `RefCell` would do.
+/// a `RefCell` would do. Use `try_map_single` or `try_map_single_mut` to handle such conflicts gracefully.
 pub struct Distributed<S: Service> {
     _inner: SharedPtr<distributed>,
     _ty: PhantomData<S>,
     /// These allow only exclusive writes or shared reads to specific instances of the service.
-    /// Don't fret - no thread trying to map over an instance will be hanged on any of these locks.
-    /// They're not used for (blockingly) locking, but merely try-locking, which if failed will yield a panic.
+    /// Don't fret - no thread trying to map over an instance will be hanged on any of these flags.
+    /// They're not used for (blockingly) locking, but merely try-borrowing, which if failed will yield a panic
+    /// (or an error, in the case of the `try_map_*` functions).
     /// Comply with the `Distributed`'s ownership contract and all will be well.
-    _locks: Vec<Arc<RwLock<()>>>,
+    _borrows: Vec<Arc<BorrowFlag>>,
+    /// The shards the instances were started on.
+    _shards: Range<u32>,
+    _stopped: AtomicBool,
 }
 
 impl<S: Service> Distributed<S> {
@@ -144,6 +322,19 @@ impl<S: Service> Distributed<S> {
         unsafe { &*(local as *const S) }
     }
 
+    /// Returns a mutable reference to the underlying service on the current shard.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the instance on the current shard is borrowed by a map that hasn't finished yet.
+    pub fn local_mut(&mut self) -> &mut S {
+        // No new maps can be started through `self` while the returned reference is alive,
+        // so it's enough to check that there are no outstanding ones.
+        drop(self.borrow_mut(this_shard_id()));
+        let local = ffi::local(self._inner.as_ref().unwrap());
+        unsafe { &mut *(local as *mut S) }
+    }
+
     fn start_inner<Func>(service_maker: Func, single: bool) -> impl Future<Output = Self>
     where
         Func: Fn() -> S + Sync,
@@ -182,14 +373,23 @@ impl<S: Service> Distributed<S> {
         };
 
         async move {
-            match fut.await {
-                Ok(_) => Distributed {
-                    _inner: distr,
-                    _ty: PhantomData,
-                    _locks: vec![Default::default(); get_count() as usize],
-                },
-                Err(_) => panic!(),
+            if fut.await.is_err() {
+                panic!();
             }
+
+            let mut distributed = Distributed {
+                _inner: distr,
+                _ty: PhantomData,
+                _borrows: (0..get_count()).map(|_| Default::default()).collect(),
+                _shards: if single { 0..1 } else { 0..get_count() },
+                _stopped: AtomicBool::new(false),
+            };
+            let starts = distributed.map_selected_mut(
+                |pss| async move { Pin::from(pss.instance.start()).await },
+                distributed.shards(),
+            );
+            join_all(starts).await;
+            distributed
         }
     }
 
@@ -268,10 +468,91 @@ impl<S: Service> Distributed<S> {
         Distributed::start_inner(service_maker, false)
     }
 
+    /// Starts an instance of the service on each shard, passing the id of the shard
+    /// the instance is created for to `service_maker`.
+    ///
+    /// Useful for per-shard configuration, e.g. binding a shard-specific file.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use seastar::{Distributed, Service};
+    ///
+    /// struct ShardService {
+    ///     shard_id: u32,
+    /// }
+    ///
+    /// impl Service for ShardService {}
+    ///
+    /// #[seastar::test]
+    /// async fn test_start_with() {
+    ///     let distr = Distributed::start_with(|shard_id| ShardService { shard_id }).await;
+    ///     assert_eq!(distr.local().shard_id, seastar::this_shard_id());
+    ///     distr.stop().await;
+    /// }
+    /// ```
+    pub fn start_with<Func>(service_maker: Func) -> impl Future<Output = Self>
+    where
+        Func: Fn(u32) -> S + Sync,
+    {
+        // The maker is called on the shard the instance is created for.
+        Distributed::start_inner(move || service_maker(this_shard_id()), false)
+    }
+
+    /// Starts an instance of the service on each shard, passing a reference to `config`
+    /// to `service_maker`.
+    ///
+    /// The configuration is shared by all shards instead of being cloned into the maker for each of them,
+    /// which is handy for large configuration structs living for the duration of the app.
+    /// It only needs to outlive the returned future - instances don't borrow it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use seastar::{Distributed, Service};
+    ///
+    /// struct Config {
+    ///     capacity: usize,
+    /// }
+    ///
+    /// struct CacheService {
+    ///     entries: Vec<u64>,
+    /// }
+    ///
+    /// impl Service for CacheService {}
+    ///
+    /// #[seastar::test]
+    /// async fn test_start_with_config() {
+    ///     let config = Config { capacity: 1024 };
+    ///     let distr = Distributed::start_with_config(&config, |config| CacheService {
+    ///         entries: Vec::with_capacity(config.capacity),
+    ///     })
+    ///     .await;
+    ///     distr.stop().await;
+    /// }
+    /// ```
+    pub fn start_with_config<'c, C, Func>(
+        config: &'c C,
+        service_maker: Func,
+    ) -> impl Future<Output = Self> + 'c
+    where
+        C: Sync,
+        Func: Fn(&C) -> S + Sync + 'c,
+        S: 'c,
+    {
+        Distributed::start_inner(move || service_maker(config), false)
+    }
+
     /// Stops the service on all shards on which it was ran, freeing each instance's memory. Effectively an async destructor.
     ///
     /// This **must** be called when the distributed service is no longer to be used!.
     ///
+    /// Waits for all maps already running on an instance to finish before stopping it.
+    ///
+    /// # Panics
+    ///
+    /// Maps that start running on an instance after it began stopping will `panic!`.
+    ///
     /// ```rust
     /// use std::future::Future;
     /// use std::sync::atomic::{AtomicU32, Ordering};
@@ -300,14 +581,91 @@ impl<S: Service> Distributed<S> {
     /// ```
     pub async fn stop(&self) {
         crate::assert_runtime_is_running();
+        self._stopped.store(true, Ordering::Release);
         ffi::stop(self._inner.as_ref().unwrap()).await.unwrap();
     }
 
+    /// Stops the service like [`Distributed::stop`], but stops the instances on the shards
+    /// from `order` one by one, in the given order, awaiting each before stopping the next.
+    ///
+    /// Useful when instances depend on each other, e.g. when the one on shard `0` coordinates
+    /// the others and has to stop last. The instances on shards missing from `order`
+    /// are stopped concurrently afterwards.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `order` contains a shard without a live instance (see [`Distributed::shards`]).
+    pub async fn stop_ordered(&self, order: impl IntoIterator<Item = u32>) {
+        crate::assert_runtime_is_running();
+        let order: Vec<u32> = order.into_iter().collect();
+        for shard in &order {
+            assert!(
+                self.shards().any(|live| live == *shard),
+                "no live instance on shard {shard}"
+            );
+        }
+        self._stopped.store(true, Ordering::Release);
+        ffi::stop_ordered(self._inner.as_ref().unwrap(), &order)
+            .await
+            .unwrap();
+    }
+
+    /// Returns the ids of the shards with live instances of the service, in ascending order.
+    ///
+    /// That's every shard for a service started with `start`, only shard `0` for one
+    /// started with `start_single`, and none once `stop` has been called.
+    pub fn shards(&self) -> impl Iterator<Item = u32> {
+        if self._stopped.load(Ordering::Acquire) {
+            0..0
+        } else {
+            self._shards.clone()
+        }
+    }
+
+    /// Applies a function to every live instance of the service (see [`Distributed::shards`])
+    /// and waits for all of them to finish.
+    ///
+    /// Handy for administrative sweeps, like flushing caches or dumping stats on all shards.
+    pub async fn for_each_shard<'a, Func, Fut>(&'a self, func: Func)
+    where
+        Func: FnOnce(PeeringShardedService<'a, S>) -> Fut + Send + Clone + 'static,
+        Fut: Future<Output = ()>,
+    {
+        join_all(self.map_selected(func, self.shards())).await;
+    }
+
+    fn try_borrow(&self, shard_id: u32) -> Result<BorrowGuard, BorrowError> {
+        self._borrows[shard_id as usize]
+            .try_borrow()
+            .ok_or(BorrowError { shard_id })
+    }
+
+    fn try_borrow_mut(&self, shard_id: u32) -> Result<BorrowGuard, BorrowError> {
+        self._borrows[shard_id as usize]
+            .try_borrow_mut()
+            .ok_or(BorrowError { shard_id })
+    }
+
+    fn borrow(&self, shard_id: u32) -> BorrowGuard {
+        match self.try_borrow(shard_id) {
+            Ok(guard) => guard,
+            Err(_) => panic!("instance {} already mutably borrowed", shard_id),
+        }
+    }
+
+    fn borrow_mut(&self, shard_id: u32) -> BorrowGuard {
+        match self.try_borrow_mut(shard_id) {
+            Ok(guard) => guard,
+            Err(_) => panic!("instance {} already borrowed", shard_id),
+        }
+    }
+
     fn submit_to<'a, Func, Fut, Ret>(
         &'a self,
         shard_id: u32,
         func: Func,
         container: PtrWrapper,
+        guard: BorrowGuard,
     ) -> impl Future<Output = Ret>
     where
         Func: FnOnce(PeeringShardedService<'a, S>) -> Fut + Send + 'static,
@@ -317,12 +675,9 @@ impl<S: Service> Distributed<S> {
         crate::assert_runtime_is_running();
 
         let distr = self._inner.clone();
-        let lock = self._locks[shard_id as usize].clone();
         submit_to(shard_id, move || async move {
-            let lock = lock.try_read();
-            if lock.is_err() {
-                panic!("instance {} already mutably borrowed", shard_id);
-            }
+            let _guard = guard;
+            let _holder = enter_local(&distr);
 
             let instance = ffi::local(distr.as_ref().unwrap());
             let instance: &S = unsafe { &*(instance as *const S) };
@@ -331,6 +686,7 @@ impl<S: Service> Distributed<S> {
             let pss = PeeringShardedService {
                 instance,
                 container,
+                shard_id,
             };
             func(pss).await
         })
@@ -341,6 +697,7 @@ impl<S: Service> Distributed<S> {
         shard_id: u32,
         func: Func,
         container: PtrWrapper,
+        guard: BorrowGuard,
     ) -> impl Future<Output = Ret>
     where
         Func: FnOnce(PeeringShardedServiceMut<'a, S>) -> Fut + Send + 'static,
@@ -356,12 +713,9 @@ impl<S: Service> Distributed<S> {
         crate::assert_runtime_is_running();
 
         let distr = self._inner.clone();
-        let lock = self._locks[shard_id as usize].clone();
         submit_to(shard_id, move || async move {
-            let lock = lock.try_read();
-            if lock.is_err() {
-                panic!("instance {} already borrowed", shard_id);
-            }
+            let _guard = guard;
+            let _holder = enter_local(&distr);
 
             let instance = ffi::local(distr.as_ref().unwrap());
             let instance = unsafe { &mut *(instance as *mut S) };
@@ -370,6 +724,7 @@ impl<S: Service> Distributed<S> {
             let pss = PeeringShardedServiceMut {
                 instance,
                 container,
+                shard_id,
             };
             func(pss).await
         })
@@ -411,12 +766,14 @@ impl<S: Service> Distributed<S> {
         let mut res = vec![];
         for shard in shards.into_iter() {
             let container = unsafe { PtrWrapper::new(self as *const Distributed<S> as _) };
-            res.push(self.submit_to_mut(shard, func.clone(), container));
+            let guard = self.borrow_mut(shard);
+            res.push(self.submit_to_mut(shard, func.clone(), container, guard));
         }
         res
     }
 
-    /// Applies a map function to all instances of the service and returns a vector of the results.
+    /// Applies a map function to all live instances of the service (see [`Distributed::shards`])
+    /// and returns a vector of the results.
     ///
     /// Equivalent to `seastar::distributed::map`.
     ///
@@ -458,10 +815,10 @@ impl<S: Service> Distributed<S> {
         Fut: Future<Output = Ret>,
         Ret: Send + 'static,
     {
-        self.map_selected(func, 0..get_count())
+        self.map_selected(func, self.shards())
     }
 
-    /// Applies a mutating map function to all instances of the service and returns a vector of the results.
+    /// Applies a mutating map function to all live instances of the service and returns a vector of the results.
     ///
     /// Operates like `map_all` but mutates data along the way.
     pub fn map_all_mut<'a, Func, Ret, Fut>(
@@ -473,10 +830,90 @@ impl<S: Service> Distributed<S> {
         Fut: Future<Output = Ret>,
         Ret: Send + 'static,
     {
-        self.map_selected_mut(func, 0..get_count())
+        let shards = self.shards();
+        self.map_selected_mut(func, shards)
     }
 
-    /// Applies a map function to all instances of the service, except the one on the current shard, and returns a vector of the results.
+    /// Applies a synchronous map function to all live instances of the service
+    /// and returns a vector of the results.
+    ///
+    /// Operates like `map_all`, but `func` returns the result directly instead of a future,
+    /// so simple per-shard work doesn't need to be wrapped in an `async` block.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use futures::future::join_all;
+    /// use seastar::{get_count, Distributed, Service};
+    ///
+    /// struct ShardService(u32);
+    ///
+    /// impl Service for ShardService {}
+    ///
+    /// #[seastar::test]
+    /// async fn test_map_all_sync() {
+    ///     let distr = Distributed::start_with(ShardService).await;
+    ///     let ids = join_all(distr.map_all_sync(|pss| pss.instance.0)).await;
+    ///     assert_eq!(ids, (0..get_count()).collect::<Vec<_>>());
+    ///     distr.stop().await;
+    /// }
+    /// ```
+    pub fn map_all_sync<'a, Func, Ret>(&'a self, func: Func) -> Vec<impl Future<Output = Ret>>
+    where
+        Func: FnOnce(PeeringShardedService<'a, S>) -> Ret + Send + Clone + 'static,
+        Ret: Send + 'static,
+    {
+        self.map_all(move |pss| ready(func(pss)))
+    }
+
+    /// Applies a synchronous mutating map function to all live instances of the service
+    /// and returns a vector of the results.
+    ///
+    /// Operates like `map_all_mut`, but `func` returns the result directly instead of a future.
+    pub fn map_all_mut_sync<'a, Func, Ret>(
+        &'a mut self,
+        func: Func,
+    ) -> Vec<impl Future<Output = Ret>>
+    where
+        Func: FnOnce(PeeringShardedServiceMut<'a, S>) -> Ret + Send + Clone + 'static,
+        Ret: Send + 'static,
+    {
+        self.map_all_mut(move |pss| ready(func(pss)))
+    }
+
+    /// Applies a map function to all live instances of the service, running at most `limit`
+    /// of the maps at a time, and returns a vector of the results, ordered by shard id.
+    ///
+    /// Works like `map_all`, but doesn't overwhelm the system when the maps do heavy work
+    /// and there are lots of shards. Maps are started in the order of shard ids.
+    ///
+    /// Analogous to `seastar::max_concurrent_for_each`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `limit` is `0`.
+    pub async fn map_all_limited<'a, Func, Ret, Fut>(&'a self, limit: usize, func: Func) -> Vec<Ret>
+    where
+        Func: FnOnce(PeeringShardedService<'a, S>) -> Fut + Send + Clone + 'static,
+        Fut: Future<Output = Ret>,
+        Ret: Send + 'static,
+    {
+        assert!(limit > 0, "map_all_limited limit must be positive");
+        crate::assert_runtime_is_running();
+
+        let sem = Semaphore::new(limit);
+        let sem = &sem;
+        join_all(self.shards().map(|shard| {
+            let func = func.clone();
+            async move {
+                let _units = sem.get_units(1).await;
+                self.map_single(shard, func).await
+            }
+        }))
+        .await
+    }
+
+    /// Applies a map function to all live instances of the service, except the one on the current shard, and returns a vector of the results.
     ///
     /// Spiritually, a hybrid of `seastar::distributed::map` and `seastar::distributed::invoke_on_others`.
     ///
@@ -519,10 +956,10 @@ impl<S: Service> Distributed<S> {
         Ret: Send + 'static,
     {
         let this_shard = this_shard_id();
-        self.map_selected(func, (0..get_count()).filter(move |sh| sh.ne(&this_shard)))
+        self.map_selected(func, self.shards().filter(move |sh| sh.ne(&this_shard)))
     }
 
-    /// Applies a map function to all instances of the service, except the one on the current shard, and returns a vector of the results.
+    /// Applies a map function to all live instances of the service, except the one on the current shard, and returns a vector of the results.
     ///
     /// Operates like `map_others` but mutates data along the way.
     pub fn map_others_mut<'a, Func, Ret, Fut>(
@@ -535,7 +972,8 @@ impl<S: Service> Distributed<S> {
         Ret: Send + 'static,
     {
         let this_shard = this_shard_id();
-        self.map_selected_mut(func, (0..get_count()).filter(move |sh| sh.ne(&this_shard)))
+        let shards = self.shards().filter(move |sh| sh.ne(&this_shard));
+        self.map_selected_mut(func, shards)
     }
 
     /// Applies a map function only to the service instance on the provided shard.
@@ -584,7 +1022,28 @@ impl<S: Service> Distributed<S> {
         Ret: Send + 'static,
     {
         let container = unsafe { PtrWrapper::new(self as *const Distributed<S> as _) };
-        self.submit_to(shard_id, func, container)
+        let guard = self.borrow(shard_id);
+        self.submit_to(shard_id, func, container, guard)
+    }
+
+    /// Applies a map function only to the service instance on the provided shard,
+    /// unless the instance is currently mutably borrowed.
+    ///
+    /// Operates like `map_single`, but returns [`BorrowError`] instead of panicking
+    /// if the instance is already borrowed by a mutating map, similarly to `RefCell::try_borrow`.
+    pub fn try_map_single<'a, Func, Ret, Fut>(
+        &'a self,
+        shard_id: u32,
+        func: Func,
+    ) -> Result<impl Future<Output = Ret>, BorrowError>
+    where
+        Func: FnOnce(PeeringShardedService<'a, S>) -> Fut + Send + 'static,
+        Fut: Future<Output = Ret>,
+        Ret: Send + 'static,
+    {
+        let container = unsafe { PtrWrapper::new(self as *const Distributed<S> as _) };
+        let guard = self.try_borrow(shard_id)?;
+        Ok(self.submit_to(shard_id, func, container, guard))
     }
 
     /// Applies a map function only to the service instance on the provided shard.
@@ -601,7 +1060,141 @@ impl<S: Service> Distributed<S> {
         Ret: Send + 'static,
     {
         let container = unsafe { PtrWrapper::new(self as *const Distributed<S> as _) };
-        self.submit_to_mut(shard_id, func, container)
+        let guard = self.borrow_mut(shard_id);
+        self.submit_to_mut(shard_id, func, container, guard)
+    }
+
+    /// Applies a synchronous map function only to the service instance on the provided shard.
+    ///
+    /// Operates like `map_single`, but `func` returns the result directly instead of a future.
+    pub fn map_single_sync<'a, Func, Ret>(
+        &'a self,
+        shard_id: u32,
+        func: Func,
+    ) -> impl Future<Output = Ret>
+    where
+        Func: FnOnce(PeeringShardedService<'a, S>) -> Ret + Send + 'static,
+        Ret: Send + 'static,
+    {
+        self.map_single(shard_id, move |pss| ready(func(pss)))
+    }
+
+    /// Applies a synchronous mutating map function only to the service instance on the provided shard.
+    ///
+    /// Operates like `map_single_mut`, but `func` returns the result directly instead of a future.
+    pub fn map_single_mut_sync<'a, Func, Ret>(
+        &'a mut self,
+        shard_id: u32,
+        func: Func,
+    ) -> impl Future<Output = Ret>
+    where
+        Func: FnOnce(PeeringShardedServiceMut<'a, S>) -> Ret + Send + 'static,
+        Ret: Send + 'static,
+    {
+        self.map_single_mut(shard_id, move |pss| ready(func(pss)))
+    }
+
+    /// Applies a mutating map function only to the service instance on the provided shard,
+    /// unless the instance is currently borrowed.
+    ///
+    /// Operates like `map_single_mut`, but returns [`BorrowError`] instead of panicking
+    /// if the instance is already borrowed by any other map, similarly to `RefCell::try_borrow_mut`.
+    pub fn try_map_single_mut<'a, Func, Ret, Fut>(
+        &'a mut self,
+        shard_id: u32,
+        func: Func,
+    ) -> Result<impl Future<Output = Ret>, BorrowError>
+    where
+        Func: FnOnce(PeeringShardedServiceMut<'a, S>) -> Fut + Send + 'static,
+        Fut: Future<Output = Ret>,
+        Ret: Send + 'static,
+    {
+        let container = unsafe { PtrWrapper::new(self as *const Distributed<S> as _) };
+        let guard = self.try_borrow_mut(shard_id)?;
+        Ok(self.submit_to_mut(shard_id, func, container, guard))
+    }
+
+    /// Applies a map function only to the service instance on the provided shard,
+    /// returning a future which doesn't borrow the `Distributed`.
+    ///
+    /// Operates like `map_single`, but the returned future is `'static`, so it can be stored
+    /// or spawned as a separate task. The instance is borrowed from the call until the future
+    /// completes or is dropped, and it isn't stopped in the meantime - `stop` waits for it.
+    /// As the instance is given by an owning handle, the container isn't accessible from `func`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use seastar::{spawn, Distributed, Service};
+    ///
+    /// struct NameService(String);
+    ///
+    /// impl Service for NameService {}
+    ///
+    /// #[seastar::test]
+    /// async fn test_map_single_owned() {
+    ///     let distr = Distributed::start(|| NameService("name".to_string())).await;
+    ///     let fut = distr.map_single_owned(0, |service| async move { service.0.clone() });
+    ///     assert_eq!(spawn(fut).await, "name");
+    ///     distr.stop().await;
+    /// }
+    /// ```
+    pub fn map_single_owned<Func, Ret, Fut>(
+        &self,
+        shard_id: u32,
+        func: Func,
+    ) -> impl Future<Output = Ret> + 'static
+    where
+        S: 'static,
+        Func: FnOnce(OwnedPeeringService<S>) -> Fut + Send + 'static,
+        Fut: Future<Output = Ret> + 'static,
+        Ret: Send + 'static,
+    {
+        crate::assert_runtime_is_running();
+
+        let guard = self.borrow(shard_id);
+        let distr = self._inner.clone();
+        submit_to(shard_id, move || async move {
+            let service = OwnedPeeringService {
+                _holder: enter_local(&distr),
+                instance: ffi::local(distr.as_ref().unwrap()) as *const S,
+                shard_id,
+                _guard: guard,
+            };
+            func(service).await
+        })
+    }
+
+    /// Applies a mutating map function only to the service instance on the provided shard,
+    /// returning a future which doesn't borrow the `Distributed`.
+    ///
+    /// Operates like `map_single_owned` but mutates data along the way. The mutable borrow
+    /// is taken when this function is called, so it panics if the instance is already
+    /// borrowed by any other map.
+    pub fn map_single_mut_owned<Func, Ret, Fut>(
+        &self,
+        shard_id: u32,
+        func: Func,
+    ) -> impl Future<Output = Ret> + 'static
+    where
+        S: 'static,
+        Func: FnOnce(OwnedPeeringServiceMut<S>) -> Fut + Send + 'static,
+        Fut: Future<Output = Ret> + 'static,
+        Ret: Send + 'static,
+    {
+        crate::assert_runtime_is_running();
+
+        let guard = self.borrow_mut(shard_id);
+        let distr = self._inner.clone();
+        submit_to(shard_id, move || async move {
+            let service = OwnedPeeringServiceMut {
+                _holder: enter_local(&distr),
+                instance: ffi::local(distr.as_ref().unwrap()) as *mut S,
+                shard_id,
+                _guard: guard,
+            };
+            func(service).await
+        })
     }
 
     /// Like `map_single` but for the current shard.
@@ -618,12 +1211,12 @@ impl<S: Service> Distributed<S> {
 
         let distr = self._inner.clone();
         let container = unsafe { PtrWrapper::new(self as *const Distributed<S> as _) };
-        let lock = self._locks[this_shard_id() as usize].clone();
+        let shard_id = this_shard_id();
+        let guard = self.borrow(shard_id);
         spawn(async move {
-            let lock = lock.try_read();
-            if lock.is_err() {
-                panic!("instance {} already mutably borrowed", this_shard_id());
-            }
+            let _guard = guard;
+            let _holder = enter_local(&distr);
+
             let instance = ffi::local(distr.as_ref().unwrap());
             let instance: &S = unsafe { &*(instance as *const S) };
             let _ = &container; // this is to avoid a partial move of the pointer
@@ -631,6 +1224,7 @@ impl<S: Service> Distributed<S> {
             let pss = PeeringShardedService {
                 instance,
                 container,
+                shard_id,
             };
             func(pss).await
         })
@@ -651,12 +1245,11 @@ impl<S: Service> Distributed<S> {
 
         let distr = self._inner.clone();
         let container = unsafe { PtrWrapper::new(self as *const Distributed<S> as _) };
-        let lock = self._locks[this_shard_id() as usize].clone();
+        let shard_id = this_shard_id();
+        let guard = self.borrow_mut(shard_id);
         spawn(async move {
-            let lock = lock.try_read();
-            if lock.is_err() {
-                panic!("instance {} already borrowed", this_shard_id());
-            }
+            let _guard = guard;
+            let _holder = enter_local(&distr);
 
             let instance = ffi::local(distr.as_ref().unwrap());
             let instance = unsafe { &mut *(instance as *mut S) };
@@ -665,6 +1258,7 @@ impl<S: Service> Distributed<S> {
             let pss = PeeringShardedServiceMut {
                 instance,
                 container,
+                shard_id,
             };
             func(pss).await
         })
@@ -673,12 +1267,13 @@ impl<S: Service> Distributed<S> {
 
 #[cfg(test)]
 mod tests {
-    use futures::future::join_all;
+    use futures::channel::oneshot;
     use std::sync::atomic::{AtomicU32, Ordering};
     use std::sync::Arc;
 
     use super::*;
     use crate as seastar;
+    use crate::{sleep, Duration, SteadyClock};
 
     struct CounterService(Arc<AtomicU32>);
 
@@ -730,6 +1325,149 @@ mod tests {
         assert_eq!(get_count(), counter.load(Ordering::SeqCst));
     }
 
+    #[seastar::test]
+    async fn test_start_with_shard_id() {
+        struct ShardService(u32);
+
+        impl Service for ShardService {}
+
+        let distr = Distributed::start_with(ShardService).await;
+        let futs = distr.map_all(|pss| async move { pss.instance.0 });
+        let ids = join_all(futs).await;
+        assert_eq!(ids, (0..get_count()).collect::<Vec<_>>());
+        distr.stop().await;
+    }
+
+    #[seastar::test]
+    async fn test_start_awaits_service_start() {
+        struct StartedService(bool);
+
+        impl Service for StartedService {
+            fn start(&mut self) -> Box<dyn Future<Output = ()> + '_> {
+                Box::new(async move {
+                    sleep(Duration::<SteadyClock>::from_millis(1)).await;
+                    self.0 = true;
+                })
+            }
+        }
+
+        let distr = Distributed::start(|| StartedService(false)).await;
+        let futs = distr.map_all(|pss| async move { pss.instance.0 });
+        assert!(join_all(futs).await.into_iter().all(|started| started));
+        distr.stop().await;
+
+        let distr = Distributed::start_single(|| StartedService(false)).await;
+        assert!(distr.local().0);
+        distr.stop().await;
+    }
+
+    #[seastar::test]
+    async fn test_start_with_config() {
+        struct Config {
+            names: Vec<String>,
+        }
+
+        struct NameService(String);
+
+        impl Service for NameService {}
+
+        let config = Config {
+            names: (0..get_count()).map(|id| format!("shard-{id}")).collect(),
+        };
+        let distr = Distributed::start_with_config(&config, |config| {
+            NameService(config.names[this_shard_id() as usize].clone())
+        })
+        .await;
+        let futs = distr.map_all(|pss| async move { pss.instance.0.clone() });
+        assert_eq!(join_all(futs).await, config.names);
+        distr.stop().await;
+    }
+
+    #[seastar::test]
+    async fn test_stop_ordered() {
+        struct OrderedService(u32, Arc<std::sync::Mutex<Vec<u32>>>);
+
+        impl Service for OrderedService {
+            fn stop(&self) -> Box<dyn Future<Output = ()>> {
+                let (shard_id, stopped) = (self.0, self.1.clone());
+                Box::new(async move {
+                    // Give the instances stopped later a chance to overtake this one.
+                    sleep(Duration::<SteadyClock>::from_millis(1)).await;
+                    stopped.lock().unwrap().push(shard_id);
+                })
+            }
+        }
+
+        let stopped: Arc<std::sync::Mutex<Vec<u32>>> = Default::default();
+        let stopped_clone = stopped.clone();
+        let distr = Distributed::start_with(move |shard_id| {
+            OrderedService(shard_id, stopped_clone.clone())
+        })
+        .await;
+        // Shard 0 stops last.
+        let order: Vec<u32> = (0..get_count()).rev().collect();
+        distr.stop_ordered(order.iter().copied()).await;
+        assert_eq!(*stopped.lock().unwrap(), order);
+        assert_eq!(distr.shards().count(), 0);
+    }
+
+    #[seastar::test]
+    async fn test_shards() {
+        let distr = Distributed::start(|| CounterService(Default::default())).await;
+        assert_eq!(
+            distr.shards().collect::<Vec<_>>(),
+            (0..get_count()).collect::<Vec<_>>()
+        );
+        distr.stop().await;
+        assert_eq!(distr.shards().count(), 0);
+
+        let distr = Distributed::start_single(|| CounterService(Default::default())).await;
+        assert_eq!(distr.shards().collect::<Vec<_>>(), vec![0]);
+        distr.stop().await;
+    }
+
+    #[seastar::test]
+    async fn test_map_all_limited() {
+        let running: Arc<AtomicU32> = Default::default();
+        let max_running: Arc<AtomicU32> = Default::default();
+        let distr = Distributed::start(|| CounterService(Default::default())).await;
+
+        let (running_clone, max_running_clone) = (running.clone(), max_running.clone());
+        let shards = distr
+            .map_all_limited(2, move |_| async move {
+                let now_running = running_clone.fetch_add(1, Ordering::SeqCst) + 1;
+                max_running_clone.fetch_max(now_running, Ordering::SeqCst);
+                sleep(Duration::<SteadyClock>::from_millis(10)).await;
+                running_clone.fetch_sub(1, Ordering::SeqCst);
+                this_shard_id()
+            })
+            .await;
+        distr.stop().await;
+
+        assert_eq!(shards, (0..get_count()).collect::<Vec<_>>());
+        assert!(max_running.load(Ordering::SeqCst) <= 2);
+    }
+
+    #[seastar::test]
+    async fn test_map_all_single() {
+        let counter: Arc<AtomicU32> = Default::default();
+        let counter_clone = counter.clone();
+        let distr = Distributed::start_single(move || CounterService(counter_clone.clone())).await;
+        join_all(distr.map_all(|pss| pss.instance.inc())).await;
+        distr.stop().await;
+        assert_eq!(2, counter.load(Ordering::SeqCst));
+    }
+
+    #[seastar::test]
+    async fn test_for_each_shard() {
+        let counter: Arc<AtomicU32> = Default::default();
+        let counter_clone = counter.clone();
+        let distr = Distributed::start(move || CounterService(counter_clone.clone())).await;
+        distr.for_each_shard(|pss| pss.instance.inc()).await;
+        distr.stop().await;
+        assert_eq!(2 * get_count(), counter.load(Ordering::SeqCst));
+    }
+
     #[seastar::test]
     async fn test_map_all() {
         let counter: Arc<AtomicU32> = Default::default();
@@ -763,6 +1501,30 @@ mod tests {
         distr.stop().await;
     }
 
+    #[seastar::test]
+    async fn test_map_all_sync() {
+        struct NumberService(u32);
+
+        impl Service for NumberService {}
+
+        let mut distr = Distributed::start_with(NumberService).await;
+        join_all(distr.map_all_mut_sync(|pss| pss.instance.0 *= 2)).await;
+        let numbers = join_all(distr.map_all_sync(|pss| pss.instance.0)).await;
+        assert_eq!(
+            numbers,
+            (0..get_count()).map(|id| id * 2).collect::<Vec<_>>()
+        );
+
+        for shard in 0..get_count() {
+            distr
+                .map_single_mut_sync(shard, |pss| pss.instance.0 += 1)
+                .await;
+            let number = distr.map_single_sync(shard, |pss| pss.instance.0).await;
+            assert_eq!(number, shard * 2 + 1);
+        }
+        distr.stop().await;
+    }
+
     #[seastar::test]
     async fn test_map_others() {
         let counter: Arc<AtomicU32> = Default::default();
@@ -823,6 +1585,28 @@ mod tests {
         distr.stop().await;
     }
 
+    #[seastar::test]
+    async fn test_pss_shard_id() {
+        let service_maker = move || BoolService(false);
+        let mut distr = Distributed::start(service_maker).await;
+
+        let same = distr
+            .map_current(|pss| async move { pss.shard_id == this_shard_id() })
+            .await;
+        assert!(same);
+        let same = distr
+            .map_current_mut(|pss| async move { pss.shard_id == this_shard_id() })
+            .await;
+        assert!(same);
+        let same =
+            join_all(distr.map_all(|pss| async move { pss.shard_id == this_shard_id() })).await;
+        assert!(same.into_iter().all(|same| same));
+        let same =
+            join_all(distr.map_all_mut(|pss| async move { pss.shard_id == this_shard_id() })).await;
+        assert!(same.into_iter().all(|same| same));
+        distr.stop().await;
+    }
+
     #[seastar::test]
     async fn test_map_current_mut() {
         let service_maker = move || BoolService(false);
@@ -834,6 +1618,92 @@ mod tests {
         distr.stop().await;
     }
 
+    #[seastar::test]
+    async fn test_stop_awaits_running_map() {
+        let counter: Arc<AtomicU32> = Default::default();
+        let counter_clone = counter.clone();
+        let service_maker = move || CounterService(counter_clone.clone());
+        let distr = Distributed::start(service_maker).await;
+
+        let (started_tx, started_rx) = oneshot::channel();
+        let map = distr.map_single(0, |pss| async move {
+            started_tx.send(()).unwrap();
+            sleep(Duration::<SteadyClock>::from_millis(100)).await;
+            pss.instance.inc().await;
+        });
+        started_rx.await.unwrap();
+
+        distr.stop().await;
+        assert_eq!(get_count() + 1, counter.load(Ordering::SeqCst));
+        map.await;
+    }
+
+    #[seastar::test]
+    async fn test_map_single_mut_without_interior_mutability() {
+        struct PlainCounter(u32);
+
+        impl Service for PlainCounter {}
+
+        let mut distr = Distributed::start(|| PlainCounter(0)).await;
+        for shard in 0..get_count() {
+            for _ in 0..=shard {
+                distr
+                    .map_single_mut(shard, |pss| async move { pss.instance.0 += 1 })
+                    .await;
+            }
+        }
+        for shard in 0..get_count() {
+            let value = distr
+                .map_single(shard, |pss| async move { pss.instance.0 })
+                .await;
+            assert_eq!(value, shard + 1);
+        }
+        distr.stop().await;
+    }
+
+    #[seastar::test]
+    async fn test_try_map_single_mut_already_borrowed() {
+        let service_maker = move || BoolService(false);
+        let mut distr = Distributed::start(service_maker).await;
+
+        // The outer map holds a shared borrow of the instance while the inner one runs.
+        let res = distr
+            .map_single(0, |pss| async move {
+                match pss
+                    .container
+                    .try_map_single_mut(0, |pss| pss.instance.set())
+                {
+                    Ok(_) => None,
+                    Err(e) => Some(e.shard_id()),
+                }
+            })
+            .await;
+        assert_eq!(res, Some(0));
+
+        // After the borrow is released, the mutating map succeeds.
+        distr
+            .try_map_single_mut(0, |pss| pss.instance.set())
+            .unwrap()
+            .await;
+        let res = distr
+            .try_map_single(0, |pss| pss.instance.get())
+            .unwrap()
+            .await;
+        assert!(res);
+        distr.stop().await;
+    }
+
+    #[seastar::test]
+    async fn test_local_mut() {
+        let service_maker = move || BoolService(false);
+        let mut distr = Distributed::start(service_maker).await;
+
+        distr.local_mut().0 = true;
+        assert!(distr.local().0);
+        assert!(distr.map_current(|pss| pss.instance.get()).await);
+        distr.stop().await;
+    }
+
     #[seastar::test]
     async fn test_map_single_mut() {
         let service_maker = move || BoolService(false);
@@ -918,4 +1788,43 @@ mod tests {
         assert_eq!(get_count().pow(2), counter.load(Ordering::SeqCst));
         distr.stop().await;
     }
+
+    #[seastar::test]
+    async fn test_spawn_stored_map_single_owned() {
+        struct PendingMaps<F> {
+            futs: Vec<F>,
+        }
+
+        let counter: Arc<AtomicU32> = Default::default();
+        let counter_clone = counter.clone();
+        let service_maker = move || CounterService(counter_clone.clone());
+        let distr = Distributed::start(service_maker).await;
+
+        let pending = PendingMaps {
+            futs: distr
+                .shards()
+                .map(|shard| {
+                    distr.map_single_owned(shard, |service| async move {
+                        service.inc().await;
+                        service.shard_id()
+                    })
+                })
+                .collect(),
+        };
+        let ids = spawn(join_all(pending.futs)).await;
+        assert_eq!(ids, (0..get_count()).collect::<Vec<_>>());
+        assert_eq!(get_count(), counter.load(Ordering::SeqCst));
+
+        distr.stop().await;
+    }
+
+    #[seastar::test]
+    async fn test_map_single_mut_owned() {
+        let distr = Distributed::start(|| BoolService(false)).await;
+        let fut = distr.map_single_mut_owned(0, |mut service| async move { service.set().await });
+        assert!(distr.try_map_single(0, |pss| pss.instance.get()).is_err());
+        spawn(fut).await;
+        assert!(distr.map_single(0, |pss| pss.instance.get()).await);
+        distr.stop().await;
+    }
 }
@@ -0,0 +1,187 @@
+use crate::DmaBuffer;
+use std::cell::RefCell;
+use std::ops::{Deref, DerefMut};
+use std::rc::Rc;
+
+struct PoolInner {
+    buffer_len: usize,
+    capacity: usize,
+    free: RefCell<Vec<DmaBuffer>>,
+}
+
+impl PoolInner {
+    fn release(&self, mut buffer: DmaBuffer) {
+        let mut free = self.free.borrow_mut();
+        if free.len() < self.capacity && buffer.reset_len(self.buffer_len) {
+            free.push(buffer);
+        }
+    }
+}
+
+/// A pool of equally sized [`DmaBuffer`]s, reusing their allocations instead of freeing them.
+///
+/// Suits I/O loops which would otherwise allocate and free an aligned buffer for every
+/// operation. Buffers are handed out as [`PooledDmaBuffer`]s, which go back to the pool
+/// when dropped. The pool keeps at most `capacity` free buffers, the ones released above
+/// that are freed. Buffers are allocated lazily, when no free one is left.
+///
+/// The pool is local to the shard it was created on (it's `!Send`). Cloning it gives
+/// another handle to the same pool.
+///
+/// Reused buffers are not zeroed, they keep the contents they had when released.
+#[derive(Clone)]
+pub struct DmaBufferPool {
+    inner: Rc<PoolInner>,
+}
+
+impl DmaBufferPool {
+    /// Creates an empty pool of buffers holding `buffer_len` bytes,
+    /// keeping at most `capacity` free buffers.
+    pub fn new(buffer_len: usize, capacity: usize) -> Self {
+        DmaBufferPool {
+            inner: Rc::new(PoolInner {
+                buffer_len,
+                capacity,
+                free: RefCell::new(Vec::with_capacity(capacity)),
+            }),
+        }
+    }
+
+    /// Returns the length of the pool's buffers.
+    pub fn buffer_len(&self) -> usize {
+        self.inner.buffer_len
+    }
+
+    /// Returns the maximum number of free buffers kept by the pool.
+    pub fn capacity(&self) -> usize {
+        self.inner.capacity
+    }
+
+    /// Returns the number of free buffers in the pool.
+    pub fn available(&self) -> usize {
+        self.inner.free.borrow().len()
+    }
+
+    /// Takes a free buffer from the pool, or allocates a new, zeroed one if there is none.
+    pub fn acquire(&self) -> PooledDmaBuffer {
+        let free = self.inner.free.borrow_mut().pop();
+        let buffer = free.unwrap_or_else(|| DmaBuffer::new_zeroed(self.inner.buffer_len));
+        PooledDmaBuffer {
+            buffer: Some(buffer),
+            pool: self.inner.clone(),
+        }
+    }
+
+    /// Gives a buffer back to the pool.
+    ///
+    /// Used for buffers detached with [`PooledDmaBuffer::into_inner`], e.g. to be passed to
+    /// [`File::read_dma`](crate::File::read_dma). The buffer is freed instead if the pool
+    /// is full or if it doesn't come from a pool with the same buffer length.
+    pub fn release(&self, buffer: DmaBuffer) {
+        self.inner.release(buffer);
+    }
+}
+
+/// A [`DmaBuffer`] taken from a [`DmaBufferPool`], given back to it when dropped.
+pub struct PooledDmaBuffer {
+    // Always `Some`, until the buffer is released.
+    buffer: Option<DmaBuffer>,
+    pool: Rc<PoolInner>,
+}
+
+impl PooledDmaBuffer {
+    /// Detaches the buffer from the pool.
+    ///
+    /// [`File`](crate::File)'s functions take buffers by value, so this is needed to do I/O
+    /// with a pooled buffer. Give the buffer back with [`DmaBufferPool::release`] afterwards,
+    /// otherwise it's freed when dropped.
+    pub fn into_inner(mut self) -> DmaBuffer {
+        self.buffer.take().unwrap()
+    }
+}
+
+impl Deref for PooledDmaBuffer {
+    type Target = DmaBuffer;
+
+    fn deref(&self) -> &DmaBuffer {
+        self.buffer.as_ref().unwrap()
+    }
+}
+
+impl DerefMut for PooledDmaBuffer {
+    fn deref_mut(&mut self) -> &mut DmaBuffer {
+        self.buffer.as_mut().unwrap()
+    }
+}
+
+impl Drop for PooledDmaBuffer {
+    fn drop(&mut self) {
+        if let Some(buffer) = self.buffer.take() {
+            self.pool.release(buffer);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as seastar;
+    use crate::OpenOptions;
+
+    #[test]
+    fn test_dma_buffer_pool_reuses_buffers() {
+        let pool = DmaBufferPool::new(8192, 2);
+        let first = pool.acquire();
+        let ptr = first.as_slice().as_ptr();
+        assert_eq!(first.len(), 8192);
+        drop(first);
+        assert_eq!(pool.available(), 1);
+
+        // The same allocation is handed out over and over again.
+        for i in 0..10000 {
+            let mut buffer = pool.acquire();
+            assert_eq!(buffer.as_slice().as_ptr(), ptr);
+            buffer[0] = i as u8;
+        }
+        assert_eq!(pool.available(), 1);
+        assert_eq!(pool.acquire()[0], (9999 % 256) as u8);
+    }
+
+    #[test]
+    fn test_dma_buffer_pool_capacity() {
+        let pool = DmaBufferPool::new(100, 2);
+        let mut buffers: Vec<_> = (0..3).map(|_| pool.acquire()).collect();
+        assert_eq!(pool.available(), 0);
+        buffers.pop();
+        assert_eq!(pool.available(), 1);
+
+        // Buffers with another allocation are not taken by the pool.
+        pool.release(DmaBuffer::from_slice(&[0; 5000]));
+        assert_eq!(pool.available(), 1);
+
+        // Buffers released to a full pool are freed.
+        drop(buffers);
+        pool.release(DmaBuffer::from_slice(&[0; 100]));
+        assert_eq!(pool.available(), 2);
+    }
+
+    #[seastar::test]
+    async fn test_dma_buffer_pool_file_io() {
+        let mut path = std::env::temp_dir();
+        path.push(format!("dma_buffer_pool_{}", std::process::id()));
+        std::fs::write(&path, [7; 4096]).unwrap();
+        let file = OpenOptions::new().read(true).open(&path).await.unwrap();
+
+        let pool = DmaBufferPool::new(4096, 1);
+        let buffer = pool.acquire().into_inner();
+        let ptr = buffer.as_slice().as_ptr();
+        let (read, buffer) = file.read_dma(buffer, 0).await.unwrap();
+        assert_eq!(read, 4096);
+        assert!(buffer.iter().all(|&b| b == 7));
+        pool.release(buffer);
+        assert_eq!(pool.acquire().as_slice().as_ptr(), ptr);
+
+        file.close().await.unwrap();
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -8,31 +8,89 @@ open_flags parse_options(const OpenOptions& opts) {
     bool read = opts.get_read();
     bool write = opts.get_write();
     bool create = opts.get_create();
+    bool truncate = opts.get_truncate();
+    bool append = opts.get_append();
+    bool create_new = opts.get_create_new();
 
     open_flags flags = open_flags(0);
 
-    if (read) flags |= open_flags::ro;
-    if (write) flags |= open_flags::wo;
-    if (create) flags |= open_flags::create;
+    if (read && write) flags |= open_flags::rw;
+    else if (read) flags |= open_flags::ro;
+    else if (write) flags |= open_flags::wo;
+    if (create_new) {
+        flags |= open_flags::create | open_flags::exclusive;
+    } else {
+        if (create) flags |= open_flags::create;
+        if (truncate) flags |= open_flags::truncate;
+    }
+    // Seastar doesn't define a flag for O_APPEND.
+    if (append) flags |= open_flags(O_APPEND);
 
     return flags;
 }
 
-VoidFuture open_dma(std::unique_ptr<file_t>& file, rust::str name, const OpenOptions& opts) {
+VoidFuture open_dma(std::unique_ptr<file_t>& file, rust::str name, const OpenOptions& opts, int32_t& error) {
     std::string_view sv_name(name.begin(), name.size());
     open_flags flags = parse_options(opts);
-    file_t new_file = co_await seastar::open_file_dma(sv_name, flags);
-    file = std::make_unique<file_t>(std::move(new_file));
+    try {
+        file_t new_file = co_await seastar::open_file_dma(sv_name, flags);
+        file = std::make_unique<file_t>(std::move(new_file));
+    } catch (const std::system_error& e) {
+        error = e.code().value();
+        throw;
+    }
+}
+
+VoidFuture open_buffered(std::unique_ptr<file_t>& file, rust::str name, const OpenOptions& opts, int32_t& error, int32_t& fd) {
+    std::string s_name(name.begin(), name.size());
+    int flags = static_cast<int>(parse_options(opts)) | O_CLOEXEC;
+    // Seastar only exposes opening files with O_DIRECT, so the file is opened
+    // with a plain syscall. Unlike I/O on the file, opening it does not block for long.
+    fd = ::open(s_name.c_str(), flags, S_IRUSR | S_IWUSR | S_IRGRP | S_IROTH);
+    if (fd == -1) {
+        error = errno;
+        throw std::system_error(errno, std::system_category(), s_name);
+    }
+    file = std::make_unique<file_t>(fd);
+    co_return;
 }
 
 IntFuture read_dma(const std::unique_ptr<file_t>& file, uint8_t* buffer, uint64_t size, uint64_t pos) {
     co_return co_await file->dma_read(pos, buffer, size);
 }
 
+static seastar::future<size_t> read_dma_iov(const std::unique_ptr<file_t>& file, uint64_t pos, std::vector<iovec> iov) {
+    co_return co_await file->dma_read(pos, std::move(iov));
+}
+
+IntFuture read_dma_iov(const std::unique_ptr<file_t>& file, uint64_t pos, rust::Slice<const io_vec> iov) {
+    // The slice is copied before the first suspension point, it doesn't have to outlive the read.
+    std::vector<iovec> vec;
+    vec.reserve(iov.size());
+    for (const auto& v : iov) {
+        vec.push_back(iovec{reinterpret_cast<void*>(v.base), v.len});
+    }
+    co_return co_await read_dma_iov(file, pos, std::move(vec));
+}
+
 IntFuture write_dma(const std::unique_ptr<file_t>& file, uint8_t* buffer, uint64_t size, uint64_t pos) {
     co_return co_await file->dma_write(pos, buffer, size);
 }
 
+static seastar::future<size_t> write_dma_iov(const std::unique_ptr<file_t>& file, uint64_t pos, std::vector<iovec> iov) {
+    co_return co_await file->dma_write(pos, std::move(iov));
+}
+
+IntFuture write_dma_iov(const std::unique_ptr<file_t>& file, uint64_t pos, rust::Slice<const io_vec> iov) {
+    // The slice is copied before the first suspension point, it doesn't have to outlive the write.
+    std::vector<iovec> vec;
+    vec.reserve(iov.size());
+    for (const auto& v : iov) {
+        vec.push_back(iovec{reinterpret_cast<void*>(v.base), v.len});
+    }
+    co_return co_await write_dma_iov(file, pos, std::move(vec));
+}
+
 VoidFuture flush(const std::unique_ptr<file_t>& file) {
     co_await file->flush();
 }
@@ -41,9 +99,58 @@ VoidFuture close(const std::unique_ptr<file_t>& file) {
     co_await file->close();
 }
 
+std::unique_ptr<file_t> dup(const std::unique_ptr<file_t>& file, int32_t& fd, int32_t& error) {
+    if (fd == -1) {
+        return std::make_unique<file_t>(file->dup());
+    }
+    // The descriptor is needed for `fadvise`, so it's duplicated here rather than by Seastar.
+    int new_fd = ::fcntl(fd, F_DUPFD_CLOEXEC, 0);
+    if (new_fd == -1) {
+        error = errno;
+        throw std::system_error(errno, std::system_category(), "dup");
+    }
+    fd = new_fd;
+    return std::make_unique<file_t>(new_fd);
+}
+
 IntFuture size(const std::unique_ptr<file_t>& file) {
     co_return co_await file->size();
 }
 
+int32_t fadvise(int32_t fd, uint64_t pos, uint64_t len, Hint hint) {
+    int advice;
+    switch (hint) {
+    case Hint::Sequential:
+        advice = POSIX_FADV_SEQUENTIAL;
+        break;
+    case Hint::Random:
+        advice = POSIX_FADV_RANDOM;
+        break;
+    case Hint::WillNeed:
+        advice = POSIX_FADV_WILLNEED;
+        break;
+    case Hint::DontNeed:
+        advice = POSIX_FADV_DONTNEED;
+        break;
+    default:
+        return EINVAL;
+    }
+    // Seastar doesn't expose `posix_fadvise`. It only gives advice to the kernel, so it doesn't block.
+    return ::posix_fadvise(fd, (off_t)pos, (off_t)len, advice);
+}
+
+std::unique_ptr<input_stream> make_input_stream(const std::unique_ptr<file_t>& file) {
+    return std::make_unique<input_stream>(seastar::make_file_input_stream(*file));
+}
+
+VoidFuture make_output_stream(const std::unique_ptr<file_t>& file, size_t buffer_size, std::unique_ptr<output_stream>& output) {
+    seastar::file_output_stream_options options;
+    if (buffer_size != 0) {
+        options.buffer_size = buffer_size;
+    }
+    output_stream new_output = co_await seastar::make_file_output_stream(*file, options);
+    output = std::make_unique<output_stream>(std::move(new_output));
+}
+
 } // file
 } // seastar_ffi
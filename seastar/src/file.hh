@@ -1,9 +1,12 @@
 #pragma once
 
 #include "cxx_async_futures.hh"
+#include "iostream.hh"
 #include <seastar/core/file.hh>
 #include <seastar/core/file-types.hh>
 #include <seastar/core/seastar.hh>
+#include <seastar/core/fstream.hh>
+#include <fcntl.h>
 
 struct OpenOptions;
 
@@ -12,21 +15,48 @@ namespace file {
 
 using file_t = seastar::file;
 using open_flags = seastar::open_flags;
+using seastar_ffi::iostream::input_stream;
+using seastar_ffi::iostream::output_stream;
+
+struct io_vec;
 
 // Creates `seastar::open_flags` from Rust's OpenOptions.
 open_flags parse_options(const OpenOptions& opts);
 
-VoidFuture open_dma(std::unique_ptr<file_t>& file, rust::str name, const OpenOptions& opts);
+// On failure, sets `error` to the errno of the failed system call, if there was one.
+VoidFuture open_dma(std::unique_ptr<file_t>& file, rust::str name, const OpenOptions& opts, int32_t& error);
+
+// Opens a file without O_DIRECT, so that the I/O goes through the page cache.
+// Sets `fd` to the descriptor of the opened file, which stays owned by `file`.
+VoidFuture open_buffered(std::unique_ptr<file_t>& file, rust::str name, const OpenOptions& opts, int32_t& error, int32_t& fd);
 
 IntFuture read_dma(const std::unique_ptr<file_t>& file, uint8_t* buffer, uint64_t size, uint64_t pos);
 
+IntFuture read_dma_iov(const std::unique_ptr<file_t>& file, uint64_t pos, rust::Slice<const io_vec> iov);
+
 IntFuture write_dma(const std::unique_ptr<file_t>& file, uint8_t* buffer, uint64_t size, uint64_t pos);
 
+IntFuture write_dma_iov(const std::unique_ptr<file_t>& file, uint64_t pos, rust::Slice<const io_vec> iov);
+
 VoidFuture flush(const std::unique_ptr<file_t>& file);
 
 VoidFuture close(const std::unique_ptr<file_t>& file);
 
+// `fd` is the descriptor of a file opened without direct I/O, or -1.
+// It's replaced with the descriptor of the new handle.
+std::unique_ptr<file_t> dup(const std::unique_ptr<file_t>& file, int32_t& fd, int32_t& error);
+
 IntFuture size(const std::unique_ptr<file_t>& file);
 
+enum class Hint : uint8_t;
+
+// Returns the error number on failure, 0 otherwise.
+int32_t fadvise(int32_t fd, uint64_t pos, uint64_t len, Hint hint);
+
+std::unique_ptr<input_stream> make_input_stream(const std::unique_ptr<file_t>& file);
+
+// A `buffer_size` of 0 stands for the default one.
+VoidFuture make_output_stream(const std::unique_ptr<file_t>& file, size_t buffer_size, std::unique_ptr<output_stream>& output);
+
 } // file
 } // seastar_ffi
@@ -1,10 +1,13 @@
 use crate::assert_runtime_is_running;
+use crate::{InputStream, OutputStream};
 use cxx::UniquePtr;
 use ffi::*;
 use std::alloc::{self, Layout};
+use std::fmt;
 use std::io;
 use std::ops::{Deref, Index, IndexMut};
 use std::path::Path;
+use thiserror::Error;
 
 #[cxx::bridge]
 mod ffi {
@@ -14,6 +17,10 @@ mod ffi {
         pub fn get_read(&self) -> bool;
         pub fn get_write(&self) -> bool;
         pub fn get_create(&self) -> bool;
+        pub fn get_truncate(&self) -> bool;
+        pub fn get_append(&self) -> bool;
+        pub fn get_create_new(&self) -> bool;
+        pub fn get_dma(&self) -> bool;
     }
 
     #[namespace = "seastar_ffi"]
@@ -22,13 +29,53 @@ mod ffi {
         type IntFuture = crate::cxx_async_futures::IntFuture;
     }
 
+    /// An access pattern hint for the page cache, given with [`File::hint_access`].
+    #[namespace = "seastar_ffi::file"]
+    #[repr(u8)]
+    enum Hint {
+        /// The region will be read sequentially, so the kernel can read ahead more aggressively.
+        Sequential,
+        /// The region will be read in a random order, so reading ahead is wasteful.
+        Random,
+        /// The region will be needed soon, so the kernel can start reading it into the cache.
+        WillNeed,
+        /// The region won't be needed soon, so the kernel can evict it from the cache.
+        DontNeed,
+    }
+
+    // A part of a scatter/gather transfer, like `struct iovec`.
+    #[namespace = "seastar_ffi::file"]
+    struct io_vec {
+        base: usize,
+        len: usize,
+    }
+
+    #[namespace = "seastar_ffi::iostream"]
+    unsafe extern "C++" {
+        type input_stream = crate::iostream::ffi::input_stream;
+        type output_stream = crate::iostream::ffi::output_stream;
+    }
+
     #[namespace = "seastar_ffi::file"]
     unsafe extern "C++" {
         include!("seastar/src/file.hh");
 
         type file_t;
 
-        fn open_dma(file: &mut UniquePtr<file_t>, name: &str, opts: &OpenOptions) -> VoidFuture;
+        fn open_dma(
+            file: &mut UniquePtr<file_t>,
+            name: &str,
+            opts: &OpenOptions,
+            error: &mut i32,
+        ) -> VoidFuture;
+
+        fn open_buffered(
+            file: &mut UniquePtr<file_t>,
+            name: &str,
+            opts: &OpenOptions,
+            error: &mut i32,
+            fd: &mut i32,
+        ) -> VoidFuture;
 
         unsafe fn read_dma(
             file: &UniquePtr<file_t>,
@@ -37,6 +84,8 @@ mod ffi {
             pos: u64,
         ) -> IntFuture;
 
+        unsafe fn read_dma_iov(file: &UniquePtr<file_t>, pos: u64, iov: &[io_vec]) -> IntFuture;
+
         unsafe fn write_dma(
             file: &UniquePtr<file_t>,
             buffer: *mut u8,
@@ -44,23 +93,57 @@ mod ffi {
             pos: u64,
         ) -> IntFuture;
 
+        unsafe fn write_dma_iov(file: &UniquePtr<file_t>, pos: u64, iov: &[io_vec]) -> IntFuture;
+
         fn flush(file: &UniquePtr<file_t>) -> VoidFuture;
 
         fn close(file: &UniquePtr<file_t>) -> VoidFuture;
 
+        fn dup(
+            file: &UniquePtr<file_t>,
+            fd: &mut i32,
+            error: &mut i32,
+        ) -> Result<UniquePtr<file_t>>;
+
         fn size(file: &UniquePtr<file_t>) -> IntFuture;
+
+        fn fadvise(fd: i32, pos: u64, len: u64, hint: Hint) -> i32;
+
+        fn make_input_stream(file: &UniquePtr<file_t>) -> UniquePtr<input_stream>;
+
+        fn make_output_stream(
+            file: &UniquePtr<file_t>,
+            buffer_size: usize,
+            output: &mut UniquePtr<output_stream>,
+        ) -> VoidFuture;
     }
 }
 
+pub use ffi::Hint;
+
 const ALIGN: usize = 512;
 const CHUNK_SIZE: usize = 4096;
 
+/// Error returned when a [`DmaBuffer`] doesn't meet the requirements of direct I/O.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum DmaError {
+    /// The length of the buffer isn't a multiple of its alignment.
+    #[error("DmaError: length {len} is not a multiple of {alignment} bytes")]
+    UnalignedLength { len: usize, alignment: usize },
+}
+
 /// A buffer that stores/receives data for I/O operations.
-/// Its contents are aligned in memory up to 512 bytes.
-/// read_dma and write_dma require memory to be aligned.
+/// Its contents are aligned in memory up to 512 bytes and the underlying
+/// allocation is always a multiple of the DMA chunk size (4096 bytes),
+/// as read_dma and write_dma on files opened with direct I/O require it.
 pub struct DmaBuffer {
     buffer: *mut u8,
     size: usize,
+    // Number of bytes visible through the slice accessors. Never bigger than `size`.
+    len: usize,
+    // Capacity of the `Vec` the buffer was taken from, if it was created with `from_vec`.
+    // Such buffers must be freed by `Vec` as they were allocated with a different layout.
+    vec_capacity: Option<usize>,
 }
 
 impl Deref for DmaBuffer {
@@ -73,9 +156,16 @@ impl Deref for DmaBuffer {
 
 impl Drop for DmaBuffer {
     fn drop(&mut self) {
-        let layout = Layout::from_size_align(self.size, ALIGN).unwrap();
-        unsafe {
-            alloc::dealloc(self.buffer, layout);
+        match self.vec_capacity {
+            Some(capacity) => unsafe {
+                drop(Vec::from_raw_parts(self.buffer, self.size, capacity));
+            },
+            None => {
+                let layout = Layout::from_size_align(self.size, ALIGN).unwrap();
+                unsafe {
+                    alloc::dealloc(self.buffer, layout);
+                }
+            }
         }
     }
 }
@@ -94,16 +184,195 @@ impl IndexMut<usize> for DmaBuffer {
     }
 }
 
+/// Copies the buffer into a new aligned allocation of the same size,
+/// so the clone can be used for DMA right away.
+impl Clone for DmaBuffer {
+    fn clone(&self) -> Self {
+        let mut clone = Self::new_zeroed(self.size);
+        clone.len = self.len;
+        unsafe {
+            std::ptr::copy_nonoverlapping(self.buffer, clone.buffer, self.size);
+        }
+        clone
+    }
+}
+
+/// Compares the visible contents of the buffers, ignoring the padding.
+impl PartialEq for DmaBuffer {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl Eq for DmaBuffer {}
+
+/// Prints the length of the buffer and a hex preview of its first bytes.
+impl fmt::Debug for DmaBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const PREVIEW_LEN: usize = 16;
+        write!(f, "DmaBuffer {{ len: {}, data: ", self.len)?;
+        for byte in self.iter().take(PREVIEW_LEN) {
+            write!(f, "{:02x}", byte)?;
+        }
+        if self.len > PREVIEW_LEN {
+            write!(f, "...")?;
+        }
+        write!(f, " }}")
+    }
+}
+
+/// Iterates over the visible contents of the buffer, without the padding.
+impl IntoIterator for DmaBuffer {
+    type Item = u8;
+    type IntoIter = DmaBufferIntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        DmaBufferIntoIter {
+            buffer: self,
+            pos: 0,
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a DmaBuffer {
+    type Item = &'a u8;
+    type IntoIter = std::slice::Iter<'a, u8>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.as_slice().iter()
+    }
+}
+
+/// An iterator moving the bytes out of a [`DmaBuffer`].
+///
+/// Created by [`DmaBuffer::into_iter`]. The buffer is freed when the iterator is dropped.
+pub struct DmaBufferIntoIter {
+    buffer: DmaBuffer,
+    pos: usize,
+}
+
+impl Iterator for DmaBufferIntoIter {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let byte = *self.buffer.as_slice().get(self.pos)?;
+        self.pos += 1;
+        Some(byte)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.buffer.len - self.pos;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for DmaBufferIntoIter {}
+
+/// Collects bytes into a buffer without an intermediate allocation.
+///
+/// Like with [`DmaBuffer::from_slice`], the underlying allocation is rounded up
+/// to the DMA chunk size and zero-padded.
+impl FromIterator<u8> for DmaBuffer {
+    fn from_iter<I: IntoIterator<Item = u8>>(iter: I) -> Self {
+        let iter = iter.into_iter();
+        let mut buffer = Self::new_zeroed(iter.size_hint().0);
+        buffer.len = 0;
+        for byte in iter {
+            if buffer.len == buffer.size {
+                buffer.grow();
+            }
+            unsafe {
+                *buffer.buffer.add(buffer.len) = byte;
+            }
+            buffer.len += 1;
+        }
+        buffer
+    }
+}
+
 impl DmaBuffer {
-    pub fn from_slice(bytes: &[u8]) -> Self {
-        let size = bytes.len();
-        assert!(size % CHUNK_SIZE == 0);
+    // Allocates a zeroed buffer of `len` bytes.
+    // The allocation itself is rounded up to a non-zero multiple of `CHUNK_SIZE`.
+    pub(crate) fn new_zeroed(len: usize) -> Self {
+        let size = len.div_ceil(CHUNK_SIZE).max(1) * CHUNK_SIZE;
         let layout = Layout::from_size_align(size, ALIGN).unwrap();
-        unsafe {
-            let buffer = alloc::alloc_zeroed(layout);
-            let slice = std::slice::from_raw_parts_mut(buffer, size);
-            slice.copy_from_slice(bytes);
-            Self { buffer, size }
+        let buffer = unsafe { alloc::alloc_zeroed(layout) };
+        if buffer.is_null() {
+            alloc::handle_alloc_error(layout);
+        }
+        Self {
+            buffer,
+            size,
+            len,
+            vec_capacity: None,
+        }
+    }
+
+    /// Creates a buffer holding a copy of `bytes`.
+    ///
+    /// `bytes` may have any length, but files opened with direct I/O only write buffers
+    /// whose length is a multiple of 512 bytes. Use [`DmaBuffer::pad_to_alignment`]
+    /// to write other buffers to such files.
+    pub fn from_slice(bytes: &[u8]) -> Self {
+        let mut buffer = Self::new_zeroed(bytes.len());
+        buffer.as_mut_slice().copy_from_slice(bytes);
+        buffer
+    }
+
+    /// Creates a buffer holding a copy of `bytes`, which can be written as is to files
+    /// opened with direct I/O.
+    ///
+    /// Fails with [`DmaError::UnalignedLength`] if the length of `bytes` isn't a multiple
+    /// of the buffer alignment (512 bytes). To write such data anyway, create the buffer
+    /// with [`DmaBuffer::from_slice`] and zero-pad it with [`DmaBuffer::pad_to_alignment`].
+    pub fn try_from_slice(bytes: &[u8]) -> Result<Self, DmaError> {
+        if bytes.len() % ALIGN != 0 {
+            return Err(DmaError::UnalignedLength {
+                len: bytes.len(),
+                alignment: ALIGN,
+            });
+        }
+        Ok(Self::from_slice(bytes))
+    }
+
+    /// Creates a buffer taking ownership of `vec` without copying its contents.
+    ///
+    /// It is only possible if `vec`'s data is aligned to 512 bytes and its length
+    /// is a multiple of the DMA chunk size (4096 bytes). Otherwise, `vec` is returned
+    /// back in the `Err` variant.
+    pub fn from_vec(vec: Vec<u8>) -> Result<Self, Vec<u8>> {
+        if vec.as_ptr() as usize % ALIGN != 0 || vec.is_empty() || vec.len() % CHUNK_SIZE != 0 {
+            return Err(vec);
+        }
+
+        let mut vec = std::mem::ManuallyDrop::new(vec);
+        Ok(Self {
+            buffer: vec.as_mut_ptr(),
+            size: vec.len(),
+            len: vec.len(),
+            vec_capacity: Some(vec.capacity()),
+        })
+    }
+
+    /// Copies the contents of the buffer into a new `Vec`.
+    pub fn to_vec(&self) -> Vec<u8> {
+        self.as_slice().to_vec()
+    }
+
+    /// Converts the buffer into a `Vec` holding its contents.
+    ///
+    /// Buffers created with [`DmaBuffer::from_vec`] are converted without copying.
+    /// Other buffers use an aligned allocation that `Vec` can't own, so their contents
+    /// are copied and the aligned allocation is freed.
+    pub fn into_vec(self) -> Vec<u8> {
+        match self.vec_capacity {
+            Some(capacity) => {
+                let this = std::mem::ManuallyDrop::new(self);
+                let mut vec = unsafe { Vec::from_raw_parts(this.buffer, this.size, capacity) };
+                vec.truncate(this.len);
+                vec
+            }
+            None => self.to_vec(),
         }
     }
 
@@ -113,11 +382,88 @@ impl DmaBuffer {
     }
 
     pub fn as_mut_slice(&mut self) -> &mut [u8] {
-        unsafe { std::slice::from_raw_parts_mut(self.buffer, self.size) }
+        unsafe { std::slice::from_raw_parts_mut(self.buffer, self.len) }
     }
 
     pub fn as_slice(&self) -> &[u8] {
-        unsafe { std::slice::from_raw_parts(self.buffer, self.size) }
+        unsafe { std::slice::from_raw_parts(self.buffer, self.len) }
+    }
+
+    /// Returns the number of bytes the buffer can hold, i.e. the size of its underlying allocation.
+    ///
+    /// It's the length rounded up to the DMA chunk size (4096 bytes), unless the buffer
+    /// was created with [`DmaBuffer::from_vec`] or its length was decreased.
+    pub fn capacity(&self) -> usize {
+        self.size
+    }
+
+    /// Sets the number of visible bytes of the buffer.
+    ///
+    /// The underlying allocation is always initialized, so increasing the length exposes
+    /// the zeroed padding or bytes which were hidden before, e.g. by [`File::read_dma`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len` is bigger than the buffer's capacity.
+    pub fn set_len(&mut self, len: usize) {
+        assert!(
+            len <= self.size,
+            "length {} exceeds the capacity {}",
+            len,
+            self.size
+        );
+        self.len = len;
+    }
+
+    /// Extends the buffer with zeros up to the next multiple of its alignment (512 bytes).
+    ///
+    /// Files opened with direct I/O only write buffers whose length is such a multiple,
+    /// so this is needed to write a buffer of any other length to them. The zeros are
+    /// written to the file too. Does nothing if the length is already a multiple.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use seastar::DmaBuffer;
+    ///
+    /// let mut buffer = DmaBuffer::from_slice(b"hello");
+    /// buffer.pad_to_alignment();
+    /// assert_eq!(buffer.len(), 512);
+    /// assert_eq!(&buffer[..6], b"hello\0");
+    /// ```
+    pub fn pad_to_alignment(&mut self) {
+        // The allocation is a multiple of `CHUNK_SIZE`, so the padded length always fits.
+        let len = self.len.next_multiple_of(ALIGN);
+        unsafe {
+            std::ptr::write_bytes(self.buffer.add(self.len), 0, len - self.len);
+        }
+        self.len = len;
+    }
+
+    /// Returns the alignment (in bytes) of the buffer's contents in memory.
+    ///
+    /// It is the same for all buffers, regardless of how they were created.
+    pub fn alignment(&self) -> usize {
+        ALIGN
+    }
+
+    // Makes a buffer allocated like `new_zeroed(len)` hold `len` bytes again, keeping its contents.
+    // Returns false, leaving the buffer untouched, if it was allocated differently.
+    pub(crate) fn reset_len(&mut self, len: usize) -> bool {
+        let size = len.div_ceil(CHUNK_SIZE).max(1) * CHUNK_SIZE;
+        if self.vec_capacity.is_some() || self.size != size {
+            return false;
+        }
+        self.len = len;
+        true
+    }
+
+    // Doubles the size of the allocation, keeping the contents.
+    fn grow(&mut self) {
+        let mut grown = Self::new_zeroed(self.size * 2);
+        grown.len = self.len;
+        grown.as_mut_slice().copy_from_slice(self.as_slice());
+        *self = grown;
     }
 }
 
@@ -128,6 +474,10 @@ pub struct OpenOptions {
     read: bool,
     write: bool,
     create: bool,
+    truncate: bool,
+    append: bool,
+    create_new: bool,
+    dma: bool,
 }
 
 impl Default for OpenOptions {
@@ -137,12 +487,16 @@ impl Default for OpenOptions {
 }
 
 impl OpenOptions {
-    /// Creates a new `OpenOptions` struct with no flag set.
+    /// Creates a new `OpenOptions` struct with no flag set, except for `dma`.
     pub fn new() -> Self {
         Self {
             read: false,
             write: false,
             create: false,
+            truncate: false,
+            append: false,
+            create_new: false,
+            dma: true,
         }
     }
 
@@ -164,6 +518,38 @@ impl OpenOptions {
         self
     }
 
+    /// Sets a flag `truncate` which truncates an existing file to 0 length when opening it.
+    /// The file must be opened with write access for it to work.
+    pub fn truncate(&mut self, flag: bool) -> &mut Self {
+        self.truncate = flag;
+        self
+    }
+
+    /// Sets a flag `append` which makes writes append to the end of a file
+    /// regardless of the position they are given.
+    pub fn append(&mut self, flag: bool) -> &mut Self {
+        self.append = flag;
+        self
+    }
+
+    /// Sets a flag `create_new` which creates a new file and fails if it already exists.
+    ///
+    /// If set, `create` and `truncate` are ignored.
+    pub fn create_new(&mut self, flag: bool) -> &mut Self {
+        self.create_new = flag;
+        self
+    }
+
+    /// Sets a flag `dma` which makes the file use direct I/O (`O_DIRECT`). Set by default.
+    ///
+    /// Some filesystems (e.g. tmpfs) don't support direct I/O. Files opened
+    /// without this flag go through the page cache instead and their reads
+    /// and writes don't have any alignment requirements.
+    pub fn dma(&mut self, flag: bool) -> &mut Self {
+        self.dma = flag;
+        self
+    }
+
     /// Getter for a `read` flag.
     pub fn get_read(&self) -> bool {
         self.read
@@ -179,52 +565,216 @@ impl OpenOptions {
         self.create
     }
 
+    /// Getter for a `truncate` flag.
+    pub fn get_truncate(&self) -> bool {
+        self.truncate
+    }
+
+    /// Getter for an `append` flag.
+    pub fn get_append(&self) -> bool {
+        self.append
+    }
+
+    /// Getter for a `create_new` flag.
+    pub fn get_create_new(&self) -> bool {
+        self.create_new
+    }
+
+    /// Getter for a `dma` flag.
+    pub fn get_dma(&self) -> bool {
+        self.dma
+    }
+
     /// Opens a new file `path` from the OpenOptions set before.
     pub async fn open<P: AsRef<Path>>(&self, path: P) -> io::Result<File> {
         File::new(&self.clone(), path.as_ref()).await
     }
 }
 
+/// An [`InputStream`] reading a [`File`] sequentially.
+pub type FileInputStream = InputStream;
+
+/// An [`OutputStream`] writing a [`File`] sequentially.
+pub type FileOutputStream = OutputStream;
+
 pub struct File {
     inner: UniquePtr<file_t>,
+    dma: bool,
+    // The descriptor of a file opened without direct I/O, owned by `inner`.
+    buffered_fd: Option<i32>,
 }
 
 impl File {
     /// Creates a new file with `opts` OpenOptions and `path` path to a file.
     ///
-    /// Returns a file.
+    /// Returns a file. Errors reported by the operating system are translated
+    /// to the matching [`io::ErrorKind`] (e.g. [`io::ErrorKind::NotFound`]).
     pub async fn new(opts: &OpenOptions, path: &Path) -> io::Result<File> {
         assert_runtime_is_running();
         let mut f_ptr = UniquePtr::null();
         let name = path.to_str().unwrap();
-        let res = open_dma(&mut f_ptr, name, opts).await;
+        let mut error = 0;
+        let mut fd = -1;
+        let res = if opts.dma {
+            open_dma(&mut f_ptr, name, opts, &mut error).await
+        } else {
+            open_buffered(&mut f_ptr, name, opts, &mut error, &mut fd).await
+        };
         match res {
-            Ok(_) => Ok(File { inner: f_ptr }),
-            Err(_) => Err(io::Error::new(io::ErrorKind::Other, "No read permission")),
+            Ok(_) => Ok(File {
+                inner: f_ptr,
+                dma: opts.dma,
+                buffered_fd: (!opts.dma).then_some(fd),
+            }),
+            Err(_) if error != 0 => Err(io::Error::from_raw_os_error(error)),
+            Err(e) => Err(io::Error::new(io::ErrorKind::Other, e)),
         }
     }
 
+    /// Opens the file at `path` with `opts` OpenOptions.
+    ///
+    /// Equivalent to `opts.open(path)`. See [`File::new`] for how errors are reported.
+    pub async fn open_with_options<P: AsRef<Path>>(
+        path: P,
+        opts: &OpenOptions,
+    ) -> io::Result<File> {
+        File::new(opts, path.as_ref()).await
+    }
+
     /// Read some bytes at given position.
     ///
-    /// Returns the number of bytes read and the original buffer.
+    /// Files opened with direct I/O fill the whole underlying allocation of the buffer,
+    /// `pos` must be aligned then. Other files fill only its visible length.
+    ///
+    /// Returns the number of bytes read and the original buffer, whose length is set
+    /// to the number of bytes read. Use [`DmaBuffer::set_len`] to read into it again.
     pub async fn read_dma(
         &self,
-        buffer: DmaBuffer,
+        mut buffer: DmaBuffer,
         pos: u64,
     ) -> Result<(usize, DmaBuffer), io::Error> {
         assert_runtime_is_running();
-        let size = buffer.size as u64;
+        let size = self.transfer_size(&buffer) as u64;
         unsafe {
             let fut = read_dma(&self.inner, buffer.buffer, size, pos);
             match fut.await {
-                Ok(res) => Ok((res as usize, buffer)),
+                Ok(res) => {
+                    buffer.len = res as usize;
+                    Ok((res as usize, buffer))
+                }
                 Err(e) => Err(io::Error::new(io::ErrorKind::Other, e)),
             }
         }
     }
 
+    /// Reads some bytes at given position into multiple buffers (scatter read),
+    /// filling them in order, in a single operation.
+    ///
+    /// Saves issuing one giant allocation for large sequential reads.
+    /// Each buffer is filled like with [`File::read_dma`], so for files opened with
+    /// direct I/O `pos` must be aligned.
+    ///
+    /// Returns the total number of bytes read and the original buffers. The bytes read are
+    /// distributed among the buffers in order: the length of each buffer is set to the number
+    /// of bytes read into it, so the buffers after the end of the file are empty.
+    ///
+    /// Equivalent of the iovec-based `seastar::file::dma_read`.
+    ///
+    /// # Safety
+    ///
+    /// The returned future must be polled to completion. The read is performed by Seastar
+    /// in the background and can't be cancelled, so if the future is dropped early,
+    /// the read may still write into the buffers after they are freed.
+    pub async unsafe fn read_dma_iov(
+        &self,
+        pos: u64,
+        mut buffers: Vec<DmaBuffer>,
+    ) -> io::Result<(usize, Vec<DmaBuffer>)> {
+        assert_runtime_is_running();
+        let iov: Vec<io_vec> = buffers
+            .iter()
+            .map(|buffer| io_vec {
+                base: buffer.buffer as usize,
+                len: self.transfer_size(buffer),
+            })
+            .collect();
+        match read_dma_iov(&self.inner, pos, &iov).await {
+            Ok(res) => {
+                let mut remaining = res as usize;
+                for (buffer, io_vec) in buffers.iter_mut().zip(&iov) {
+                    buffer.len = remaining.min(io_vec.len);
+                    remaining -= buffer.len;
+                }
+                Ok((res as usize, buffers))
+            }
+            Err(e) => Err(io::Error::new(io::ErrorKind::Other, e)),
+        }
+    }
+
+    /// Reads some bytes at given position directly into `buf`.
+    ///
+    /// Meant for callers managing their own aligned memory (e.g. buffer pools),
+    /// as it avoids passing the ownership of a [`DmaBuffer`] back and forth.
+    ///
+    /// Returns the number of bytes read.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `buf` is not aligned to 512 bytes. For files opened with direct I/O,
+    /// its length must be a multiple of 512 bytes too.
+    ///
+    /// # Safety
+    ///
+    /// The returned future must be polled to completion. The read is performed by Seastar
+    /// in the background and can't be cancelled, so if the future is dropped early,
+    /// the read may still write into `buf` after it's no longer borrowed.
+    pub async unsafe fn dma_read_into(&self, buf: &mut [u8], pos: u64) -> io::Result<usize> {
+        assert_runtime_is_running();
+        assert!(
+            buf.as_ptr() as usize % ALIGN == 0,
+            "buffer must be aligned to {} bytes",
+            ALIGN
+        );
+        if self.dma {
+            assert!(
+                buf.len() % ALIGN == 0,
+                "buffer length must be a multiple of {} bytes",
+                ALIGN
+            );
+        }
+        match read_dma(&self.inner, buf.as_mut_ptr(), buf.len() as u64, pos).await {
+            Ok(res) => Ok(res as usize),
+            Err(e) => Err(io::Error::new(io::ErrorKind::Other, e)),
+        }
+    }
+
+    /// Reads up to `len` bytes at given position into a freshly allocated buffer.
+    ///
+    /// The buffer is allocated with `len` rounded up to the DMA chunk size and its
+    /// visible length is truncated to the number of bytes actually read, so reading
+    /// past the end of the file returns a shorter buffer. `pos` must be aligned
+    /// to the file's DMA alignment.
+    ///
+    /// Equivalent of `seastar::file::dma_read_bulk`.
+    ///
+    /// # Safety
+    ///
+    /// The returned future must be polled to completion. The read is performed by Seastar
+    /// in the background and can't be cancelled, so if the future is dropped early,
+    /// the read may still write into the buffer after it's freed.
+    pub async unsafe fn dma_read_bulk(&self, pos: u64, len: usize) -> io::Result<DmaBuffer> {
+        let (read, mut buffer) = self.read_dma(DmaBuffer::new_zeroed(len), pos).await?;
+        buffer.len = read.min(len);
+        Ok(buffer)
+    }
+
     /// Writes some bytes at given position.
     ///
+    /// The visible length of the buffer is written. For files opened with direct I/O
+    /// it must be a multiple of 512 bytes (see [`DmaBuffer::pad_to_alignment`]),
+    /// otherwise an error of kind [`io::ErrorKind::InvalidInput`] is returned,
+    /// and `pos` must be aligned.
+    ///
     /// Returns the number of bytes writted and the original buffer.
     pub async fn write_dma(
         &self,
@@ -232,7 +782,8 @@ impl File {
         pos: u64,
     ) -> Result<(usize, DmaBuffer), io::Error> {
         assert_runtime_is_running();
-        let size = buffer.size as u64;
+        self.check_write_len(&buffer)?;
+        let size = buffer.len as u64;
         unsafe {
             let fut = write_dma(&self.inner, buffer.buffer, size, pos);
             match fut.await {
@@ -242,6 +793,44 @@ impl File {
         }
     }
 
+    /// Writes multiple buffers at given position (gather write), one after another,
+    /// in a single operation.
+    ///
+    /// Useful for writing e.g. a header and a body without concatenating them first.
+    /// Each buffer is written like with [`File::write_dma`], so for files opened with
+    /// direct I/O their lengths must be multiples of 512 bytes and `pos` must be aligned.
+    ///
+    /// Returns the total number of bytes written and the original buffers.
+    ///
+    /// Equivalent of the iovec-based `seastar::file::dma_write`.
+    ///
+    /// # Safety
+    ///
+    /// The returned future must be polled to completion. The write is performed by Seastar
+    /// in the background and can't be cancelled, so if the future is dropped early,
+    /// the write may still read from the buffers after they are freed.
+    pub async unsafe fn write_dma_iov(
+        &self,
+        pos: u64,
+        buffers: Vec<DmaBuffer>,
+    ) -> io::Result<(usize, Vec<DmaBuffer>)> {
+        assert_runtime_is_running();
+        for buffer in &buffers {
+            self.check_write_len(buffer)?;
+        }
+        let iov: Vec<io_vec> = buffers
+            .iter()
+            .map(|buffer| io_vec {
+                base: buffer.buffer as usize,
+                len: buffer.len,
+            })
+            .collect();
+        match write_dma_iov(&self.inner, pos, &iov).await {
+            Ok(res) => Ok((res as usize, buffers)),
+            Err(e) => Err(io::Error::new(io::ErrorKind::Other, e)),
+        }
+    }
+
     /// Causes any previously written data to be made stable on presistent storage.
     /// After a flush, data is guaranteed to be on disk.
     pub async fn flush(&self) -> Result<(), io::Error> {
@@ -261,6 +850,117 @@ impl File {
         }
     }
 
+    /// Creates a new, independent handle to the same open file.
+    ///
+    /// Both handles can be used concurrently, e.g. by separate readers, and each of them
+    /// has to be closed separately. The underlying file is closed once all of them are.
+    ///
+    /// Equivalent of `seastar::file::dup`.
+    pub fn dup(&self) -> io::Result<File> {
+        assert_runtime_is_running();
+        let mut fd = self.buffered_fd.unwrap_or(-1);
+        let mut error = 0;
+        match dup(&self.inner, &mut fd, &mut error) {
+            Ok(inner) => Ok(File {
+                inner,
+                dma: self.dma,
+                buffered_fd: self.buffered_fd.map(|_| fd),
+            }),
+            Err(_) if error != 0 => Err(io::Error::from_raw_os_error(error)),
+            Err(e) => Err(io::Error::new(io::ErrorKind::Other, e)),
+        }
+    }
+
+    /// Creates a writer writing buffers to the file sequentially from the beginning,
+    /// tracking the offset of the next write.
+    ///
+    /// Unlike [`File::output_stream`], buffers are written with [`File::write_dma`],
+    /// without copying them into the stream's buffer.
+    pub fn writer(&self) -> DmaWriter<'_> {
+        DmaWriter { file: self, pos: 0 }
+    }
+
+    /// Creates a stream reading the file sequentially from the beginning.
+    ///
+    /// Equivalent of `seastar::make_file_input_stream`.
+    pub fn input_stream(&self) -> FileInputStream {
+        assert_runtime_is_running();
+        InputStream::new(make_input_stream(&self.inner))
+    }
+
+    /// Creates a stream writing the file sequentially from the beginning.
+    ///
+    /// The stream must be closed with [`OutputStream::close`] to make sure
+    /// all buffered data is written.
+    ///
+    /// Equivalent of `seastar::make_file_output_stream`.
+    pub async fn output_stream(&self) -> io::Result<FileOutputStream> {
+        self.make_output_stream(0).await
+    }
+
+    /// Creates a stream writing the file sequentially from the beginning,
+    /// buffering at most `buffer_size` bytes before writing them to the file.
+    ///
+    /// For files opened with direct I/O, `buffer_size` should be a multiple of 4096.
+    ///
+    /// See [`File::output_stream`].
+    pub async fn output_stream_with_buffer_size(
+        &self,
+        buffer_size: usize,
+    ) -> io::Result<FileOutputStream> {
+        assert!(buffer_size > 0, "buffer_size must be positive");
+        self.make_output_stream(buffer_size).await
+    }
+
+    // A `buffer_size` of 0 stands for Seastar's default.
+    async fn make_output_stream(&self, buffer_size: usize) -> io::Result<FileOutputStream> {
+        assert_runtime_is_running();
+        let mut output = UniquePtr::null();
+        match make_output_stream(&self.inner, buffer_size, &mut output).await {
+            Ok(_) => Ok(OutputStream::new(output)),
+            Err(e) => Err(io::Error::new(io::ErrorKind::Other, e)),
+        }
+    }
+
+    // Direct I/O requires transfers of whole chunks, other files can use any length.
+    fn transfer_size(&self, buffer: &DmaBuffer) -> usize {
+        if self.dma {
+            buffer.size
+        } else {
+            buffer.len
+        }
+    }
+
+    // Direct I/O requires aligned lengths. Buffers are not padded implicitly,
+    // as the padding would end up in the file.
+    fn check_write_len(&self, buffer: &DmaBuffer) -> io::Result<()> {
+        if self.dma && buffer.len % ALIGN != 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                DmaError::UnalignedLength {
+                    len: buffer.len,
+                    alignment: ALIGN,
+                },
+            ));
+        }
+        Ok(())
+    }
+
+    /// Tells the kernel how the region of `len` bytes starting at `pos` is going to be accessed,
+    /// so it can tune readahead and caching of the file. A `len` of 0 stands for the end of the file.
+    ///
+    /// Wraps `posix_fadvise`. Files opened with direct I/O bypass the page cache,
+    /// so the hint has no effect on them and `Ok(())` is returned right away.
+    pub fn hint_access(&self, pos: u64, len: u64, hint: Hint) -> io::Result<()> {
+        let Some(fd) = self.buffered_fd else {
+            return Ok(());
+        };
+        match fadvise(fd, pos, len, hint) {
+            0 => Ok(()),
+            error => Err(io::Error::from_raw_os_error(error)),
+        }
+    }
+
     /// Returns the number of bytes in a file.
     pub async fn size(&self) -> Result<i32, io::Error> {
         assert_runtime_is_running();
@@ -271,6 +971,67 @@ impl File {
     }
 }
 
+/// Writes buffers to a [`File`] one after another, tracking the offset of the next write.
+///
+/// Created with [`File::writer`].
+pub struct DmaWriter<'a> {
+    file: &'a File,
+    pos: u64,
+}
+
+impl DmaWriter<'_> {
+    /// Writes the whole `buffer` at the current offset and advances it past the buffer.
+    ///
+    /// Buffers are written like with [`File::write_dma`]: files opened with direct I/O only
+    /// take lengths which are multiples of 512 bytes, which keeps the offset aligned.
+    /// Short writes are retried with the rest of the buffer. If a write makes no progress,
+    /// an error of kind [`io::ErrorKind::WriteZero`] is returned.
+    ///
+    /// On error, the offset is still advanced by the bytes written before it.
+    ///
+    /// Returns the original buffer.
+    ///
+    /// # Safety
+    ///
+    /// The returned future must be polled to completion. The writes are performed by Seastar
+    /// in the background and can't be cancelled, so if the future is dropped early,
+    /// a write may still read from the buffer after it's freed.
+    pub async unsafe fn write(&mut self, buffer: DmaBuffer) -> io::Result<DmaBuffer> {
+        assert_runtime_is_running();
+        self.file.check_write_len(&buffer)?;
+        let mut written = 0;
+        while written < buffer.len {
+            // With direct I/O, the rest of the buffer can only be written from an aligned offset.
+            if self.file.dma && written % ALIGN != 0 {
+                return Err(io::Error::new(
+                    io::ErrorKind::Other,
+                    format!(
+                        "unaligned short write of {written} bytes to a file opened with direct I/O"
+                    ),
+                ));
+            }
+            let rest = (buffer.len - written) as u64;
+            let res = write_dma(&self.file.inner, buffer.buffer.add(written), rest, self.pos)
+                .await
+                .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+            if res == 0 {
+                return Err(io::Error::new(
+                    io::ErrorKind::WriteZero,
+                    "failed to write the whole buffer",
+                ));
+            }
+            written += res as usize;
+            self.pos += res as u64;
+        }
+        Ok(buffer)
+    }
+
+    /// Returns the offset at which the next buffer will be written.
+    pub fn position(&self) -> u64 {
+        self.pos
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use rand::Rng;
@@ -293,28 +1054,247 @@ mod tests {
         p
     }
 
-    #[seastar::test]
-    async fn test_file_read_dma() {
-        let p = rand_path();
-        let msg = b"I <3 seastar!";
-        std::fs::OpenOptions::new()
-            .create(true)
-            .write(true)
-            .open(p.as_path())
-            .unwrap()
-            .write_all(msg)
-            .unwrap();
-        let file = OpenOptions::new()
-            .read(true)
-            .open(p.as_path())
-            .await
+    // The allocator is not obliged to return aligned memory, so we allocate until it does.
+    fn aligned_vec(byte: u8, len: usize) -> Vec<u8> {
+        let mut unaligned = Vec::new();
+        loop {
+            let vec = vec![byte; len];
+            if vec.as_ptr() as usize % ALIGN == 0 {
+                return vec;
+            }
+            unaligned.push(vec);
+            assert!(
+                unaligned.len() < 10000,
+                "couldn't get an aligned allocation"
+            );
+        }
+    }
+
+    #[test]
+    fn test_dma_buffer_from_vec_accepted() {
+        let vec = aligned_vec(0, CHUNK_SIZE);
+        let ptr = vec.as_ptr();
+        let buffer = DmaBuffer::from_vec(vec).unwrap();
+        assert_eq!(buffer.as_slice().as_ptr(), ptr);
+        assert_eq!(buffer.len(), CHUNK_SIZE);
+    }
+
+    #[test]
+    fn test_dma_buffer_from_iter() {
+        let byte = |i: usize| (i % 251) as u8;
+
+        // The exact length is known up front.
+        let buffer: DmaBuffer = (0..5000).map(byte).collect();
+        assert_eq!(buffer.len(), 5000);
+        assert_eq!(buffer.size, 2 * CHUNK_SIZE);
+        assert!(buffer.iter().enumerate().all(|(i, &b)| b == byte(i)));
+        let padding = unsafe { std::slice::from_raw_parts(buffer.buffer, buffer.size) };
+        assert!(padding[5000..].iter().all(|&b| b == 0));
+
+        // The buffer has to grow while collecting.
+        let buffer: DmaBuffer = (0..10000).filter(|_| true).map(byte).collect();
+        assert_eq!(buffer.len(), 10000);
+        assert_eq!(buffer.size % CHUNK_SIZE, 0);
+        assert!(buffer.iter().enumerate().all(|(i, &b)| b == byte(i)));
+        let padding = unsafe { std::slice::from_raw_parts(buffer.buffer, buffer.size) };
+        assert!(padding[10000..].iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn test_dma_buffer_eq_and_debug() {
+        let bytes: Vec<u8> = (0..20).collect();
+        let buffer = DmaBuffer::from_slice(&bytes);
+        assert_eq!(buffer, DmaBuffer::from_slice(&bytes));
+        assert_ne!(buffer, DmaBuffer::from_slice(&bytes[..19]));
+        assert_eq!(
+            format!("{:?}", buffer),
+            "DmaBuffer { len: 20, data: 000102030405060708090a0b0c0d0e0f... }"
+        );
+        assert_eq!(
+            format!("{:?}", DmaBuffer::from_slice(b"ab")),
+            "DmaBuffer { len: 2, data: 6162 }"
+        );
+    }
+
+    #[test]
+    fn test_dma_buffer_from_slice_pads_unaligned_length() {
+        let buffer = DmaBuffer::from_slice(&[42u8; 1000]);
+        assert_eq!(buffer.len(), 1000);
+        assert_eq!(buffer.alignment(), ALIGN);
+        assert_eq!(buffer.as_slice().as_ptr() as usize % buffer.alignment(), 0);
+        assert_eq!(buffer.size, CHUNK_SIZE);
+        let padding = unsafe { std::slice::from_raw_parts(buffer.buffer, buffer.size) };
+        assert!(padding[..1000].iter().all(|&b| b == 42));
+        assert!(padding[1000..].iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn test_dma_buffer_try_from_slice() {
+        let buffer = DmaBuffer::try_from_slice(&[42u8; 2 * ALIGN]).unwrap();
+        assert_eq!(buffer.len(), 2 * ALIGN);
+        assert_eq!(buffer.capacity(), CHUNK_SIZE);
+        assert!(buffer.iter().all(|&b| b == 42));
+
+        let err = DmaBuffer::try_from_slice(&[42u8; 1000]).unwrap_err();
+        assert_eq!(
+            err,
+            DmaError::UnalignedLength {
+                len: 1000,
+                alignment: ALIGN
+            }
+        );
+    }
+
+    #[test]
+    fn test_dma_buffer_clone() {
+        let buffer = DmaBuffer::from_slice(b"I <3 seastar!");
+        let mut clone = buffer.clone();
+        assert_eq!(clone, buffer);
+        assert_eq!(clone.size, buffer.size);
+        clone[0] = b'U';
+        assert_eq!(buffer.as_slice(), b"I <3 seastar!");
+        assert_eq!(clone.as_slice(), b"U <3 seastar!");
+        assert_eq!(buffer.as_slice().as_ptr() as usize % ALIGN, 0);
+        assert_eq!(clone.as_slice().as_ptr() as usize % ALIGN, 0);
+    }
+
+    #[test]
+    fn test_dma_buffer_into_iter() {
+        let bytes: Vec<u8> = (0..100).collect();
+        let buffer = DmaBuffer::from_slice(&bytes);
+        assert_eq!((&buffer).into_iter().count(), 100);
+        let mut sum = 0u32;
+        for byte in &buffer {
+            sum += *byte as u32;
+        }
+        assert_eq!(sum, (0..100).sum());
+
+        // The padding after the visible contents isn't iterated over.
+        let iter = buffer.into_iter();
+        assert_eq!(iter.len(), 100);
+        assert_eq!(iter.collect::<Vec<_>>(), bytes);
+    }
+
+    #[test]
+    fn test_dma_buffer_from_vec_rejected() {
+        let vec = vec![42u8; CHUNK_SIZE - 1];
+        let vec = DmaBuffer::from_vec(vec).unwrap_err();
+        assert_eq!(vec, vec![42u8; CHUNK_SIZE - 1]);
+        assert!(DmaBuffer::from_vec(Vec::new()).is_err());
+    }
+
+    #[seastar::test]
+    async fn test_file_read_dma() {
+        let p = rand_path();
+        let msg = b"I <3 seastar!";
+        std::fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .open(p.as_path())
+            .unwrap()
+            .write_all(msg)
+            .unwrap();
+        let file = OpenOptions::new()
+            .read(true)
+            .open(p.as_path())
+            .await
             .unwrap();
         let buffer = DmaBuffer::from_slice(&[0u8; CHUNK_SIZE]);
         let res = file.read_dma(buffer, 0).await.unwrap();
         file.close().await.unwrap();
         assert_eq!(res.0, msg.len());
-        let bytes = &res.1.as_slice()[..msg.len()];
-        assert_eq!(bytes, msg);
+        assert_eq!(res.1.as_slice(), msg);
+    }
+
+    #[seastar::test]
+    async fn test_file_read_dma_sets_len() {
+        let p = rand_path();
+        let msg = (0..CHUNK_SIZE + 100)
+            .map(|i| (i % 251) as u8)
+            .collect::<Vec<u8>>();
+        std::fs::write(p.as_path(), &msg).unwrap();
+        let file = OpenOptions::new()
+            .read(true)
+            .open(p.as_path())
+            .await
+            .unwrap();
+
+        let buffer = DmaBuffer::from_slice(&[0u8; CHUNK_SIZE * 2]);
+        let (read, mut buffer) = file.read_dma(buffer, 0).await.unwrap();
+        assert_eq!(read, msg.len());
+        assert_eq!(buffer.as_slice().len(), read);
+        assert_eq!(buffer.capacity(), CHUNK_SIZE * 2);
+        assert_eq!(buffer.as_slice(), msg.as_slice());
+
+        // The buffer can be reused for reading after restoring its length.
+        buffer.set_len(buffer.capacity());
+        let (read, buffer) = file.read_dma(buffer, CHUNK_SIZE as u64).await.unwrap();
+        file.close().await.unwrap();
+        assert_eq!(read, 100);
+        assert_eq!(buffer.as_slice(), &msg[CHUNK_SIZE..]);
+    }
+
+    #[test]
+    fn test_dma_buffer_set_len() {
+        let mut buffer = DmaBuffer::from_slice(b"abc");
+        assert_eq!(buffer.capacity(), CHUNK_SIZE);
+        buffer.set_len(1);
+        assert_eq!(buffer.as_slice(), b"a");
+        buffer.set_len(5);
+        assert_eq!(buffer.as_slice(), b"abc\0\0");
+        assert!(std::panic::catch_unwind(move || buffer.set_len(CHUNK_SIZE + 1)).is_err());
+    }
+
+    #[test]
+    fn test_dma_buffer_into_vec_from_vec() {
+        let vec = aligned_vec(7, CHUNK_SIZE);
+        let ptr = vec.as_ptr();
+        let vec = DmaBuffer::from_vec(vec).unwrap().into_vec();
+        assert_eq!(vec.as_ptr(), ptr);
+        assert_eq!(vec, vec![7u8; CHUNK_SIZE]);
+    }
+
+    #[seastar::test]
+    async fn test_file_read_dma_into_vec() {
+        let p = rand_path();
+        let msg = b"I <3 seastar!";
+        std::fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .open(p.as_path())
+            .unwrap()
+            .write_all(msg)
+            .unwrap();
+        let file = OpenOptions::new()
+            .read(true)
+            .open(p.as_path())
+            .await
+            .unwrap();
+        let buffer = unsafe { file.dma_read_bulk(0, CHUNK_SIZE).await.unwrap() };
+        file.close().await.unwrap();
+        assert_eq!(buffer.to_vec(), msg);
+        assert_eq!(buffer.into_vec(), msg);
+    }
+
+    #[seastar::test]
+    async fn test_file_dma_read_into() {
+        let p = rand_path();
+        let msg = b"I <3 seastar!";
+        std::fs::write(p.as_path(), msg).unwrap();
+        let file = OpenOptions::new()
+            .read(true)
+            .open(p.as_path())
+            .await
+            .unwrap();
+
+        // Align a part of a bigger allocation manually.
+        let mut memory = vec![0u8; 2 * CHUNK_SIZE];
+        let offset = memory.as_ptr().align_offset(ALIGN);
+        let buf = &mut memory[offset..offset + CHUNK_SIZE];
+        let read = unsafe { file.dma_read_into(buf, 0).await.unwrap() };
+        file.close().await.unwrap();
+        assert_eq!(read, msg.len());
+        assert_eq!(&buf[..read], msg);
     }
 
     #[seastar::test]
@@ -339,8 +1319,95 @@ mod tests {
         let res = file.read_dma(buffer, 0).await.unwrap();
         file.close().await.unwrap();
         assert_eq!(res.0, msg.len());
-        let bytes = &res.1.as_slice()[..msg.len()];
-        assert_eq!(bytes, msg.as_slice());
+        assert_eq!(res.1.as_slice(), msg.as_slice());
+    }
+
+    #[seastar::test]
+    async fn test_file_dma_read_bulk() {
+        let p = rand_path();
+        let msg = b"I <3 seastar!";
+        std::fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .open(p.as_path())
+            .unwrap()
+            .write_all(msg)
+            .unwrap();
+        let file = OpenOptions::new()
+            .read(true)
+            .open(p.as_path())
+            .await
+            .unwrap();
+        let buffer = unsafe { file.dma_read_bulk(0, CHUNK_SIZE).await.unwrap() };
+        file.close().await.unwrap();
+        assert_eq!(buffer.len(), msg.len());
+        assert_eq!(buffer.as_slice(), msg);
+    }
+
+    #[seastar::test]
+    async fn test_file_read_dma_iov() {
+        let p = rand_path();
+        let msg = (0..2 * CHUNK_SIZE)
+            .map(|_| rand::random::<u8>())
+            .collect::<Vec<u8>>();
+        std::fs::write(&p, &msg).unwrap();
+        let file = OpenOptions::new()
+            .read(true)
+            .open(p.as_path())
+            .await
+            .unwrap();
+        let buffers = vec![
+            DmaBuffer::from_slice(&[0; CHUNK_SIZE]),
+            DmaBuffer::from_slice(&[0; CHUNK_SIZE]),
+        ];
+        let (read, buffers) = unsafe { file.read_dma_iov(0, buffers).await.unwrap() };
+        file.close().await.unwrap();
+        assert_eq!(read, msg.len());
+        assert_eq!([buffers[0].as_slice(), buffers[1].as_slice()].concat(), msg);
+    }
+
+    #[seastar::test]
+    async fn test_file_read_dma_iov_sets_lengths() {
+        let p = rand_path();
+        let msg = (0..CHUNK_SIZE + 100)
+            .map(|_| rand::random::<u8>())
+            .collect::<Vec<u8>>();
+        std::fs::write(&p, &msg).unwrap();
+        let file = OpenOptions::new()
+            .read(true)
+            .open(p.as_path())
+            .await
+            .unwrap();
+        let buffers = (0..3)
+            .map(|_| DmaBuffer::from_slice(&[0; CHUNK_SIZE]))
+            .collect();
+        let (read, buffers) = unsafe { file.read_dma_iov(0, buffers).await.unwrap() };
+        file.close().await.unwrap();
+        assert_eq!(read, msg.len());
+        let lens: Vec<_> = buffers.iter().map(|buffer| buffer.len()).collect();
+        assert_eq!(lens, vec![CHUNK_SIZE, 100, 0]);
+        assert_eq!([buffers[0].as_slice(), buffers[1].as_slice()].concat(), msg);
+    }
+
+    #[seastar::test]
+    async fn test_file_write_dma_iov() {
+        let p = rand_path();
+        let mut header = [0u8; CHUNK_SIZE];
+        let mut body = [0u8; CHUNK_SIZE];
+        rand::thread_rng().fill(&mut header[..]);
+        rand::thread_rng().fill(&mut body[..]);
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .open(p.as_path())
+            .await
+            .unwrap();
+        let buffers = vec![DmaBuffer::from_slice(&header), DmaBuffer::from_slice(&body)];
+        let (written, _) = unsafe { file.write_dma_iov(0, buffers).await.unwrap() };
+        file.flush().await.unwrap();
+        file.close().await.unwrap();
+        assert_eq!(written, 2 * CHUNK_SIZE);
+        assert_eq!(std::fs::read(&p).unwrap(), [header, body].concat());
     }
 
     #[seastar::test]
@@ -370,6 +1437,92 @@ mod tests {
         assert_eq!(bytes, line.as_slice());
     }
 
+    #[seastar::test]
+    async fn test_file_writer() {
+        let p = rand_path();
+        let chunks: Vec<_> = (0..3)
+            .map(|_| {
+                let mut chunk = [0u8; CHUNK_SIZE];
+                rand::thread_rng().fill(&mut chunk[..]);
+                chunk
+            })
+            .collect();
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .dma(false)
+            .open(p.as_path())
+            .await
+            .unwrap();
+        let mut writer = file.writer();
+        for chunk in &chunks {
+            let buffer = unsafe { writer.write(DmaBuffer::from_slice(chunk)).await.unwrap() };
+            assert_eq!(&buffer[..], &chunk[..]);
+        }
+        assert_eq!(writer.position(), 3 * CHUNK_SIZE as u64);
+
+        // Files opened without direct I/O take buffers of any length.
+        unsafe { writer.write(DmaBuffer::from_slice(b"tail")).await.unwrap() };
+        assert_eq!(writer.position(), 3 * CHUNK_SIZE as u64 + 4);
+        file.flush().await.unwrap();
+        file.close().await.unwrap();
+        assert_eq!(
+            std::fs::read(&p).unwrap(),
+            [chunks.concat(), b"tail".to_vec()].concat()
+        );
+    }
+
+    #[test]
+    fn test_dma_buffer_pad_to_alignment() {
+        let mut buffer = DmaBuffer::from_slice(&[42u8; 1000]);
+        buffer.pad_to_alignment();
+        assert_eq!(buffer.len(), 1024);
+        assert!(buffer[..1000].iter().all(|&b| b == 42));
+        assert!(buffer[1000..].iter().all(|&b| b == 0));
+        buffer.pad_to_alignment();
+        assert_eq!(buffer.len(), 1024);
+
+        // Padding overwrites hidden contents.
+        let mut buffer = DmaBuffer::from_slice(&[42u8; ALIGN]);
+        buffer.set_len(1);
+        buffer.pad_to_alignment();
+        assert_eq!(buffer.len(), ALIGN);
+        assert!(buffer[1..].iter().all(|&b| b == 0));
+    }
+
+    #[seastar::test]
+    async fn test_file_write_dma_unaligned_length() {
+        let p = rand_path();
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .open(p.as_path())
+            .await
+            .unwrap();
+        let err = file
+            .write_dma(DmaBuffer::from_slice(&[1u8; 1000]), 0)
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        let buffers = vec![
+            DmaBuffer::from_slice(&[1u8; ALIGN]),
+            DmaBuffer::from_slice(&[1u8; 1000]),
+        ];
+        let err = unsafe { file.write_dma_iov(0, buffers).await.unwrap_err() };
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert_eq!(std::fs::metadata(&p).unwrap().len(), 0);
+
+        let mut buffer = DmaBuffer::from_slice(&[1u8; 1000]);
+        buffer.pad_to_alignment();
+        let (written, _) = file.write_dma(buffer, 0).await.unwrap();
+        file.flush().await.unwrap();
+        file.close().await.unwrap();
+        assert_eq!(written, 1024);
+        let contents = std::fs::read(&p).unwrap();
+        assert_eq!(&contents[..1000], &[1u8; 1000]);
+        assert!(contents[1000..].iter().all(|&b| b == 0));
+    }
+
     #[seastar::test]
     async fn test_file_write_dma_big() {
         let p = rand_path();
@@ -397,6 +1550,111 @@ mod tests {
         assert_eq!(bytes, line.as_slice());
     }
 
+    #[seastar::test]
+    async fn test_file_streams() {
+        let p = rand_path();
+        let msg = (0..15000)
+            .map(|_| rand::random::<u8>())
+            .collect::<Vec<u8>>();
+
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .open(p.as_path())
+            .await
+            .unwrap();
+        let mut output = file.output_stream().await.unwrap();
+        output.write(&msg[..10000]).await.unwrap();
+        output.write(&msg[10000..]).await.unwrap();
+        output.close().await.unwrap();
+
+        let file = OpenOptions::new()
+            .read(true)
+            .open(p.as_path())
+            .await
+            .unwrap();
+        let mut input = file.input_stream();
+        let mut read = Vec::new();
+        loop {
+            let buffer = input.read().await.unwrap();
+            if buffer.is_empty() {
+                break;
+            }
+            read.extend_from_slice(&buffer);
+        }
+        input.close().await.unwrap();
+        file.close().await.unwrap();
+        assert_eq!(read, msg);
+    }
+
+    #[seastar::test]
+    async fn test_file_line_reader() {
+        let p = rand_path();
+        std::fs::write(p.as_path(), b"first line\nsecond line\nlast line").unwrap();
+        let file = OpenOptions::new()
+            .read(true)
+            .open(p.as_path())
+            .await
+            .unwrap();
+        let mut reader = crate::LineReader::new(file.input_stream());
+        let mut lines = Vec::new();
+        while let Some(line) = reader.read_line().await.unwrap() {
+            lines.push(line);
+        }
+        reader.into_inner().close().await.unwrap();
+        file.close().await.unwrap();
+        assert_eq!(lines, ["first line", "second line", "last line"]);
+    }
+
+    #[seastar::test]
+    async fn test_output_stream_write_all_through_small_buffer() {
+        let p = rand_path();
+        let msg = (0..1_000_123)
+            .map(|_| rand::random::<u8>())
+            .collect::<Vec<u8>>();
+
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .open(p.as_path())
+            .await
+            .unwrap();
+        let mut output = file.output_stream_with_buffer_size(4096).await.unwrap();
+        output.write_all_and_flush(&msg).await.unwrap();
+        output.write_all(&msg[..10]).await.unwrap();
+        output.close().await.unwrap();
+
+        assert_eq!(std::fs::read(&p).unwrap(), [&msg[..], &msg[..10]].concat());
+    }
+
+    #[seastar::test]
+    async fn test_file_dup() {
+        let p = rand_path();
+        let data: Vec<u8> = (0..2 * CHUNK_SIZE).map(|i| (i % 251) as u8).collect();
+        std::fs::write(&p, &data).unwrap();
+        let file = OpenOptions::new()
+            .read(true)
+            .open(p.as_path())
+            .await
+            .unwrap();
+        let dup = file.dup().unwrap();
+
+        let (first, second) = unsafe {
+            futures::join!(
+                file.dma_read_bulk(0, CHUNK_SIZE),
+                dup.dma_read_bulk(CHUNK_SIZE as u64, CHUNK_SIZE)
+            )
+        };
+        assert_eq!(first.unwrap().as_slice(), &data[..CHUNK_SIZE]);
+        assert_eq!(second.unwrap().as_slice(), &data[CHUNK_SIZE..]);
+
+        // Closing one handle doesn't affect the other.
+        file.close().await.unwrap();
+        let buffer = unsafe { dup.dma_read_bulk(0, CHUNK_SIZE).await.unwrap() };
+        assert_eq!(buffer.as_slice(), &data[..CHUNK_SIZE]);
+        dup.close().await.unwrap();
+    }
+
     #[seastar::test]
     async fn test_file_close() {
         let p = rand_path();
@@ -424,4 +1682,127 @@ mod tests {
         file.close().await.unwrap();
         assert_eq!(size as usize, msg.len());
     }
+
+    #[seastar::test]
+    async fn test_file_buffered_read_write() {
+        let p = rand_path();
+        let msg = b"I <3 seastar!";
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .dma(false)
+            .open(p.as_path())
+            .await
+            .unwrap();
+        let (written, _) = file.write_dma(DmaBuffer::from_slice(msg), 0).await.unwrap();
+        assert_eq!(written, msg.len());
+        file.flush().await.unwrap();
+        assert_eq!(file.size().await.unwrap() as usize, msg.len());
+
+        let (read, buffer) = file
+            .read_dma(DmaBuffer::from_slice(&[0u8; 5]), 3)
+            .await
+            .unwrap();
+        file.close().await.unwrap();
+        assert_eq!(read, 5);
+        assert_eq!(buffer.as_slice(), &msg[3..8]);
+        assert_eq!(std::fs::read(p.as_path()).unwrap(), msg);
+    }
+
+    #[seastar::test]
+    async fn test_file_hint_access() {
+        let p = rand_path();
+        std::fs::write(p.as_path(), [42u8; 8192]).unwrap();
+        let file = OpenOptions::new()
+            .read(true)
+            .dma(false)
+            .open(p.as_path())
+            .await
+            .unwrap();
+        file.hint_access(0, 8192, Hint::Sequential).unwrap();
+        file.hint_access(4096, 0, Hint::WillNeed).unwrap();
+        let (read, buffer) = file
+            .read_dma(DmaBuffer::from_slice(&[0u8; 4]), 0)
+            .await
+            .unwrap();
+        file.close().await.unwrap();
+        assert_eq!(read, 4);
+        assert_eq!(buffer.as_slice(), &[42u8; 4]);
+    }
+
+    #[seastar::test]
+    async fn test_file_open_not_found() {
+        let p = rand_path();
+        let err = OpenOptions::new()
+            .read(true)
+            .open(p.as_path())
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    }
+
+    #[seastar::test]
+    async fn test_file_open_permission_denied() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let p = rand_path();
+        std::fs::write(p.as_path(), b"I <3 seastar!").unwrap();
+        std::fs::set_permissions(p.as_path(), std::fs::Permissions::from_mode(0o000)).unwrap();
+        if std::fs::File::open(p.as_path()).is_ok() {
+            // Privileged users (e.g. root) ignore file permissions.
+            return;
+        }
+
+        let mut opts = OpenOptions::new();
+        opts.read(true);
+        let err = File::open_with_options(p.as_path(), &opts)
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+    }
+
+    #[seastar::test]
+    async fn test_file_create_new_existing() {
+        let p = rand_path();
+        std::fs::write(p.as_path(), b"I <3 seastar!").unwrap();
+        let err = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(p.as_path())
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+    }
+
+    #[seastar::test]
+    async fn test_file_create_new() {
+        let p = rand_path();
+        let file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(p.as_path())
+            .await
+            .unwrap();
+        file.close().await.unwrap();
+        assert!(p.exists());
+    }
+
+    #[seastar::test]
+    async fn test_file_truncate() {
+        let p = rand_path();
+        std::fs::write(p.as_path(), b"I <3 seastar!").unwrap();
+        let file = OpenOptions::new()
+            .write(true)
+            .truncate(true)
+            .open(p.as_path())
+            .await
+            .unwrap();
+        assert_eq!(file.size().await.unwrap(), 0);
+        file.close().await.unwrap();
+        assert!(std::fs::read(p.as_path()).unwrap().is_empty());
+    }
 }
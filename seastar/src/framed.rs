@@ -0,0 +1,341 @@
+use crate::{InputStream, OutputStream};
+use std::io;
+
+/// Translates between a stream of bytes and a sequence of frames (messages).
+///
+/// Used by [`Framed`] to read frames from an [`InputStream`] and write them to an [`OutputStream`].
+pub trait Codec {
+    /// The type of the frames.
+    type Item;
+
+    /// Decodes a frame from the beginning of `buf`, removing the bytes it was decoded from.
+    ///
+    /// Returns `None` if `buf` doesn't contain a whole frame yet. In that case, more data
+    /// is appended to `buf` and `decode` is called again.
+    fn decode(&mut self, buf: &mut Vec<u8>) -> io::Result<Option<Self::Item>>;
+
+    /// Decodes a frame once the end of the stream is reached.
+    ///
+    /// Called repeatedly until it returns `None`. By default it calls [`Codec::decode`],
+    /// and reports an error of kind [`io::ErrorKind::UnexpectedEof`] if bytes not forming
+    /// a whole frame are left in `buf`.
+    fn decode_eof(&mut self, buf: &mut Vec<u8>) -> io::Result<Option<Self::Item>> {
+        match self.decode(buf)? {
+            None if !buf.is_empty() => Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "bytes remaining at the end of the stream",
+            )),
+            item => Ok(item),
+        }
+    }
+
+    /// Encodes `item`, appending it to `buf`.
+    fn encode(&mut self, item: Self::Item, buf: &mut Vec<u8>) -> io::Result<()>;
+}
+
+/// A [`Codec`] for newline-delimited UTF-8 lines.
+///
+/// Decoded lines don't contain the trailing newline (`\n` or `\r\n`). The last line
+/// of the stream doesn't have to be terminated by a newline. Lines that are not valid UTF-8
+/// result in an error of kind [`io::ErrorKind::InvalidData`].
+///
+/// Encoded lines are terminated with `\n`.
+///
+/// The length of decoded lines can be limited with [`LinesCodec::new_with_max_length`].
+#[derive(Clone, Debug)]
+pub struct LinesCodec {
+    // Number of bytes at the beginning of the buffer known not to contain a newline.
+    searched: usize,
+    max_length: usize,
+    // Whether the rest of a line exceeding `max_length` is being skipped.
+    discarding: bool,
+}
+
+impl Default for LinesCodec {
+    fn default() -> Self {
+        Self::new_with_max_length(usize::MAX)
+    }
+}
+
+impl LinesCodec {
+    /// Creates a new codec, decoding lines of any length.
+    ///
+    /// Lines are buffered until their newline is read, so a peer which never sends one
+    /// makes the buffer grow without bounds. Use [`LinesCodec::new_with_max_length`] for
+    /// untrusted input.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new codec, decoding lines of at most `max_length` bytes (without the newline).
+    ///
+    /// A longer line results in an error of kind [`io::ErrorKind::InvalidData`], and the rest of it
+    /// is skipped without being buffered. Decoding then continues from the next line.
+    pub fn new_with_max_length(max_length: usize) -> Self {
+        Self {
+            searched: 0,
+            max_length,
+            discarding: false,
+        }
+    }
+
+    /// Returns the maximum length of the decoded lines.
+    pub fn max_length(&self) -> usize {
+        self.max_length
+    }
+
+    fn into_string(line: Vec<u8>) -> io::Result<String> {
+        String::from_utf8(line).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+impl Codec for LinesCodec {
+    type Item = String;
+
+    fn decode(&mut self, buf: &mut Vec<u8>) -> io::Result<Option<String>> {
+        loop {
+            // A line of `max_length` bytes may be followed by its newline.
+            let read_to = buf.len().min(self.max_length.saturating_add(1));
+            let newline = buf[self.searched..read_to]
+                .iter()
+                .position(|&byte| byte == b'\n');
+
+            match (self.discarding, newline) {
+                (true, Some(pos)) => {
+                    buf.drain(..=self.searched + pos);
+                    self.searched = 0;
+                    self.discarding = false;
+                }
+                (true, None) => {
+                    buf.drain(..read_to);
+                    self.searched = 0;
+                    if buf.is_empty() {
+                        return Ok(None);
+                    }
+                }
+                (false, Some(pos)) => {
+                    let mut line: Vec<u8> = buf.drain(..=self.searched + pos).collect();
+                    self.searched = 0;
+                    line.pop();
+                    if line.last() == Some(&b'\r') {
+                        line.pop();
+                    }
+                    return Self::into_string(line).map(Some);
+                }
+                (false, None) if buf.len() > self.max_length => {
+                    self.discarding = true;
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "line length limit exceeded",
+                    ));
+                }
+                (false, None) => {
+                    self.searched = read_to;
+                    return Ok(None);
+                }
+            }
+        }
+    }
+
+    fn decode_eof(&mut self, buf: &mut Vec<u8>) -> io::Result<Option<String>> {
+        if let Some(line) = self.decode(buf)? {
+            return Ok(Some(line));
+        }
+        if buf.is_empty() {
+            return Ok(None);
+        }
+        self.searched = 0;
+        Self::into_string(std::mem::take(buf)).map(Some)
+    }
+
+    fn encode(&mut self, item: String, buf: &mut Vec<u8>) -> io::Result<()> {
+        buf.reserve(item.len() + 1);
+        buf.extend_from_slice(item.as_bytes());
+        buf.push(b'\n');
+        Ok(())
+    }
+}
+
+/// An adapter reading or writing frames, defined by a [`Codec`], on a stream of bytes.
+///
+/// Over an [`InputStream`], the chunks returned by the stream don't have to be aligned
+/// with frames, so partially read frames are buffered internally. Over an [`OutputStream`],
+/// frames are encoded into an internal buffer and then written to the stream.
+///
+/// # Examples
+///
+/// ```rust
+/// use seastar::{Framed, InputStream, LinesCodec};
+///
+/// async fn print_lines(input: InputStream) -> std::io::Result<()> {
+///     let mut lines = Framed::new(input, LinesCodec::new());
+///     while let Some(line) = lines.next().await? {
+///         println!("{line}");
+///     }
+///     Ok(())
+/// }
+/// ```
+pub struct Framed<S, C> {
+    stream: S,
+    codec: C,
+    buffer: Vec<u8>,
+    eof: bool,
+}
+
+impl<S, C: Codec> Framed<S, C> {
+    /// Creates an adapter over `stream`, using `codec` to translate the frames.
+    pub fn new(stream: S, codec: C) -> Self {
+        Self {
+            stream,
+            codec,
+            buffer: Vec::new(),
+            eof: false,
+        }
+    }
+
+    /// Returns a reference to the underlying stream.
+    pub fn get_ref(&self) -> &S {
+        &self.stream
+    }
+
+    /// Returns a mutable reference to the underlying stream.
+    ///
+    /// Reading from or writing to it directly may corrupt the frames.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.stream
+    }
+
+    /// Returns a reference to the codec.
+    pub fn codec(&self) -> &C {
+        &self.codec
+    }
+
+    /// Returns a mutable reference to the codec.
+    pub fn codec_mut(&mut self) -> &mut C {
+        &mut self.codec
+    }
+
+    /// Returns the underlying stream. Data buffered by the adapter is lost.
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
+
+impl<C: Codec> Framed<InputStream, C> {
+    /// Reads the next frame from the stream.
+    ///
+    /// Returns `None` when the end of the stream is reached and all frames were read.
+    pub async fn next(&mut self) -> io::Result<Option<C::Item>> {
+        loop {
+            if self.eof {
+                return self.codec.decode_eof(&mut self.buffer);
+            }
+            if let Some(item) = self.codec.decode(&mut self.buffer)? {
+                return Ok(Some(item));
+            }
+
+            let chunk = self.stream.read().await?;
+            if chunk.is_empty() {
+                self.eof = true;
+            } else {
+                self.buffer.extend_from_slice(&chunk);
+            }
+        }
+    }
+}
+
+impl<C: Codec> Framed<OutputStream, C> {
+    /// Writes `item` to the stream, without flushing it.
+    ///
+    /// The frame may stay buffered by the stream until it's flushed or closed.
+    pub async fn feed(&mut self, item: C::Item) -> io::Result<()> {
+        self.codec.encode(item, &mut self.buffer)?;
+        let ret = self.stream.write_all(&self.buffer).await;
+        self.buffer.clear();
+        ret
+    }
+
+    /// Writes `item` to the stream and sends it to the underlying sink.
+    pub async fn send(&mut self, item: C::Item) -> io::Result<()> {
+        self.feed(item).await?;
+        self.stream.flush().await
+    }
+
+    /// Flushes and closes the stream.
+    ///
+    /// It must be called before the adapter is dropped.
+    pub async fn close(&mut self) -> io::Result<()> {
+        self.stream.close().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as seastar;
+    use crate::{connect, TcpListener};
+    use std::net::{Ipv4Addr, SocketAddr};
+
+    #[test]
+    fn test_lines_codec() {
+        let mut codec = LinesCodec::new();
+        let mut buf = b"first\r\nsec".to_vec();
+        assert_eq!(codec.decode(&mut buf).unwrap().unwrap(), "first");
+        assert!(codec.decode(&mut buf).unwrap().is_none());
+        buf.extend_from_slice(b"ond\nlast");
+        assert_eq!(codec.decode(&mut buf).unwrap().unwrap(), "second");
+        assert!(codec.decode(&mut buf).unwrap().is_none());
+        assert_eq!(codec.decode_eof(&mut buf).unwrap().unwrap(), "last");
+        assert!(codec.decode_eof(&mut buf).unwrap().is_none());
+
+        codec.encode("encoded".to_string(), &mut buf).unwrap();
+        assert_eq!(buf, b"encoded\n");
+
+        let mut invalid = vec![0xff, b'\n'];
+        let err = codec.decode(&mut invalid).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_lines_codec_max_length() {
+        let mut codec = LinesCodec::new_with_max_length(4);
+        assert_eq!(codec.max_length(), 4);
+
+        let mut buf = b"four\nfive".to_vec();
+        assert_eq!(codec.decode(&mut buf).unwrap().unwrap(), "four");
+        assert!(codec.decode(&mut buf).unwrap().is_none());
+        buf.extend_from_slice(b"!!");
+        let err = codec.decode(&mut buf).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        // The rest of the long line is skipped as it arrives, without being buffered.
+        buf.extend_from_slice(b"more of the long line");
+        assert!(codec.decode(&mut buf).unwrap().is_none());
+        assert!(buf.is_empty());
+        buf.extend_from_slice(b"\nok\n");
+        assert_eq!(codec.decode(&mut buf).unwrap().unwrap(), "ok");
+        assert!(codec.decode_eof(&mut buf).unwrap().is_none());
+    }
+
+    #[seastar::test]
+    async fn test_framed_lines() {
+        let listener = TcpListener::bind(SocketAddr::from((Ipv4Addr::LOCALHOST, 0))).unwrap();
+        let connection = connect(listener.local_addr()).await.unwrap();
+        let (accepted, _) = listener.accept().await.unwrap();
+
+        let mut sink = Framed::new(connection.output(), LinesCodec::new());
+        let messages = ["STORE$key$value", "", "LOAD$key"];
+        for message in messages {
+            sink.feed(message.to_string()).await.unwrap();
+        }
+        sink.send("bye".to_string()).await.unwrap();
+        sink.close().await.unwrap();
+
+        let mut source = Framed::new(accepted.input(), LinesCodec::new());
+        for message in messages {
+            assert_eq!(source.next().await.unwrap().unwrap(), message);
+        }
+        assert_eq!(source.next().await.unwrap().unwrap(), "bye");
+        assert!(source.next().await.unwrap().is_none());
+    }
+}
@@ -1,9 +1,12 @@
+use crate::{spawn, JoinHandle};
 use cxx::UniquePtr;
+use std::future::Future;
 use std::marker::PhantomData;
+use std::rc::Rc;
 use thiserror::Error;
 
 #[cxx::bridge(namespace = "seastar_ffi::gate")]
-mod ffi {
+pub(crate) mod ffi {
     unsafe extern "C++" {
         include!("seastar/src/gate.hh");
 
@@ -65,6 +68,42 @@ impl Gate {
         crate::assert_runtime_is_running();
         close_gate(&self.inner).await.unwrap();
     }
+
+    /// Enters the gate and spawns `fut` as a background task, which leaves the gate when it completes.
+    ///
+    /// Thus [`close`](Gate::close) waits for all tasks spawned this way, e.g. for all
+    /// in-flight connection handlers of a server that shuts down. The task keeps the gate alive,
+    /// which is why it has to be shared through an `Rc`.
+    ///
+    /// Returns [`GateClosedError`] without spawning anything if the gate is closed.
+    ///
+    /// This function must be called from the context of a Seastar runtime.
+    pub fn spawn<Fut>(self: &Rc<Self>, fut: Fut) -> Result<JoinHandle<Fut::Output>, GateClosedError>
+    where
+        Fut: Future + 'static,
+        Fut::Output: 'static,
+    {
+        let holder = self.enter_owned()?;
+        Ok(spawn(async move {
+            let _holder = holder;
+            fut.await
+        }))
+    }
+
+    // Enters the gate, returning a holder which keeps the gate alive.
+    pub(crate) fn enter_owned(self: &Rc<Self>) -> Result<OwnedGateHolder, GateClosedError> {
+        Ok(OwnedGateHolder {
+            _holder: new_gate_holder(&self.inner).map_err(|_| GateClosedError)?,
+            _gate: self.clone(),
+        })
+    }
+}
+
+// A gate holder owning a reference to its gate. The fields are dropped in the order
+// of declaration, so the gate is left before it can be destroyed.
+pub(crate) struct OwnedGateHolder {
+    _holder: UniquePtr<gate_holder>,
+    _gate: Rc<Gate>,
 }
 
 /// Facility to hold a gate opened using RAII.
@@ -170,6 +209,25 @@ mod tests {
         assert!(*closing_finished.borrow());
     }
 
+    #[seastar::test]
+    async fn test_gate_spawn_close_waits_for_tasks() {
+        let gate = Rc::new(Gate::new());
+        let finished = Rc::new(RefCell::new(0));
+
+        for i in 0..5 {
+            let finished = finished.clone();
+            gate.spawn(async move {
+                crate::sleep(crate::Duration::<crate::SteadyClock>::from_millis(i * 5)).await;
+                *finished.borrow_mut() += 1;
+            })
+            .unwrap();
+        }
+
+        gate.close().await;
+        assert_eq!(*finished.borrow(), 5);
+        assert!(gate.spawn(async {}).is_err());
+    }
+
     #[seastar::test]
     async fn test_gate_close_then_enter() {
         let gate = Gate::new();
@@ -0,0 +1,40 @@
+#include "iostream.hh"
+
+namespace seastar_ffi {
+namespace iostream {
+
+rust::Slice<const uint8_t> temporary_buffer_as_bytes(const temporary_buffer& buffer) {
+    return rust::Slice<const uint8_t>(reinterpret_cast<const uint8_t*>(buffer.get()), buffer.size());
+}
+
+VoidFuture read(const std::unique_ptr<input_stream>& input, std::unique_ptr<temporary_buffer>& buffer) {
+    temporary_buffer new_buffer = co_await input->read();
+    buffer = std::make_unique<temporary_buffer>(std::move(new_buffer));
+}
+
+VoidFuture read_exactly(const std::unique_ptr<input_stream>& input, size_t n, std::unique_ptr<temporary_buffer>& buffer) {
+    temporary_buffer new_buffer = co_await input->read_exactly(n);
+    buffer = std::make_unique<temporary_buffer>(std::move(new_buffer));
+}
+
+VoidFuture close_input_stream(const std::unique_ptr<input_stream>& input) {
+    co_await input->close();
+}
+
+VoidFuture write(const std::unique_ptr<output_stream>& output, rust::Slice<const uint8_t> data) {
+    // `output_stream::write` may keep reading the data after suspending, when the Rust future
+    // borrowing it may have been dropped. The copy is owned by the coroutine instead.
+    temporary_buffer copy(reinterpret_cast<const char*>(data.data()), data.size());
+    co_await output->write(std::move(copy));
+}
+
+VoidFuture flush(const std::unique_ptr<output_stream>& output) {
+    co_await output->flush();
+}
+
+VoidFuture close_output_stream(const std::unique_ptr<output_stream>& output) {
+    co_await output->close();
+}
+
+} // namespace iostream
+} // namespace seastar_ffi
@@ -0,0 +1,29 @@
+#pragma once
+
+#include "cxx_async_futures.hh"
+#include <seastar/core/iostream.hh>
+#include <seastar/core/temporary_buffer.hh>
+
+namespace seastar_ffi {
+namespace iostream {
+
+using input_stream = seastar::input_stream<char>;
+using output_stream = seastar::output_stream<char>;
+using temporary_buffer = seastar::temporary_buffer<char>;
+
+rust::Slice<const uint8_t> temporary_buffer_as_bytes(const temporary_buffer& buffer);
+
+VoidFuture read(const std::unique_ptr<input_stream>& input, std::unique_ptr<temporary_buffer>& buffer);
+
+VoidFuture read_exactly(const std::unique_ptr<input_stream>& input, size_t n, std::unique_ptr<temporary_buffer>& buffer);
+
+VoidFuture close_input_stream(const std::unique_ptr<input_stream>& input);
+
+VoidFuture write(const std::unique_ptr<output_stream>& output, rust::Slice<const uint8_t> data);
+
+VoidFuture flush(const std::unique_ptr<output_stream>& output);
+
+VoidFuture close_output_stream(const std::unique_ptr<output_stream>& output);
+
+} // namespace iostream
+} // namespace seastar_ffi
@@ -0,0 +1,343 @@
+use crate::{Framed, LinesCodec};
+use cxx::UniquePtr;
+use std::io;
+use std::ops::Deref;
+
+#[cxx::bridge]
+pub(crate) mod ffi {
+    #[namespace = "seastar_ffi"]
+    unsafe extern "C++" {
+        type VoidFuture = crate::cxx_async_futures::VoidFuture;
+    }
+
+    #[namespace = "seastar_ffi::iostream"]
+    unsafe extern "C++" {
+        include!("seastar/src/iostream.hh");
+
+        type input_stream;
+        type output_stream;
+        type temporary_buffer;
+
+        fn temporary_buffer_as_bytes(buffer: &temporary_buffer) -> &[u8];
+
+        fn read(
+            input: &UniquePtr<input_stream>,
+            buffer: &mut UniquePtr<temporary_buffer>,
+        ) -> VoidFuture;
+
+        fn read_exactly(
+            input: &UniquePtr<input_stream>,
+            n: usize,
+            buffer: &mut UniquePtr<temporary_buffer>,
+        ) -> VoidFuture;
+
+        fn close_input_stream(input: &UniquePtr<input_stream>) -> VoidFuture;
+
+        fn write(output: &UniquePtr<output_stream>, data: &[u8]) -> VoidFuture;
+
+        fn flush(output: &UniquePtr<output_stream>) -> VoidFuture;
+
+        fn close_output_stream(output: &UniquePtr<output_stream>) -> VoidFuture;
+    }
+}
+
+use ffi::*;
+
+/// A chunk of data returned by [`InputStream::read`].
+///
+/// Equivalent of `seastar::temporary_buffer<char>`. Dereferences to a byte slice.
+pub struct TemporaryBuffer {
+    inner: UniquePtr<temporary_buffer>,
+}
+
+impl TemporaryBuffer {
+    /// Returns the contents of the buffer.
+    pub fn as_slice(&self) -> &[u8] {
+        temporary_buffer_as_bytes(&self.inner)
+    }
+}
+
+impl Deref for TemporaryBuffer {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        self.as_slice()
+    }
+}
+
+/// A stream of bytes read in chunks.
+///
+/// Equivalent of `seastar::input_stream<char>`.
+pub struct InputStream {
+    inner: UniquePtr<input_stream>,
+}
+
+impl InputStream {
+    pub(crate) fn new(inner: UniquePtr<input_stream>) -> Self {
+        Self { inner }
+    }
+
+    /// Reads the next chunk of data from the stream.
+    ///
+    /// Returns an empty buffer when the end of the stream is reached.
+    pub async fn read(&mut self) -> io::Result<TemporaryBuffer> {
+        crate::assert_runtime_is_running();
+        let mut buffer = UniquePtr::null();
+        match read(&self.inner, &mut buffer).await {
+            Ok(_) => Ok(TemporaryBuffer { inner: buffer }),
+            Err(e) => Err(io::Error::new(io::ErrorKind::Other, e)),
+        }
+    }
+
+    /// Reads exactly `n` bytes from the stream, possibly across multiple chunks.
+    ///
+    /// Returns a shorter buffer if the end of the stream is reached first
+    /// (an empty one if it had already been reached).
+    /// Handy for binary protocols, e.g. reading fixed-size headers.
+    pub async fn read_exactly(&mut self, n: usize) -> io::Result<TemporaryBuffer> {
+        crate::assert_runtime_is_running();
+        let mut buffer = UniquePtr::null();
+        match read_exactly(&self.inner, n, &mut buffer).await {
+            Ok(_) => Ok(TemporaryBuffer { inner: buffer }),
+            Err(e) => Err(io::Error::new(io::ErrorKind::Other, e)),
+        }
+    }
+
+    /// Closes the stream.
+    pub async fn close(&mut self) -> io::Result<()> {
+        crate::assert_runtime_is_running();
+        match close_input_stream(&self.inner).await {
+            Ok(_) => Ok(()),
+            Err(e) => Err(io::Error::new(io::ErrorKind::Other, e)),
+        }
+    }
+}
+
+/// An adapter reading newline-delimited lines from an [`InputStream`].
+///
+/// Chunks returned by the stream don't have to be aligned with lines,
+/// so partially read lines are buffered internally.
+///
+/// A shorthand for [`Framed`] with a [`LinesCodec`].
+pub struct LineReader {
+    lines: Framed<InputStream, LinesCodec>,
+}
+
+impl LineReader {
+    /// Creates a reader consuming `input`, reading lines of any length.
+    pub fn new(input: InputStream) -> Self {
+        Self::with_codec(input, LinesCodec::new())
+    }
+
+    /// Creates a reader consuming `input`, reading lines of at most `max_length` bytes.
+    ///
+    /// See [`LinesCodec::new_with_max_length`] for how longer lines are handled.
+    pub fn with_max_length(input: InputStream, max_length: usize) -> Self {
+        Self::with_codec(input, LinesCodec::new_with_max_length(max_length))
+    }
+
+    fn with_codec(input: InputStream, codec: LinesCodec) -> Self {
+        Self {
+            lines: Framed::new(input, codec),
+        }
+    }
+
+    /// Reads the next line from the stream.
+    ///
+    /// The returned line doesn't contain the trailing newline (`\n` or `\r\n`).
+    /// The last line of the stream doesn't have to be terminated by a newline.
+    /// Returns `None` when the end of the stream is reached.
+    ///
+    /// Lines that are not valid UTF-8, or longer than the reader's maximum length,
+    /// result in an error of kind [`io::ErrorKind::InvalidData`].
+    pub async fn read_line(&mut self) -> io::Result<Option<String>> {
+        self.lines.next().await
+    }
+
+    /// Returns the underlying stream. Data buffered by the reader is lost.
+    pub fn into_inner(self) -> InputStream {
+        self.lines.into_inner()
+    }
+}
+
+/// A buffered stream of bytes.
+///
+/// Equivalent of `seastar::output_stream<char>`.
+///
+/// Written data is buffered internally, it's sent to the underlying sink
+/// when the buffer fills up or when the stream is flushed or closed.
+pub struct OutputStream {
+    inner: UniquePtr<output_stream>,
+}
+
+impl OutputStream {
+    pub(crate) fn new(inner: UniquePtr<output_stream>) -> Self {
+        Self { inner }
+    }
+
+    /// Writes `data` to the stream.
+    ///
+    /// The data is copied before the write starts, so the returned future may be dropped
+    /// before it completes without the write reading `data` afterwards.
+    pub async fn write(&mut self, data: &[u8]) -> io::Result<()> {
+        crate::assert_runtime_is_running();
+        match write(&self.inner, data).await {
+            Ok(_) => Ok(()),
+            Err(e) => Err(io::Error::new(io::ErrorKind::Other, e)),
+        }
+    }
+
+    /// Writes all of `data` to the stream.
+    ///
+    /// If the stream's buffer can't take all of `data` at once, it's written in parts,
+    /// waiting for the underlying sink to accept the previous ones (backpressure).
+    /// The data may still be buffered when this returns - see [`OutputStream::write_all_and_flush`].
+    pub async fn write_all(&mut self, data: &[u8]) -> io::Result<()> {
+        // `seastar::output_stream::write` resolves only once all of the data was accepted,
+        // splitting it into buffer-sized parts and waiting for the sink in between.
+        self.write(data).await
+    }
+
+    /// Writes all of `data` to the stream and sends it to the underlying sink.
+    pub async fn write_all_and_flush(&mut self, data: &[u8]) -> io::Result<()> {
+        self.write_all(data).await?;
+        self.flush().await
+    }
+
+    /// Sends all buffered data to the underlying sink.
+    pub async fn flush(&mut self) -> io::Result<()> {
+        crate::assert_runtime_is_running();
+        match flush(&self.inner).await {
+            Ok(_) => Ok(()),
+            Err(e) => Err(io::Error::new(io::ErrorKind::Other, e)),
+        }
+    }
+
+    /// Flushes and closes the stream.
+    ///
+    /// It must be called before the stream is dropped.
+    pub async fn close(&mut self) -> io::Result<()> {
+        crate::assert_runtime_is_running();
+        match close_output_stream(&self.inner).await {
+            Ok(_) => Ok(()),
+            Err(e) => Err(io::Error::new(io::ErrorKind::Other, e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as seastar;
+    use crate::{connect, OpenOptions, TcpListener};
+    use rand::Rng;
+    use std::net::{Ipv4Addr, SocketAddr};
+
+    async fn input_stream_for(contents: &[u8]) -> InputStream {
+        let fname: String = rand::thread_rng()
+            .sample_iter(&rand::distributions::Alphanumeric)
+            .take(10)
+            .map(char::from)
+            .collect();
+        let mut path = std::env::temp_dir();
+        path.push(fname);
+        std::fs::write(&path, contents).unwrap();
+        let file = OpenOptions::new().read(true).open(&path).await.unwrap();
+        file.input_stream()
+    }
+
+    async fn line_reader_for(contents: &[u8]) -> LineReader {
+        LineReader::new(input_stream_for(contents).await)
+    }
+
+    #[seastar::test]
+    async fn test_read_exactly_across_reads() {
+        // The body is long enough to span multiple chunks returned by the file stream.
+        let header = b"0123456789abcdef";
+        let body = vec![b'x'; 100_000];
+        let mut input = input_stream_for(&[&header[..], &body[..]].concat()).await;
+        assert_eq!(&*input.read_exactly(header.len()).await.unwrap(), header);
+        assert_eq!(&*input.read_exactly(body.len()).await.unwrap(), &body[..]);
+        assert!(input.read_exactly(1).await.unwrap().is_empty());
+        input.close().await.unwrap();
+    }
+
+    #[seastar::test]
+    async fn test_read_exactly_short_at_eof() {
+        let mut input = input_stream_for(b"short").await;
+        assert_eq!(&*input.read_exactly(16).await.unwrap(), b"short");
+        input.close().await.unwrap();
+    }
+
+    #[seastar::test]
+    async fn test_line_reader_multiple_lines_in_one_read() {
+        let mut reader = line_reader_for(b"first\nsecond\r\n\nfourth\n").await;
+        assert_eq!(reader.read_line().await.unwrap().unwrap(), "first");
+        assert_eq!(reader.read_line().await.unwrap().unwrap(), "second");
+        assert_eq!(reader.read_line().await.unwrap().unwrap(), "");
+        assert_eq!(reader.read_line().await.unwrap().unwrap(), "fourth");
+        assert!(reader.read_line().await.unwrap().is_none());
+        reader.into_inner().close().await.unwrap();
+    }
+
+    #[seastar::test]
+    async fn test_line_reader_line_split_across_reads() {
+        // Long enough to span multiple chunks returned by the file stream.
+        let long_line = "a".repeat(100_000);
+        let contents = format!("short\n{long_line}\nend\n");
+        let mut reader = line_reader_for(contents.as_bytes()).await;
+        assert_eq!(reader.read_line().await.unwrap().unwrap(), "short");
+        assert_eq!(reader.read_line().await.unwrap().unwrap(), long_line);
+        assert_eq!(reader.read_line().await.unwrap().unwrap(), "end");
+        assert!(reader.read_line().await.unwrap().is_none());
+        reader.into_inner().close().await.unwrap();
+    }
+
+    #[seastar::test]
+    async fn test_line_reader_trailing_line_without_newline() {
+        let mut reader = line_reader_for(b"first\nlast").await;
+        assert_eq!(reader.read_line().await.unwrap().unwrap(), "first");
+        assert_eq!(reader.read_line().await.unwrap().unwrap(), "last");
+        assert!(reader.read_line().await.unwrap().is_none());
+        reader.into_inner().close().await.unwrap();
+    }
+
+    #[seastar::test]
+    async fn test_line_reader_max_length() {
+        let long_line = "a".repeat(100_000);
+        let contents = format!("short\n{long_line}\nend\n");
+        let input = input_stream_for(contents.as_bytes()).await;
+        let mut reader = LineReader::with_max_length(input, 16);
+        assert_eq!(reader.read_line().await.unwrap().unwrap(), "short");
+        let err = reader.read_line().await.err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(reader.read_line().await.unwrap().unwrap(), "end");
+        assert!(reader.read_line().await.unwrap().is_none());
+        reader.into_inner().close().await.unwrap();
+    }
+
+    #[seastar::test]
+    async fn test_read_multibyte_character_split_across_reads() {
+        let listener = TcpListener::bind(SocketAddr::from((Ipv4Addr::LOCALHOST, 0))).unwrap();
+        let connection = connect(listener.local_addr()).await.unwrap();
+        let (accepted, _) = listener.accept().await.unwrap();
+        let (mut output, mut input) = (connection.output(), accepted.input());
+
+        let message = "zażółć";
+        // Splits 'ż' in half, so neither part is valid UTF-8 on its own.
+        let (first, second) = message.as_bytes().split_at(3);
+        assert!(std::str::from_utf8(first).is_err());
+
+        let mut received = Vec::new();
+        for part in [first, second] {
+            output.write_all_and_flush(part).await.unwrap();
+            // The next part isn't sent until this one is read, so it arrives in a separate chunk.
+            let buffer = input.read().await.unwrap();
+            assert_eq!(&*buffer, part);
+            received.extend_from_slice(&buffer);
+        }
+        output.close().await.unwrap();
+        assert!(input.read().await.unwrap().is_empty());
+        assert_eq!(std::str::from_utf8(&received).unwrap(), message);
+    }
+}
@@ -3,41 +3,83 @@
 //! Work in progress! Definitely not for use in production yet.
 
 mod api_safety;
+mod blocking_guard;
 mod clocks;
 mod config_and_start_seastar;
 mod cxx_async_futures;
 mod cxx_async_local_future;
 mod distributed;
+mod dma_buffer_pool;
 mod ffi_utils;
 mod file;
+mod framed;
 mod gate;
+mod iostream;
 mod logger;
+mod lru_cache;
+mod memory;
+pub mod metrics;
+mod mutex;
+mod net;
 
+mod periodic;
 mod preempt;
+mod queue;
+mod reactor;
+mod rwlock;
+mod scheduling_group;
 #[doc(hidden)]
 pub mod seastar_test_guard;
+mod semaphore;
+mod shard_local;
+mod sharded_map;
+mod shared_future;
 mod sleep;
-mod smp;
+pub mod smp;
 mod spawn;
 mod submit_to;
+pub mod thread;
 mod timer;
+mod when_all;
+mod with_timeout;
+mod work_queue;
 
 #[doc(hidden)]
-pub use seastar_test_guard::acquire_guard_for_seastar_test;
+pub use seastar_test_guard::{acquire_guard_for_seastar_test, assert_not_in_seastar_test};
 
 pub use api_safety::*;
+pub use blocking_guard::*;
 pub use clocks::*;
 pub use config_and_start_seastar::*;
 pub use distributed::*;
+pub use dma_buffer_pool::*;
 pub use file::*;
+pub use framed::*;
 pub use gate::*;
+pub use iostream::*;
 pub use logger::*;
+pub use lru_cache::*;
+pub use memory::*;
+pub use mutex::*;
+pub use net::*;
+pub use periodic::*;
 pub use preempt::*;
+pub use queue::*;
+pub use reactor::*;
+pub use rwlock::*;
+pub use scheduling_group::*;
+pub use semaphore::*;
+pub use shard_local::*;
+pub use sharded_map::*;
+pub use shared_future::*;
 pub use sleep::*;
 pub use smp::*;
 pub use spawn::*;
 pub use submit_to::*;
 pub use timer::*;
+pub use when_all::*;
+pub use with_timeout::*;
+pub use work_queue::*;
 
 /// A macro intended for running asynchronous tests.
 ///
@@ -1,4 +1,8 @@
 #include <algorithm>
+#include <iostream>
+#include <mutex>
+#include <streambuf>
+#include <string>
 
 #include "logger.hh"
 #include "seastar/src/logger.rs.h"
@@ -20,6 +24,57 @@ void log(const logger& l, uint32_t level, const FormatCtx& ctx) noexcept {
     const_cast<logger&>(l).log((seastar::log_level)level, writer_wrapper);
 }
 
+void set_level(const logger& l, uint32_t level) noexcept {
+    const_cast<logger&>(l).set_level((seastar::log_level)level);
+}
+
+uint32_t get_level(const logger& l) noexcept {
+    return (uint32_t)l.level();
+}
+
+namespace {
+
+// Passes the records written by seastar's loggers to Rust (see `TestSink`),
+// and to std::cerr, where they would go otherwise.
+class capture_buf : public std::streambuf {
+    std::mutex _mutex;
+    std::string _pending;
+
+    void append(const char* s, std::streamsize n) {
+        std::lock_guard<std::mutex> lock(_mutex);
+        std::cerr.write(s, n);
+        _pending.append(s, n);
+        // Records are passed whole, also if they span multiple lines.
+        if (!_pending.empty() && _pending.back() == '\n') {
+            capture_log_output(rust::Slice<const uint8_t>(
+                reinterpret_cast<const uint8_t*>(_pending.data()), _pending.size()));
+            _pending.clear();
+        }
+    }
+
+protected:
+    std::streamsize xsputn(const char* s, std::streamsize n) override {
+        append(s, n);
+        return n;
+    }
+
+    int_type overflow(int_type ch) override {
+        if (!traits_type::eq_int_type(ch, traits_type::eof())) {
+            char c = traits_type::to_char_type(ch);
+            append(&c, 1);
+        }
+        return traits_type::not_eof(ch);
+    }
+};
+
+} // anonymous namespace
+
+void capture_output() noexcept {
+    static capture_buf buf;
+    static std::ostream out(&buf);
+    seastar::logger::set_ostream(out);
+}
+
 void log_writer::write(rust::Slice<const uint8_t> data) noexcept {
     it = std::copy(data.begin(), data.end(), std::move(it));
 }
@@ -15,6 +15,10 @@ struct FormatCtx;
 std::unique_ptr<logger> new_logger(rust::Str name);
 void log(const logger& l, uint32_t level, const FormatCtx& ctx) noexcept;
 
+void set_level(const logger& l, uint32_t level) noexcept;
+uint32_t get_level(const logger& l) noexcept;
+void capture_output() noexcept;
+
 struct log_writer {
     seastar::internal::log_buf::inserter_iterator it;
     void write(rust::Slice<const uint8_t> data) noexcept;
@@ -1,5 +1,7 @@
-use std::fmt::Arguments;
+use std::fmt::{self, Arguments, Display};
 use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, Weak};
 
 use cxx::UniquePtr;
 
@@ -8,6 +10,7 @@ mod ffi {
     extern "Rust" {
         type FormatCtx<'a>;
         fn write_log_line(writer: Pin<&mut log_writer>, ctx: &FormatCtx<'_>);
+        fn capture_log_output(record: &[u8]);
     }
 
     unsafe extern "C++" {
@@ -19,6 +22,9 @@ mod ffi {
         type logger;
         fn new_logger(name: &str) -> UniquePtr<logger>;
         fn log(l: &logger, level: u32, ctx: &FormatCtx<'_>);
+        fn set_level(l: &logger, level: u32);
+        fn get_level(l: &logger) -> u32;
+        fn capture_output();
     }
 }
 
@@ -41,8 +47,21 @@ fn write_log_line(writer: Pin<&mut ffi::log_writer>, ctx: &FormatCtx<'_>) {
     std::fmt::write(&mut FmtWriter(writer), ctx.args).unwrap();
 }
 
+// Renders structured fields in a stable ` key1=value1 key2=value2` form.
+struct Fields<'a>(&'a [(&'a str, &'a dyn Display)]);
+
+impl Display for Fields<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (key, value) in self.0 {
+            write!(f, " {key}={value}")?;
+        }
+        Ok(())
+    }
+}
+
 /// Log verbosity level.
 #[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum LogLevel {
     Error = 0,
     Warn = 1,
@@ -51,6 +70,30 @@ pub enum LogLevel {
     Trace = 4,
 }
 
+impl LogLevel {
+    fn from_u32(level: u32) -> Self {
+        match level {
+            0 => LogLevel::Error,
+            1 => LogLevel::Warn,
+            2 => LogLevel::Info,
+            3 => LogLevel::Debug,
+            _ => LogLevel::Trace,
+        }
+    }
+
+    // Parses the level name with which seastar prefixes the records.
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "ERROR" => Some(LogLevel::Error),
+            "WARN" => Some(LogLevel::Warn),
+            "INFO" => Some(LogLevel::Info),
+            "DEBUG" => Some(LogLevel::Debug),
+            "TRACE" => Some(LogLevel::Trace),
+            _ => None,
+        }
+    }
+}
+
 /// A wrapper over seastar::logger.
 ///
 /// # Usage
@@ -86,6 +129,73 @@ pub struct Logger {
     core: UniquePtr<ffi::logger>,
 }
 
+type SinkEntries = Mutex<Vec<(LogLevel, String)>>;
+
+// Sinks of the loggers created with `Logger::with_test_sink`, by logger name.
+static SINKS: Mutex<Vec<(String, Weak<SinkEntries>)>> = Mutex::new(Vec::new());
+
+// Called with each record written by seastar's loggers once `capture_output` was called.
+fn capture_log_output(record: &[u8]) {
+    let record = String::from_utf8_lossy(record);
+    let record = record.strip_suffix('\n').unwrap_or(&record);
+    let mut sinks = SINKS.lock().unwrap();
+    sinks.retain(|(_, entries)| entries.strong_count() > 0);
+    for (name, entries) in sinks.iter() {
+        if let Some(entry) = parse_record(record, name) {
+            if let Some(entries) = entries.upgrade() {
+                entries.lock().unwrap().push(entry);
+            }
+        }
+    }
+}
+
+// Extracts the level and the message from a record written by the logger named `name`.
+// Records look like `INFO  2023-01-01 12:00:00,000 [shard 0] name - message`, with the level
+// possibly wrapped in ANSI color codes.
+fn parse_record(record: &str, name: &str) -> Option<(LogLevel, String)> {
+    let mut level = record.split_whitespace().next()?;
+    while let Some((_, rest)) = level
+        .strip_prefix("\x1b[")
+        .and_then(|code| code.split_once('m'))
+    {
+        level = rest;
+    }
+    let level = LogLevel::from_name(level.split('\x1b').next()?)?;
+    let (_, message) = record.split_once(&format!(" {name} - "))?;
+    Some((level, message.to_string()))
+}
+
+/// Collects messages emitted by a logger created with [`Logger::with_test_sink`].
+///
+/// Cloning a `TestSink` yields a handle to the same collection of messages.
+#[derive(Clone)]
+pub struct TestSink {
+    entries: Arc<SinkEntries>,
+}
+
+impl TestSink {
+    /// Returns the messages emitted so far, in the order they were emitted.
+    pub fn lines(&self) -> Vec<String> {
+        self.entries
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(_, line)| line.clone())
+            .collect()
+    }
+
+    /// Returns the messages emitted so far along with their levels,
+    /// in the order they were emitted.
+    pub fn entries(&self) -> Vec<(LogLevel, String)> {
+        self.entries.lock().unwrap().clone()
+    }
+
+    /// Forgets all messages emitted so far.
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+}
+
 unsafe impl Send for Logger {}
 unsafe impl Sync for Logger {}
 
@@ -119,6 +229,55 @@ impl Logger {
         }
     }
 
+    /// Creates a logger whose messages are also collected in the returned [`TestSink`].
+    ///
+    /// The logger is a regular seastar logger with a unique name, so its messages
+    /// are filtered by its level (see [`Logger::set_level`]) and formatted by seastar
+    /// like any other. The sink collects them from seastar's output, stripped of
+    /// the level, timestamp, shard and logger name. Meant for asserting on the output
+    /// of a logger in tests.
+    ///
+    /// Creating the first sink redirects the output of all seastar loggers through
+    /// a stream which passes it on to `stderr`, seastar's default output.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use seastar::Logger;
+    /// # async fn compile_only() {
+    /// let (logger, sink) = Logger::with_test_sink();
+    /// seastar::info!(logger, "The answer is: {}", 42);
+    /// assert_eq!(sink.lines(), vec!["The answer is: 42"]);
+    /// # }
+    /// ```
+    pub fn with_test_sink() -> (Self, TestSink) {
+        static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+        // Seastar refuses to register two loggers with the same name.
+        let name = format!("test_sink_{}", NEXT_ID.fetch_add(1, Ordering::Relaxed));
+        let sink = TestSink {
+            entries: Default::default(),
+        };
+        SINKS
+            .lock()
+            .unwrap()
+            .push((name.clone(), Arc::downgrade(&sink.entries)));
+        ffi::capture_output();
+        (Self::new(&name), sink)
+    }
+
+    /// Sets the level of the logger, messages of lower importance are dropped.
+    ///
+    /// Seastar loggers start with [`LogLevel::Info`].
+    pub fn set_level(&self, level: LogLevel) {
+        ffi::set_level(&self.core, level as u32);
+    }
+
+    /// Returns the level of the logger.
+    pub fn level(&self) -> LogLevel {
+        LogLevel::from_u32(ffi::get_level(&self.core))
+    }
+
     /// Emits a message with requested level.
     ///
     /// While it's possible to use directly, you will most likely be
@@ -129,6 +288,23 @@ impl Logger {
         ffi::log(&self.core, level as u32, &ctx);
     }
 
+    /// Emits a message with requested level and structured fields.
+    ///
+    /// The fields are appended to the message in the `key=value` form,
+    /// separated by spaces.
+    ///
+    /// While it's possible to use directly, you will most likely be
+    /// interested in the [`log_kv!`](crate::log_kv!) macro instead.
+    #[inline]
+    pub fn log_with_fields(
+        &self,
+        level: LogLevel,
+        args: Arguments<'_>,
+        fields: &[(&str, &dyn Display)],
+    ) {
+        self.log(level, format_args!("{}{}", args, Fields(fields)));
+    }
+
     /// Emits a `trace` level message.
     ///
     /// While it's possible to use directly, you will most likely be
@@ -197,6 +373,34 @@ macro_rules! log {
     }};
 }
 
+/// Emits a formatted log message with structured fields.
+///
+/// The arguments are the same as for [`log!`](crate::log!), followed by
+/// a semicolon and a list of `key = value` pairs. Each value must implement
+/// [`std::fmt::Display`]. The fields are appended to the message in the
+/// `key=value` form.
+///
+/// # Example
+/// ```rust
+/// # use seastar::{Logger, LogLevel};
+/// # fn compile_only() {
+/// let logger = Logger::new("my_logger");
+/// let user = "alice";
+/// // Emits "Request served user=alice status=200"
+/// seastar::log_kv!(logger, LogLevel::Info, "Request served"; user = user, status = 200);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! log_kv {
+    ($logger:expr, $level:expr, $($arg:tt),* ; $($key:ident = $value:expr),+ $(,)?) => {{
+        $logger.log_with_fields(
+            $level,
+            std::format_args!($($arg),*),
+            &[$((std::stringify!($key), &$value as &dyn std::fmt::Display)),+],
+        )
+    }};
+}
+
 /// Emits a formatted log message with `trace` level.
 ///
 /// Equivalent to calling [`log!`](crate::log!) with `trace` level.
@@ -246,3 +450,89 @@ macro_rules! error {
         $logger.error(std::format_args!($($arg),*))
     }};
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as seastar;
+
+    #[test]
+    fn test_fields_format() {
+        let fields: &[(&str, &dyn Display)] = &[("user", &"alice"), ("status", &200)];
+        assert_eq!(format!("{}", Fields(fields)), " user=alice status=200");
+        assert_eq!(format!("{}", Fields(&[])), "");
+    }
+
+    #[test]
+    fn test_parse_record() {
+        let record = "INFO  2023-01-01 12:00:00,000 [shard 0] my_logger - Hello - world";
+        assert_eq!(
+            parse_record(record, "my_logger"),
+            Some((LogLevel::Info, "Hello - world".to_string()))
+        );
+        assert_eq!(parse_record(record, "logger"), None);
+        let colored = "\x1b[32mDEBUG\x1b[0m 2023-01-01 12:00:00,000 [shard 0] my_logger - Hi";
+        assert_eq!(
+            parse_record(colored, "my_logger"),
+            Some((LogLevel::Debug, "Hi".to_string()))
+        );
+        assert_eq!(parse_record("Hello my_logger - world", "my_logger"), None);
+    }
+
+    #[seastar::test]
+    async fn test_test_sink() {
+        let (logger, sink) = Logger::with_test_sink();
+        crate::info!(logger, "Hello, {}!", "world");
+        crate::debug!(logger, "Not enabled");
+        crate::error!(logger, "The answer is: {}", 42);
+        assert_eq!(sink.lines(), vec!["Hello, world!", "The answer is: 42"]);
+        assert_eq!(
+            sink.entries(),
+            vec![
+                (LogLevel::Info, "Hello, world!".to_string()),
+                (LogLevel::Error, "The answer is: 42".to_string()),
+            ]
+        );
+        sink.clear();
+        assert!(sink.lines().is_empty());
+    }
+
+    #[seastar::test]
+    async fn test_test_sink_level() {
+        let (logger, sink) = Logger::with_test_sink();
+        assert_eq!(logger.level(), LogLevel::Info);
+        logger.set_level(LogLevel::Trace);
+        assert_eq!(logger.level(), LogLevel::Trace);
+        crate::trace!(logger, "Verbose");
+        logger.set_level(LogLevel::Error);
+        crate::warn!(logger, "Dropped");
+        assert_eq!(
+            sink.entries(),
+            vec![(LogLevel::Trace, "Verbose".to_string())]
+        );
+    }
+
+    #[seastar::test]
+    async fn test_log_kv() {
+        let (logger, sink) = Logger::with_test_sink();
+        let user = "alice";
+        crate::log_kv!(logger, LogLevel::Info, "Request served"; user = user, status = 200);
+        assert_eq!(sink.lines(), vec!["Request served user=alice status=200"]);
+    }
+
+    #[seastar::test]
+    async fn test_log_kv_captured_output() {
+        let (logger, sink) = Logger::with_test_sink();
+        let ms = 5;
+        crate::log_kv!(logger, LogLevel::Warn, "Slow {}", "request"; path = "/kv", ms = ms);
+        crate::log_kv!(logger, LogLevel::Debug, "Filtered out"; path = "/kv");
+        logger.log_with_fields(LogLevel::Error, format_args!("No fields"), &[]);
+        assert_eq!(
+            sink.entries(),
+            vec![
+                (LogLevel::Warn, "Slow request path=/kv ms=5".to_string()),
+                (LogLevel::Error, "No fields".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,185 @@
+use std::borrow::Borrow;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+/// A bounded key-value cache evicting the least recently used entries.
+///
+/// The cache is local to the shard it was created on (it's `!Send`), so it doesn't need
+/// any synchronization. It's meant to be used as per-shard state, e.g. of a [`Service`]
+/// distributed with [`Distributed`].
+///
+/// [`Service`]: crate::Service
+/// [`Distributed`]: crate::Distributed
+pub struct LruCache<K, V> {
+    capacity: usize,
+    // Each entry holds its value and the time it was last used.
+    entries: HashMap<K, (V, u64)>,
+    // Keys of the entries, ordered by the time they were last used.
+    recency: BTreeMap<u64, K>,
+    clock: u64,
+    _not_send: PhantomData<Rc<()>>,
+}
+
+impl<K: Hash + Eq + Clone, V> LruCache<K, V> {
+    /// Creates an empty cache holding at most `capacity` entries.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is `0`.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "LruCache capacity must be positive");
+        LruCache {
+            capacity,
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
+            clock: 0,
+            _not_send: PhantomData,
+        }
+    }
+
+    /// Returns the maximum number of entries in the cache.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of entries in the cache.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Checks whether the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the value of `key`, marking it as the most recently used.
+    pub fn get<Q>(&mut self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get_mut(key).map(|value| &*value)
+    }
+
+    /// Returns a mutable reference to the value of `key`, marking it as the most recently used.
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let (value, last_used) = self.entries.get_mut(key)?;
+        self.clock += 1;
+        let key = self.recency.remove(&*last_used).unwrap();
+        self.recency.insert(self.clock, key);
+        *last_used = self.clock;
+        Some(value)
+    }
+
+    /// Returns the value of `key` without marking it as used.
+    pub fn peek<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.entries.get(key).map(|(value, _)| value)
+    }
+
+    /// Checks whether the cache holds `key`, without marking it as used.
+    pub fn contains<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.entries.contains_key(key)
+    }
+
+    /// Inserts `value` under `key`, marking it as the most recently used.
+    ///
+    /// If the cache already held `key`, its value is replaced. Otherwise, if the cache is full,
+    /// the least recently used entry is evicted and returned.
+    pub fn put(&mut self, key: K, value: V) -> Option<(K, V)> {
+        self.clock += 1;
+        if let Some((old_value, last_used)) = self.entries.get_mut(&key) {
+            *old_value = value;
+            self.recency.remove(&*last_used);
+            *last_used = self.clock;
+            self.recency.insert(self.clock, key);
+            return None;
+        }
+
+        let evicted = if self.entries.len() == self.capacity {
+            self.pop_lru()
+        } else {
+            None
+        };
+        self.recency.insert(self.clock, key.clone());
+        self.entries.insert(key, (value, self.clock));
+        evicted
+    }
+
+    /// Removes `key` from the cache, returning its value.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let (value, last_used) = self.entries.remove(key)?;
+        self.recency.remove(&last_used);
+        Some(value)
+    }
+
+    /// Removes the least recently used entry from the cache and returns it.
+    pub fn pop_lru(&mut self) -> Option<(K, V)> {
+        let (_, key) = self.recency.pop_first()?;
+        let (value, _) = self.entries.remove(&key).unwrap();
+        Some((key, value))
+    }
+
+    /// Removes all entries from the cache.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.recency.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lru_cache_get_put() {
+        let mut cache = LruCache::new(2);
+        assert!(cache.is_empty());
+        assert_eq!(cache.put("a", 1), None);
+        assert_eq!(cache.put("b", 2), None);
+        assert_eq!(cache.get("a"), Some(&1));
+        assert_eq!(cache.get("c"), None);
+        assert_eq!(cache.put("a", 3), None);
+        assert_eq!(cache.peek("a"), Some(&3));
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.remove("b"), Some(2));
+        assert!(!cache.contains("b"));
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_lru_cache_evicts_least_recently_used() {
+        let mut cache = LruCache::new(3);
+        for i in 0..3 {
+            cache.put(i, i * 10);
+        }
+        // Using 0 makes 1 the least recently used entry.
+        assert_eq!(cache.get(&0), Some(&0));
+        assert_eq!(cache.put(3, 30), Some((1, 10)));
+        // Peeking doesn't count as a use.
+        assert_eq!(cache.peek(&2), Some(&20));
+        assert_eq!(cache.put(4, 40), Some((2, 20)));
+        assert_eq!(cache.len(), 3);
+        assert_eq!(cache.pop_lru(), Some((0, 0)));
+        assert_eq!(cache.pop_lru(), Some((3, 30)));
+        assert_eq!(cache.pop_lru(), Some((4, 40)));
+        assert_eq!(cache.pop_lru(), None);
+    }
+}
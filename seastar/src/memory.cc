@@ -0,0 +1,23 @@
+#include "memory.hh"
+#include "seastar/src/memory.rs.h"
+#include <seastar/core/memory.hh>
+
+namespace seastar_ffi {
+namespace memory {
+
+MemoryStats stats() {
+    auto stats = seastar::memory::stats();
+    return MemoryStats {
+        .mallocs = stats.mallocs(),
+        .frees = stats.frees(),
+        .cross_cpu_frees = stats.cross_cpu_frees(),
+        .live_objects = stats.live_objects(),
+        .free_memory = stats.free_memory(),
+        .allocated_memory = stats.allocated_memory(),
+        .total_memory = stats.total_memory(),
+        .reclaims = stats.reclaims(),
+    };
+}
+
+} // namespace memory
+} // namespace seastar_ffi
@@ -0,0 +1,13 @@
+#pragma once
+
+#include "rust/cxx.h"
+
+namespace seastar_ffi {
+namespace memory {
+
+struct MemoryStats;
+
+MemoryStats stats();
+
+} // namespace memory
+} // namespace seastar_ffi
@@ -0,0 +1,70 @@
+#[cxx::bridge(namespace = "seastar_ffi::memory")]
+mod ffi {
+    /// Memory allocation statistics of a shard.
+    ///
+    /// Equivalent of `seastar::memory::statistics`.
+    #[derive(Clone, Copy, Debug)]
+    struct MemoryStats {
+        /// Total number of memory allocations calls since the system was started.
+        mallocs: u64,
+        /// Total number of memory deallocations calls since the system was started.
+        frees: u64,
+        /// Total number of memory deallocations that occurred on a different shard
+        /// than the one the memory was allocated on.
+        cross_cpu_frees: u64,
+        /// Total number of objects which were allocated but not freed.
+        live_objects: u64,
+        /// Total free memory (in bytes).
+        free_memory: usize,
+        /// Total allocated memory (in bytes).
+        allocated_memory: usize,
+        /// Total memory (in bytes).
+        total_memory: usize,
+        /// Number of reclaims performed due to low memory.
+        reclaims: u64,
+    }
+
+    unsafe extern "C++" {
+        include!("seastar/src/memory.hh");
+
+        fn stats() -> MemoryStats;
+    }
+}
+
+pub use ffi::MemoryStats;
+
+/// Returns memory allocation statistics of the current shard.
+///
+/// Useful for diagnosing per-shard memory pressure. Note that when Seastar is built
+/// to use the default (system) allocator, all of the statistics are zero.
+///
+/// Equivalent of `seastar::memory::stats`.
+pub fn memory_stats() -> MemoryStats {
+    crate::assert_runtime_is_running();
+    ffi::stats()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as seastar;
+
+    #[seastar::test]
+    async fn test_memory_stats_track_allocations() {
+        let before = memory_stats();
+        if before.total_memory == 0 {
+            // Seastar uses the default allocator, which doesn't gather statistics.
+            return;
+        }
+
+        let allocation = vec![1u8; 16 << 20];
+        let after = memory_stats();
+        assert!(after.allocated_memory >= before.allocated_memory + allocation.len());
+        assert!(after.mallocs > before.mallocs);
+        assert_eq!(
+            after.allocated_memory + after.free_memory,
+            after.total_memory
+        );
+        drop(allocation);
+    }
+}
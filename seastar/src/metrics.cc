@@ -0,0 +1,93 @@
+#include "metrics.hh"
+#include <seastar/core/metrics_api.hh>
+
+namespace seastar_ffi {
+namespace metrics {
+
+namespace sm = seastar::metrics;
+
+namespace {
+
+// Owns a Rust closure reporting the value of a metric. Seastar copies metric
+// functions around, so they share a single instance of this class, which drops
+// the closure when the last copy is destroyed.
+template<typename T>
+class metric_callback {
+    const uint8_t* _callback;
+    rust::Fn<T(const uint8_t*)> _caller;
+    rust::Fn<void(const uint8_t*)> _dropper;
+
+public:
+    metric_callback(
+        const uint8_t* callback,
+        rust::Fn<T(const uint8_t*)> caller,
+        rust::Fn<void(const uint8_t*)> dropper
+    ) : _callback(callback), _caller(caller), _dropper(dropper) {}
+
+    metric_callback(const metric_callback&) = delete;
+
+    metric_callback& operator=(const metric_callback&) = delete;
+
+    ~metric_callback() {
+        _dropper(_callback);
+    }
+
+    T operator()() const {
+        return _caller(_callback);
+    }
+};
+
+seastar::sstring to_sstring(rust::Str str) {
+    return seastar::sstring(str.data(), str.size());
+}
+
+} // anonymous namespace
+
+std::unique_ptr<metric_groups> new_metric_groups() {
+    return std::make_unique<metric_groups>();
+}
+
+// `add_group` throws if a metric with the same name is already registered on the shard.
+void add_counter(
+    metric_groups& groups,
+    rust::Str group_name,
+    rust::Str name,
+    rust::Str description,
+    const uint8_t* callback,
+    rust::Fn<uint64_t(const uint8_t*)> caller,
+    rust::Fn<void(const uint8_t*)> dropper
+) {
+    auto cb = std::make_shared<metric_callback<uint64_t>>(callback, caller, dropper);
+    groups.add_group(to_sstring(group_name), {
+        sm::make_counter(to_sstring(name), [cb] { return (*cb)(); }, sm::description(to_sstring(description))),
+    });
+}
+
+void add_gauge(
+    metric_groups& groups,
+    rust::Str group_name,
+    rust::Str name,
+    rust::Str description,
+    const uint8_t* callback,
+    rust::Fn<double(const uint8_t*)> caller,
+    rust::Fn<void(const uint8_t*)> dropper
+) {
+    auto cb = std::make_shared<metric_callback<double>>(callback, caller, dropper);
+    groups.add_group(to_sstring(group_name), {
+        sm::make_gauge(to_sstring(name), [cb] { return (*cb)(); }, sm::description(to_sstring(description))),
+    });
+}
+
+double get_metric_value(rust::Str group_name, rust::Str name) {
+    auto full_name = to_sstring(group_name) + "_" + to_sstring(name);
+    auto& value_map = sm::impl::get_value_map();
+    auto family = value_map.find(full_name);
+    if (family == value_map.end() || family->second.begin() == family->second.end()) {
+        throw std::out_of_range("no such metric");
+    }
+    auto& metric = *family->second.begin()->second;
+    return metric().d();
+}
+
+} // namespace metrics
+} // namespace seastar_ffi
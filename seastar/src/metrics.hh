@@ -0,0 +1,40 @@
+#pragma once
+
+#include <cstdint>
+#include <memory>
+#include <seastar/core/metrics.hh>
+#include <seastar/core/metrics_registration.hh>
+
+#include "rust/cxx.h"
+
+namespace seastar_ffi {
+namespace metrics {
+
+using metric_groups = seastar::metrics::metric_groups;
+
+std::unique_ptr<metric_groups> new_metric_groups();
+
+void add_counter(
+    metric_groups& groups,
+    rust::Str group_name,
+    rust::Str name,
+    rust::Str description,
+    const uint8_t* callback,
+    rust::Fn<uint64_t(const uint8_t*)> caller,
+    rust::Fn<void(const uint8_t*)> dropper
+);
+
+void add_gauge(
+    metric_groups& groups,
+    rust::Str group_name,
+    rust::Str name,
+    rust::Str description,
+    const uint8_t* callback,
+    rust::Fn<double(const uint8_t*)> caller,
+    rust::Fn<void(const uint8_t*)> dropper
+);
+
+double get_metric_value(rust::Str group_name, rust::Str name);
+
+} // namespace metrics
+} // namespace seastar_ffi
@@ -0,0 +1,288 @@
+//! Exporting application metrics through seastar's metrics subsystem.
+//!
+//! Metrics are registered per shard, in a [`MetricGroup`]. They stay registered
+//! for as long as the group they belong to is alive.
+
+use crate::ffi_utils::{get_dropper_const, get_fn_caller};
+use cxx::UniquePtr;
+use std::cell::Cell;
+use std::rc::Rc;
+use thiserror::Error;
+
+#[cxx::bridge(namespace = "seastar_ffi::metrics")]
+mod ffi {
+    unsafe extern "C++" {
+        include!("seastar/src/metrics.hh");
+
+        type metric_groups;
+
+        fn new_metric_groups() -> UniquePtr<metric_groups>;
+
+        unsafe fn add_counter(
+            groups: Pin<&mut metric_groups>,
+            group_name: &str,
+            name: &str,
+            description: &str,
+            callback: *const u8,
+            caller: unsafe fn(*const u8) -> u64,
+            dropper: unsafe fn(*const u8),
+        ) -> Result<()>;
+
+        unsafe fn add_gauge(
+            groups: Pin<&mut metric_groups>,
+            group_name: &str,
+            name: &str,
+            description: &str,
+            callback: *const u8,
+            caller: unsafe fn(*const u8) -> f64,
+            dropper: unsafe fn(*const u8),
+        ) -> Result<()>;
+
+        fn get_metric_value(group_name: &str, name: &str) -> Result<f64>;
+    }
+}
+
+/// Error returned when Seastar fails to register a metric
+/// (e.g. because a metric with the same name is already registered on the shard).
+#[derive(Error, Debug)]
+#[error("MetricsError: {0}")]
+pub struct MetricsError(String);
+
+/// A monotonically increasing metric value.
+///
+/// Cloning a `Counter` yields a handle to the same value.
+#[derive(Clone, Default)]
+pub struct Counter(Rc<Cell<u64>>);
+
+impl Counter {
+    /// Creates a counter starting at 0.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Increments the counter by 1.
+    pub fn increment(&self) {
+        self.increment_by(1);
+    }
+
+    /// Increments the counter by `value`.
+    pub fn increment_by(&self, value: u64) {
+        self.0.set(self.0.get() + value);
+    }
+
+    /// Returns the current value of the counter.
+    pub fn get(&self) -> u64 {
+        self.0.get()
+    }
+}
+
+/// A metric value that can arbitrarily go up and down.
+///
+/// Cloning a `Gauge` yields a handle to the same value.
+#[derive(Clone, Default)]
+pub struct Gauge(Rc<Cell<f64>>);
+
+impl Gauge {
+    /// Creates a gauge set to 0.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the gauge to `value`.
+    pub fn set(&self, value: f64) {
+        self.0.set(value);
+    }
+
+    /// Returns the current value of the gauge.
+    pub fn get(&self) -> f64 {
+        self.0.get()
+    }
+}
+
+/// A group of metrics registered on the current shard.
+///
+/// Equivalent of `seastar::metrics::metric_groups`. All metrics
+/// of the group are unregistered when it is dropped.
+///
+/// # Examples
+///
+/// ```rust
+/// use seastar::metrics::MetricGroup;
+///
+/// #[seastar::test]
+/// async fn metrics_example() {
+///     let mut group = MetricGroup::new("my_service");
+///     let requests = group
+///         .add_counter("requests", "Number of served requests")
+///         .unwrap();
+///     requests.increment();
+///     group.register_gauge("load", "Current load", || 0.5).unwrap();
+/// }
+/// ```
+pub struct MetricGroup {
+    name: String,
+    inner: UniquePtr<ffi::metric_groups>,
+}
+
+impl MetricGroup {
+    /// Creates an empty group with the given name.
+    ///
+    /// Names of the group's metrics are prefixed with it.
+    pub fn new(name: &str) -> Self {
+        crate::assert_runtime_is_running();
+        Self {
+            name: name.to_owned(),
+            inner: ffi::new_metric_groups(),
+        }
+    }
+
+    /// Returns the name of the group.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Registers a counter whose value is reported by `func`.
+    ///
+    /// Fails if a metric with the same name is already registered in a group
+    /// with the same name on the current shard.
+    pub fn register_counter<Func>(
+        &mut self,
+        name: &str,
+        description: &str,
+        func: Func,
+    ) -> Result<(), MetricsError>
+    where
+        Func: Fn() -> u64 + 'static,
+    {
+        let caller = get_fn_caller(&func);
+        let dropper = get_dropper_const(&func);
+        let boxed_func = Box::into_raw(Box::new(func)) as *const u8;
+        // On failure, the closure is dropped by the C++ side.
+        unsafe {
+            ffi::add_counter(
+                self.inner.pin_mut(),
+                &self.name,
+                name,
+                description,
+                boxed_func,
+                caller,
+                dropper,
+            )
+        }
+        .map_err(|e| MetricsError(e.to_string()))
+    }
+
+    /// Registers a gauge whose value is reported by `func`.
+    ///
+    /// Fails like [`MetricGroup::register_counter`].
+    pub fn register_gauge<Func>(
+        &mut self,
+        name: &str,
+        description: &str,
+        func: Func,
+    ) -> Result<(), MetricsError>
+    where
+        Func: Fn() -> f64 + 'static,
+    {
+        let caller = get_fn_caller(&func);
+        let dropper = get_dropper_const(&func);
+        let boxed_func = Box::into_raw(Box::new(func)) as *const u8;
+        // On failure, the closure is dropped by the C++ side.
+        unsafe {
+            ffi::add_gauge(
+                self.inner.pin_mut(),
+                &self.name,
+                name,
+                description,
+                boxed_func,
+                caller,
+                dropper,
+            )
+        }
+        .map_err(|e| MetricsError(e.to_string()))
+    }
+
+    /// Registers a new [`Counter`] and returns it.
+    ///
+    /// Fails like [`MetricGroup::register_counter`].
+    pub fn add_counter(&mut self, name: &str, description: &str) -> Result<Counter, MetricsError> {
+        let counter = Counter::new();
+        let counter_clone = counter.clone();
+        self.register_counter(name, description, move || counter_clone.get())?;
+        Ok(counter)
+    }
+
+    /// Registers a new [`Gauge`] and returns it.
+    ///
+    /// Fails like [`MetricGroup::register_counter`].
+    pub fn add_gauge(&mut self, name: &str, description: &str) -> Result<Gauge, MetricsError> {
+        let gauge = Gauge::new();
+        let gauge_clone = gauge.clone();
+        self.register_gauge(name, description, move || gauge_clone.get())?;
+        Ok(gauge)
+    }
+}
+
+/// Reads the current value of a metric registered on the current shard.
+///
+/// Returns `None` if there is no metric named `name` in group `group_name`.
+pub fn metric_value(group_name: &str, name: &str) -> Option<f64> {
+    crate::assert_runtime_is_running();
+    ffi::get_metric_value(group_name, name).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as seastar;
+
+    #[seastar::test]
+    async fn test_counter() {
+        let mut group = MetricGroup::new("test_counter");
+        let counter = group.add_counter("calls", "test counter").unwrap();
+        assert_eq!(metric_value("test_counter", "calls"), Some(0.0));
+        counter.increment();
+        counter.increment_by(2);
+        assert_eq!(counter.get(), 3);
+        assert_eq!(metric_value("test_counter", "calls"), Some(3.0));
+    }
+
+    #[seastar::test]
+    async fn test_gauge() {
+        let mut group = MetricGroup::new("test_gauge");
+        let gauge = group.add_gauge("level", "test gauge").unwrap();
+        gauge.set(4.5);
+        assert_eq!(metric_value("test_gauge", "level"), Some(4.5));
+        group
+            .register_gauge("constant", "test gauge", || 2.0)
+            .unwrap();
+        assert_eq!(metric_value("test_gauge", "constant"), Some(2.0));
+    }
+
+    #[seastar::test]
+    async fn test_metrics_unregistered_on_drop() {
+        let mut group = MetricGroup::new("test_drop");
+        group
+            .register_counter("calls", "test counter", || 1)
+            .unwrap();
+        assert_eq!(metric_value("test_drop", "calls"), Some(1.0));
+        drop(group);
+        assert_eq!(metric_value("test_drop", "calls"), None);
+    }
+
+    #[seastar::test]
+    async fn test_duplicate_metric() {
+        let mut group = MetricGroup::new("test_duplicate");
+        group.add_counter("calls", "test counter").unwrap();
+        assert!(group.add_gauge("calls", "test gauge").is_err());
+
+        // Groups with the same name share the metrics' namespace.
+        let mut other = MetricGroup::new("test_duplicate");
+        assert!(other
+            .register_counter("calls", "test counter", || 2)
+            .is_err());
+        other.add_counter("other_calls", "test counter").unwrap();
+        assert_eq!(metric_value("test_duplicate", "calls"), Some(0.0));
+        assert_eq!(metric_value("test_duplicate", "other_calls"), Some(0.0));
+    }
+}
@@ -0,0 +1,143 @@
+use crate::{Semaphore, SemaphoreUnits};
+use std::cell::UnsafeCell;
+use std::ops::{Deref, DerefMut};
+
+/// An asynchronous mutual exclusion lock protecting a shard-local value.
+///
+/// A shard runs one task at a time, but tasks interleave at await points. The lock serializes
+/// critical sections spanning await points, which a `RefCell` can't do without panicking.
+/// Waiters are served in FIFO order.
+///
+/// Built on a [`Semaphore`] with a single unit.
+pub struct Mutex<T> {
+    sem: Semaphore,
+    value: UnsafeCell<T>,
+}
+
+impl<T> Mutex<T> {
+    /// Creates an unlocked mutex protecting `value`.
+    pub fn new(value: T) -> Self {
+        Mutex {
+            sem: Semaphore::new(1),
+            value: UnsafeCell::new(value),
+        }
+    }
+
+    /// Locks the mutex, waiting until it's released if it's locked.
+    ///
+    /// Returns a guard giving access to the value, which releases the lock when dropped.
+    /// Cancel safe: dropping the returned future before it completes withdraws the request,
+    /// like with [`Semaphore::wait`].
+    pub async fn lock(&self) -> MutexGuard<'_, T> {
+        MutexGuard {
+            mutex: self,
+            _units: self.sem.get_units(1).await,
+        }
+    }
+
+    /// Locks the mutex if it's unlocked and nobody is waiting for it.
+    pub fn try_lock(&self) -> Option<MutexGuard<'_, T>> {
+        self.sem.try_get_units(1).map(|units| MutexGuard {
+            mutex: self,
+            _units: units,
+        })
+    }
+
+    /// Returns a mutable reference to the value.
+    ///
+    /// Doesn't need to lock the mutex, as the exclusive borrow guarantees that it isn't locked.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.value.get_mut()
+    }
+
+    /// Consumes the mutex, returning the value.
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+}
+
+impl<T: Default> Default for Mutex<T> {
+    fn default() -> Self {
+        Mutex::new(T::default())
+    }
+}
+
+/// A lock held on a [`Mutex`], giving access to the protected value.
+///
+/// The lock is released when the guard is dropped (RAII).
+pub struct MutexGuard<'a, T> {
+    mutex: &'a Mutex<T>,
+    _units: SemaphoreUnits<'a>,
+}
+
+impl<T> Deref for MutexGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        // Holding the semaphore's only unit guarantees exclusive access.
+        unsafe { &*self.mutex.value.get() }
+    }
+}
+
+impl<T> DerefMut for MutexGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { &mut *self.mutex.value.get() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as seastar;
+    use crate::{sleep, Duration, SteadyClock};
+    use futures::join;
+    use std::cell::RefCell;
+
+    #[seastar::test]
+    async fn test_mutex_try_lock() {
+        let mutex = Mutex::new(1);
+        let mut guard = mutex.try_lock().unwrap();
+        *guard += 1;
+        assert!(mutex.try_lock().is_none());
+        drop(guard);
+        assert_eq!(*mutex.try_lock().unwrap(), 2);
+        assert_eq!(mutex.into_inner(), 2);
+    }
+
+    #[seastar::test]
+    async fn test_mutex_critical_sections_dont_interleave() {
+        let mutex = Mutex::new(Vec::new());
+        let events = RefCell::new(Vec::new());
+
+        let critical_section = |id| {
+            let mutex = &mutex;
+            let events = &events;
+            async move {
+                let mut guard = mutex.lock().await;
+                events.borrow_mut().push((id, "enter"));
+                sleep(Duration::<SteadyClock>::from_millis(2)).await;
+                guard.push(id);
+                events.borrow_mut().push((id, "exit"));
+            }
+        };
+
+        join!(critical_section(1), critical_section(2));
+        assert_eq!(
+            *events.borrow(),
+            vec![(1, "enter"), (1, "exit"), (2, "enter"), (2, "exit")]
+        );
+        assert_eq!(*mutex.lock().await, vec![1, 2]);
+    }
+
+    #[seastar::test]
+    async fn test_mutex_lock_dropped_while_waiting() {
+        let mutex = Mutex::new(0);
+        let guard = mutex.lock().await;
+        let mut lock = Box::pin(mutex.lock());
+        assert!(futures::poll!(&mut lock).is_pending());
+        drop(lock);
+        drop(guard);
+        *mutex.lock().await += 1;
+        assert_eq!(*mutex.try_lock().unwrap(), 1);
+    }
+}
@@ -0,0 +1,121 @@
+#include "net.hh"
+#include "seastar/src/net.rs.h"
+#include <arpa/inet.h>
+#include <cstring>
+
+namespace seastar_ffi {
+namespace net {
+
+static seastar::socket_address to_socket_address(const socket_addr& addr) {
+    if (addr.ipv6) {
+        sockaddr_in6 in6{};
+        in6.sin6_family = AF_INET6;
+        in6.sin6_port = htons(addr.port);
+        in6.sin6_flowinfo = htonl(addr.flowinfo);
+        in6.sin6_scope_id = addr.scope_id;
+        std::memcpy(&in6.sin6_addr, addr.ip.data(), sizeof(in6.sin6_addr));
+        return seastar::socket_address(in6);
+    } else {
+        sockaddr_in in{};
+        in.sin_family = AF_INET;
+        in.sin_port = htons(addr.port);
+        std::memcpy(&in.sin_addr, addr.ip.data(), sizeof(in.sin_addr));
+        return seastar::socket_address(in);
+    }
+}
+
+static socket_addr from_socket_address(const seastar::socket_address& address) {
+    socket_addr addr{};
+    if (address.family() == AF_INET6) {
+        const sockaddr_in6& in6 = address.as_posix_sockaddr_in6();
+        addr.ipv6 = true;
+        addr.port = ntohs(in6.sin6_port);
+        addr.flowinfo = ntohl(in6.sin6_flowinfo);
+        addr.scope_id = in6.sin6_scope_id;
+        std::memcpy(addr.ip.data(), &in6.sin6_addr, sizeof(in6.sin6_addr));
+    } else {
+        const sockaddr_in& in = address.as_posix_sockaddr_in();
+        addr.ipv6 = false;
+        addr.port = ntohs(in.sin_port);
+        std::memcpy(addr.ip.data(), &in.sin_addr, sizeof(in.sin_addr));
+    }
+    return addr;
+}
+
+std::unique_ptr<server_socket> listen(const socket_addr& addr, int32_t& error) {
+    seastar::listen_options options;
+    options.proto = seastar::transport::TCP;
+    options.reuse_address = true;
+    try {
+        server_socket socket = seastar::listen(to_socket_address(addr), options);
+        return std::make_unique<server_socket>(std::move(socket));
+    } catch (const std::system_error& e) {
+        error = e.code().value();
+        return nullptr;
+    } catch (const std::exception&) {
+        // Passed to Rust as an error by cxx.
+        throw;
+    } catch (...) {
+        throw std::runtime_error("listen failed with an unknown exception");
+    }
+}
+
+socket_addr local_address(const std::unique_ptr<server_socket>& server) {
+    return from_socket_address(server->local_address());
+}
+
+void abort_accept(const std::unique_ptr<server_socket>& server) {
+    server->abort_accept();
+}
+
+std::shared_ptr<connection> new_connection() {
+    return std::make_shared<connection>();
+}
+
+VoidFuture accept(const std::unique_ptr<server_socket>& server, std::shared_ptr<connection> conn) {
+    // The server is only used before the first suspension point.
+    auto accepted = server->accept();
+    try {
+        seastar::accept_result result = co_await std::move(accepted);
+        conn->socket = std::make_unique<connected_socket>(std::move(result.connection));
+        conn->peer = result.remote_address;
+    } catch (const std::system_error& e) {
+        conn->error = e.code().value();
+        throw;
+    }
+}
+
+socket_addr connection_peer(const std::shared_ptr<connection>& conn) {
+    return from_socket_address(conn->peer);
+}
+
+VoidFuture connect(const socket_addr& addr, std::shared_ptr<connection> conn) {
+    // The address is converted before the first suspension point, it doesn't have to outlive the call.
+    seastar::socket_address address = to_socket_address(addr);
+    try {
+        connected_socket new_socket = co_await seastar::connect(address);
+        conn->socket = std::make_unique<connected_socket>(std::move(new_socket));
+    } catch (const std::system_error& e) {
+        conn->error = e.code().value();
+        throw;
+    }
+}
+
+std::unique_ptr<connected_socket> take_socket(const std::shared_ptr<connection>& conn) {
+    return std::move(conn->socket);
+}
+
+int32_t connection_error(const std::shared_ptr<connection>& conn) {
+    return conn->error;
+}
+
+std::unique_ptr<input_stream> input(const std::unique_ptr<connected_socket>& socket) {
+    return std::make_unique<input_stream>(socket->input());
+}
+
+std::unique_ptr<output_stream> output(const std::unique_ptr<connected_socket>& socket) {
+    return std::make_unique<output_stream>(socket->output());
+}
+
+} // namespace net
+} // namespace seastar_ffi
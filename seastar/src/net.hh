@@ -0,0 +1,52 @@
+#pragma once
+
+#include "cxx_async_futures.hh"
+#include "iostream.hh"
+#include <seastar/net/api.hh>
+
+namespace seastar_ffi {
+namespace net {
+
+using server_socket = seastar::server_socket;
+using connected_socket = seastar::connected_socket;
+using seastar_ffi::iostream::input_stream;
+using seastar_ffi::iostream::output_stream;
+
+struct socket_addr;
+
+// Returns a null pointer on failure and sets `error` to the errno of the failed system call.
+// Other failures are reported by throwing.
+std::unique_ptr<server_socket> listen(const socket_addr& addr, int32_t& error);
+
+socket_addr local_address(const std::unique_ptr<server_socket>& server);
+
+void abort_accept(const std::unique_ptr<server_socket>& server);
+
+// The outcome of `accept` or `connect`. It's shared with the accepting or connecting coroutine,
+// so that the coroutine doesn't write to freed memory if its future is dropped before it completes.
+struct connection {
+    std::unique_ptr<connected_socket> socket;
+    // The address of the peer, only set by `accept`.
+    seastar::socket_address peer;
+    // The errno of the failed system call, if there was one.
+    int32_t error = 0;
+};
+
+std::shared_ptr<connection> new_connection();
+
+VoidFuture accept(const std::unique_ptr<server_socket>& server, std::shared_ptr<connection> conn);
+
+socket_addr connection_peer(const std::shared_ptr<connection>& conn);
+
+VoidFuture connect(const socket_addr& addr, std::shared_ptr<connection> conn);
+
+std::unique_ptr<connected_socket> take_socket(const std::shared_ptr<connection>& conn);
+
+int32_t connection_error(const std::shared_ptr<connection>& conn);
+
+std::unique_ptr<input_stream> input(const std::unique_ptr<connected_socket>& socket);
+
+std::unique_ptr<output_stream> output(const std::unique_ptr<connected_socket>& socket);
+
+} // namespace net
+} // namespace seastar_ffi
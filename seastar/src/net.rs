@@ -0,0 +1,481 @@
+use crate::gate::OwnedGateHolder;
+use crate::{with_timeout, Clock, Duration, Gate, InputStream, OutputStream, SteadyClock};
+use cxx::UniquePtr;
+use ffi::*;
+use std::cell::Cell;
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV6};
+use std::ops::Deref;
+use std::rc::Rc;
+
+#[cxx::bridge]
+mod ffi {
+    #[namespace = "seastar_ffi"]
+    unsafe extern "C++" {
+        type VoidFuture = crate::cxx_async_futures::VoidFuture;
+    }
+
+    #[namespace = "seastar_ffi::iostream"]
+    unsafe extern "C++" {
+        type input_stream = crate::iostream::ffi::input_stream;
+        type output_stream = crate::iostream::ffi::output_stream;
+    }
+
+    // An IPv4 or IPv6 socket address. IPv4 addresses use the first 4 bytes of `ip`
+    // and leave `flowinfo` and `scope_id` zeroed.
+    #[namespace = "seastar_ffi::net"]
+    struct socket_addr {
+        ipv6: bool,
+        ip: [u8; 16],
+        port: u16,
+        flowinfo: u32,
+        scope_id: u32,
+    }
+
+    #[namespace = "seastar_ffi::net"]
+    unsafe extern "C++" {
+        include!("seastar/src/net.hh");
+
+        type server_socket;
+        type connected_socket;
+
+        fn listen(addr: &socket_addr, error: &mut i32) -> Result<UniquePtr<server_socket>>;
+
+        fn local_address(server: &UniquePtr<server_socket>) -> socket_addr;
+
+        fn abort_accept(server: &UniquePtr<server_socket>);
+
+        type connection;
+
+        fn new_connection() -> SharedPtr<connection>;
+
+        fn accept(server: &UniquePtr<server_socket>, conn: SharedPtr<connection>) -> VoidFuture;
+
+        fn connection_peer(conn: &SharedPtr<connection>) -> socket_addr;
+
+        fn connect(addr: &socket_addr, conn: SharedPtr<connection>) -> VoidFuture;
+
+        fn take_socket(conn: &SharedPtr<connection>) -> UniquePtr<connected_socket>;
+
+        fn connection_error(conn: &SharedPtr<connection>) -> i32;
+
+        fn input(socket: &UniquePtr<connected_socket>) -> UniquePtr<input_stream>;
+
+        fn output(socket: &UniquePtr<connected_socket>) -> UniquePtr<output_stream>;
+    }
+}
+
+impl From<SocketAddr> for socket_addr {
+    fn from(addr: SocketAddr) -> Self {
+        let mut ip = [0; 16];
+        let (ipv6, flowinfo, scope_id) = match addr {
+            SocketAddr::V4(v4) => {
+                ip[..4].copy_from_slice(&v4.ip().octets());
+                (false, 0, 0)
+            }
+            SocketAddr::V6(v6) => {
+                ip = v6.ip().octets();
+                (true, v6.flowinfo(), v6.scope_id())
+            }
+        };
+        socket_addr {
+            ipv6,
+            ip,
+            port: addr.port(),
+            flowinfo,
+            scope_id,
+        }
+    }
+}
+
+impl From<socket_addr> for SocketAddr {
+    // IPv4-mapped IPv6 addresses (e.g. of IPv4 peers of sockets listening on IPv6)
+    // are converted to plain IPv4 ones.
+    fn from(addr: socket_addr) -> Self {
+        if addr.ipv6 {
+            let v6 = Ipv6Addr::from(addr.ip);
+            match v6.to_ipv4_mapped() {
+                Some(v4) => SocketAddr::new(IpAddr::V4(v4), addr.port),
+                None => SocketAddrV6::new(v6, addr.port, addr.flowinfo, addr.scope_id).into(),
+            }
+        } else {
+            let v4 = Ipv4Addr::new(addr.ip[0], addr.ip[1], addr.ip[2], addr.ip[3]);
+            SocketAddr::new(IpAddr::V4(v4), addr.port)
+        }
+    }
+}
+
+/// A TCP socket listening for connections on the current shard.
+///
+/// Seastar sets `SO_REUSEPORT` on listening sockets (if the kernel supports it),
+/// so each shard can listen on the same port and the kernel balances
+/// the incoming connections between them.
+///
+/// Equivalent of `seastar::server_socket`.
+pub struct TcpListener {
+    inner: UniquePtr<server_socket>,
+}
+
+impl TcpListener {
+    /// Starts listening for connections on `addr`.
+    ///
+    /// Errors reported by the operating system are translated to the matching
+    /// [`io::ErrorKind`], e.g. [`io::ErrorKind::AddrInUse`] if another socket
+    /// is already bound to the port, or [`io::ErrorKind::PermissionDenied`]
+    /// if the port is privileged.
+    ///
+    /// Equivalent of `seastar::listen`.
+    pub fn bind(addr: SocketAddr) -> io::Result<TcpListener> {
+        crate::assert_runtime_is_running();
+        let mut error = 0;
+        match listen(&addr.into(), &mut error) {
+            Ok(inner) if inner.is_null() => Err(io::Error::from_raw_os_error(error)),
+            Ok(inner) => Ok(TcpListener { inner }),
+            Err(e) => Err(io::Error::new(io::ErrorKind::Other, e)),
+        }
+    }
+
+    /// Returns the address the listener is bound to.
+    ///
+    /// Useful to find out the port picked by the system when binding to port 0.
+    pub fn local_addr(&self) -> SocketAddr {
+        local_address(&self.inner).into()
+    }
+
+    /// Waits for a new connection and returns it together with the address of the peer.
+    pub async fn accept(&self) -> io::Result<(TcpConnection, SocketAddr)> {
+        crate::assert_runtime_is_running();
+        let conn = new_connection();
+        match accept(&self.inner, conn.clone()).await {
+            Ok(_) => Ok((
+                TcpConnection {
+                    inner: take_socket(&conn),
+                },
+                connection_peer(&conn).into(),
+            )),
+            Err(e) => match connection_error(&conn) {
+                0 => Err(io::Error::new(io::ErrorKind::Other, e)),
+                error => Err(io::Error::from_raw_os_error(error)),
+            },
+        }
+    }
+
+    /// Makes the pending and all future calls to [`TcpListener::accept`] fail.
+    ///
+    /// Equivalent of `seastar::server_socket::abort_accept`.
+    pub fn abort_accept(&self) {
+        abort_accept(&self.inner);
+    }
+}
+
+/// Opens a TCP connection to `addr`.
+///
+/// Errors reported by the operating system are translated to the matching
+/// [`io::ErrorKind`], e.g. [`io::ErrorKind::ConnectionRefused`] if nobody listens on `addr`.
+///
+/// Equivalent of `seastar::connect`.
+pub async fn connect(addr: SocketAddr) -> io::Result<TcpConnection> {
+    crate::assert_runtime_is_running();
+    let conn = new_connection();
+    match ffi::connect(&addr.into(), conn.clone()).await {
+        Ok(_) => Ok(TcpConnection {
+            inner: take_socket(&conn),
+        }),
+        Err(e) => match connection_error(&conn) {
+            0 => Err(io::Error::new(io::ErrorKind::Other, e)),
+            error => Err(io::Error::from_raw_os_error(error)),
+        },
+    }
+}
+
+/// Opens a TCP connection to `addr`, giving up after `timeout`.
+///
+/// Works like [`connect`], but returns an [`io::ErrorKind::TimedOut`] error
+/// if the connection isn't established in time.
+///
+/// `seastar::connect` can't be aborted, so after the timeout the connection attempt keeps
+/// running in the background, holding a socket, until the operating system gives up on it.
+/// If it succeeds after all, the connection is closed right away.
+pub async fn connect_timeout(
+    addr: SocketAddr,
+    timeout: Duration<SteadyClock>,
+) -> io::Result<TcpConnection> {
+    match with_timeout(SteadyClock::now() + timeout, connect(addr)).await {
+        Ok(ret) => ret,
+        Err(e) => Err(io::Error::new(io::ErrorKind::TimedOut, e)),
+    }
+}
+
+/// An established TCP connection.
+///
+/// Equivalent of `seastar::connected_socket`.
+pub struct TcpConnection {
+    inner: UniquePtr<connected_socket>,
+}
+
+impl TcpConnection {
+    /// Returns a stream reading data received from the peer.
+    ///
+    /// The socket supports a single input stream.
+    pub fn input(&self) -> InputStream {
+        InputStream::new(input(&self.inner))
+    }
+
+    /// Returns a stream sending data to the peer.
+    ///
+    /// The socket supports a single output stream.
+    pub fn output(&self) -> OutputStream {
+        OutputStream::new(output(&self.inner))
+    }
+}
+
+/// A listener tracking the connections it accepted, so that a server can shut down gracefully:
+/// stop accepting new connections and wait for the in-flight ones to be handled.
+///
+/// The accepted connections are tracked with a [`Gate`] until they are dropped.
+pub struct ConnectionManager {
+    listener: TcpListener,
+    gate: Rc<Gate>,
+    shut_down: Cell<bool>,
+}
+
+impl ConnectionManager {
+    /// Creates a manager accepting connections from `listener`.
+    pub fn new(listener: TcpListener) -> Self {
+        ConnectionManager {
+            listener,
+            gate: Rc::new(Gate::new()),
+            shut_down: Cell::new(false),
+        }
+    }
+
+    /// Returns the address the underlying listener is bound to.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.listener.local_addr()
+    }
+
+    /// Waits for a new connection and returns it together with the address of the peer.
+    ///
+    /// [`ConnectionManager::shutdown`] waits until the returned connection is dropped.
+    /// Returns an error of kind [`io::ErrorKind::ConnectionAborted`] if the manager
+    /// is shut down before or while waiting.
+    pub async fn accept(&self) -> io::Result<(ManagedConnection, SocketAddr)> {
+        // Waiting for a connection counts as handling one, so that a shutdown
+        // can't miss a connection accepted concurrently.
+        let holder = self
+            .gate
+            .enter_owned()
+            .map_err(|e| io::Error::new(io::ErrorKind::ConnectionAborted, e))?;
+        match self.listener.accept().await {
+            Ok((connection, peer)) => Ok((
+                ManagedConnection {
+                    connection,
+                    _holder: holder,
+                },
+                peer,
+            )),
+            Err(e) if self.shut_down.get() => {
+                Err(io::Error::new(io::ErrorKind::ConnectionAborted, e))
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Stops accepting new connections and waits until all accepted ones are dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the manager has already been shut down.
+    pub async fn shutdown(&self) {
+        crate::assert_runtime_is_running();
+        assert!(
+            !self.shut_down.replace(true),
+            "ConnectionManager shut down twice"
+        );
+        self.listener.abort_accept();
+        self.gate.close().await;
+    }
+}
+
+/// A connection accepted by a [`ConnectionManager`], dereferencing to [`TcpConnection`].
+///
+/// The manager's shutdown waits until it's dropped.
+pub struct ManagedConnection {
+    connection: TcpConnection,
+    _holder: OwnedGateHolder,
+}
+
+impl Deref for ManagedConnection {
+    type Target = TcpConnection;
+
+    fn deref(&self) -> &Self::Target {
+        &self.connection
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as seastar;
+    use std::io::Write;
+
+    fn localhost(port: u16) -> SocketAddr {
+        SocketAddr::from((Ipv4Addr::LOCALHOST, port))
+    }
+
+    #[test]
+    fn test_socket_addr_conversions() {
+        let addrs = [
+            "127.0.0.1:5555".parse::<SocketAddr>().unwrap(),
+            "[::1]:5555".parse().unwrap(),
+            "[fe80::1:2:3:4]:80".parse().unwrap(),
+            SocketAddrV6::new("fe80::1".parse().unwrap(), 80, 7, 2).into(),
+        ];
+        for addr in addrs {
+            assert_eq!(SocketAddr::from(socket_addr::from(addr)), addr);
+        }
+
+        let mapped: SocketAddr = "[::ffff:10.0.0.1]:80".parse().unwrap();
+        assert_eq!(
+            SocketAddr::from(socket_addr::from(mapped)),
+            "10.0.0.1:80".parse::<SocketAddr>().unwrap()
+        );
+    }
+
+    #[seastar::test]
+    async fn test_tcp_listener_bind_assigns_port() {
+        let listener = TcpListener::bind(localhost(0)).unwrap();
+        let addr = listener.local_addr();
+        assert_eq!(addr.ip(), IpAddr::V4(Ipv4Addr::LOCALHOST));
+        assert_ne!(addr.port(), 0);
+    }
+
+    #[seastar::test]
+    async fn test_tcp_listener_bind_port_in_use() {
+        let std_listener = std::net::TcpListener::bind(localhost(0)).unwrap();
+        let port = std_listener.local_addr().unwrap().port();
+        let err = TcpListener::bind(localhost(port)).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::AddrInUse);
+    }
+
+    #[seastar::test]
+    async fn test_tcp_listener_accept() {
+        let listener = TcpListener::bind(localhost(0)).unwrap();
+        let addr = listener.local_addr();
+
+        // The connection is established by the kernel before it's accepted,
+        // so a blocking connect doesn't stall the reactor.
+        let mut client = std::net::TcpStream::connect(addr).unwrap();
+        client.write_all(b"I <3 seastar!").unwrap();
+        drop(client);
+
+        let (connection, peer) = listener.accept().await.unwrap();
+        assert_eq!(peer.ip(), IpAddr::V4(Ipv4Addr::LOCALHOST));
+        let mut input = connection.input();
+        let mut received = Vec::new();
+        loop {
+            let buffer = input.read().await.unwrap();
+            if buffer.is_empty() {
+                break;
+            }
+            received.extend_from_slice(&buffer);
+        }
+        assert_eq!(received, b"I <3 seastar!");
+    }
+
+    #[seastar::test]
+    async fn test_tcp_listener_accept_dropped() {
+        let listener = TcpListener::bind(localhost(0)).unwrap();
+        let addr = listener.local_addr();
+
+        // The pending accept outlives its future and takes the first connection.
+        let mut accept = Box::pin(listener.accept());
+        assert!(futures::poll!(&mut accept).is_pending());
+        drop(accept);
+        let _first = std::net::TcpStream::connect(addr).unwrap();
+        let _second = std::net::TcpStream::connect(addr).unwrap();
+
+        let (_connection, peer) = listener.accept().await.unwrap();
+        assert_eq!(peer.ip(), IpAddr::V4(Ipv4Addr::LOCALHOST));
+    }
+
+    #[seastar::test]
+    async fn test_connect_refused() {
+        let std_listener = std::net::TcpListener::bind(localhost(0)).unwrap();
+        let addr = std_listener.local_addr().unwrap();
+        drop(std_listener);
+        let err = connect(addr).await.err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::ConnectionRefused);
+    }
+
+    #[seastar::test]
+    async fn test_connect_timeout() {
+        let listener = TcpListener::bind(localhost(0)).unwrap();
+        let timeout = Duration::<SteadyClock>::from_secs(5);
+        let connection = connect_timeout(listener.local_addr(), timeout)
+            .await
+            .unwrap();
+        let (accepted, _) = listener.accept().await.unwrap();
+
+        let mut output = accepted.output();
+        output.write(b"pong").await.unwrap();
+        output.close().await.unwrap();
+        let buffer = connection.input().read_exactly(4).await.unwrap();
+        assert_eq!(&*buffer, b"pong");
+    }
+
+    #[seastar::test]
+    async fn test_connect_timeout_expires() {
+        // TEST-NET-1 (RFC 5737) is never routed, so the handshake never completes.
+        let blackhole = SocketAddr::from((Ipv4Addr::new(192, 0, 2, 1), 9));
+        let timeout = Duration::<SteadyClock>::from_millis(50);
+        let err = connect_timeout(blackhole, timeout).await.err().unwrap();
+        if err.raw_os_error().is_some() {
+            // Without any route, the connection fails right away instead.
+            return;
+        }
+        assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+        assert!(err.into_inner().unwrap().is::<crate::TimedOutError>());
+    }
+
+    #[seastar::test]
+    async fn test_connection_manager_shutdown_waits_for_handlers() {
+        use crate::{sleep, spawn};
+        use std::cell::RefCell;
+
+        let manager = ConnectionManager::new(TcpListener::bind(localhost(0)).unwrap());
+        let clients = [
+            connect(manager.local_addr()).await.unwrap(),
+            connect(manager.local_addr()).await.unwrap(),
+        ];
+
+        let handled = Rc::new(RefCell::new(Vec::new()));
+        for id in 0..clients.len() {
+            let (connection, _) = manager.accept().await.unwrap();
+            let handled = handled.clone();
+            let _ = spawn(async move {
+                sleep(Duration::<SteadyClock>::from_millis(10 * (id as i32 + 1))).await;
+                handled.borrow_mut().push(id);
+                drop(connection);
+            });
+        }
+
+        manager.shutdown().await;
+        assert_eq!(*handled.borrow(), vec![0, 1]);
+        let err = manager.accept().await.err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::ConnectionAborted);
+    }
+
+    #[seastar::test]
+    async fn test_connect() {
+        let listener = TcpListener::bind(localhost(0)).unwrap();
+        let connection = connect(listener.local_addr()).await.unwrap();
+        let (accepted, _) = listener.accept().await.unwrap();
+
+        let mut output = connection.output();
+        output.write(b"ping").await.unwrap();
+        output.close().await.unwrap();
+        let buffer = accepted.input().read_exactly(4).await.unwrap();
+        assert_eq!(&*buffer, b"ping");
+    }
+}
@@ -0,0 +1,302 @@
+use crate::{sleep, spawn, Clock, Duration, Gate, GateClosedError, JoinHandle};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::future::Future;
+use std::rc::Rc;
+
+/// A handle to a task spawned with [`spawn_periodic`].
+///
+/// Dropping the handle stops the task.
+pub struct PeriodicTask {
+    handle: JoinHandle<()>,
+}
+
+impl PeriodicTask {
+    /// Stops the task, so that no further ticks are run.
+    ///
+    /// A tick that is already running is interrupted at its next suspension point
+    /// (see [`JoinHandle::abort`]).
+    pub fn stop(&self) {
+        self.handle.abort();
+    }
+
+    /// Returns `true` if the task has been stopped.
+    pub fn is_stopped(&self) -> bool {
+        self.handle.is_finished()
+    }
+}
+
+impl Drop for PeriodicTask {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Spawns a task which runs `f` repeatedly, every `period`, until it's stopped.
+///
+/// The first run starts after `period` elapses. Each following run starts `period`
+/// after the previous one completes, so runs never overlap.
+///
+/// Unlike a periodic [`Timer`](crate::Timer), whose callback must be synchronous,
+/// `f` may return a future which is awaited on every tick.
+///
+/// This function must be called from the context of a Seastar runtime.
+pub fn spawn_periodic<ClockType, F, Fut>(period: Duration<ClockType>, mut f: F) -> PeriodicTask
+where
+    ClockType: Clock + 'static,
+    F: FnMut() -> Fut + 'static,
+    Fut: Future<Output = ()> + 'static,
+{
+    let handle = spawn(async move {
+        loop {
+            sleep(period).await;
+            f().await;
+        }
+    });
+    PeriodicTask { handle }
+}
+
+// A job registered in a `Scheduler`. Dropping it stops the job.
+struct Job {
+    task: JoinHandle<()>,
+    // Set while a run of the job is in progress, so that stopping doesn't interrupt it.
+    running: Rc<Cell<bool>>,
+    stopped: Rc<Cell<bool>>,
+}
+
+impl Job {
+    fn stop(&self) {
+        self.stopped.set(true);
+        // A running job notices that it was stopped once the run completes.
+        if !self.running.get() {
+            self.task.abort();
+        }
+    }
+}
+
+impl Drop for Job {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// A registry of named periodic jobs, run on the current shard.
+///
+/// A higher-level alternative to [`spawn_periodic`] for background maintenance,
+/// e.g. expiring cache entries or flushing metrics, where jobs are managed by name.
+/// Unlike [`PeriodicTask::stop`], cancelling a job never interrupts a run in progress.
+///
+/// The scheduler is local to the shard it was created on (it's `!Send`).
+/// Dropping it stops all of its jobs.
+pub struct Scheduler {
+    jobs: RefCell<HashMap<String, Job>>,
+    gate: Rc<Gate>,
+    drained: Cell<bool>,
+}
+
+impl Default for Scheduler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Scheduler {
+    /// Creates a scheduler with no jobs.
+    pub fn new() -> Self {
+        Scheduler {
+            jobs: RefCell::new(HashMap::new()),
+            gate: Rc::new(Gate::new()),
+            drained: Cell::new(false),
+        }
+    }
+
+    /// Registers a job named `name` which runs `f` every `period`, like [`spawn_periodic`].
+    ///
+    /// If a job with the same name is already registered, it's cancelled and replaced.
+    ///
+    /// Returns [`GateClosedError`] without registering anything if the scheduler was drained.
+    ///
+    /// This function must be called from the context of a Seastar runtime.
+    pub fn every<ClockType, F, Fut>(
+        &self,
+        name: impl Into<String>,
+        period: Duration<ClockType>,
+        mut f: F,
+    ) -> Result<(), GateClosedError>
+    where
+        ClockType: Clock + 'static,
+        F: FnMut() -> Fut + 'static,
+        Fut: Future<Output = ()> + 'static,
+    {
+        if self.drained.get() {
+            return Err(GateClosedError);
+        }
+
+        let running = Rc::new(Cell::new(false));
+        let stopped = Rc::new(Cell::new(false));
+        let gate = self.gate.clone();
+        let task = spawn({
+            let running = running.clone();
+            let stopped = stopped.clone();
+            async move {
+                loop {
+                    sleep(period).await;
+                    let Ok(_holder) = gate.enter_owned() else {
+                        return;
+                    };
+                    running.set(true);
+                    f().await;
+                    running.set(false);
+                    if stopped.get() {
+                        return;
+                    }
+                }
+            }
+        });
+
+        let job = Job {
+            task,
+            running,
+            stopped,
+        };
+        // The replaced job is stopped when dropped.
+        self.jobs.borrow_mut().insert(name.into(), job);
+        Ok(())
+    }
+
+    /// Cancels the job named `name`, so that it doesn't run anymore.
+    ///
+    /// A run of the job that is in progress completes in the background.
+    ///
+    /// Returns `false` if there was no such job.
+    pub fn cancel(&self, name: &str) -> bool {
+        self.jobs.borrow_mut().remove(name).is_some()
+    }
+
+    /// Checks whether a job named `name` is registered.
+    pub fn contains(&self, name: &str) -> bool {
+        self.jobs.borrow().contains_key(name)
+    }
+
+    /// Returns the number of registered jobs.
+    pub fn len(&self) -> usize {
+        self.jobs.borrow().len()
+    }
+
+    /// Checks whether there are no registered jobs.
+    pub fn is_empty(&self) -> bool {
+        self.jobs.borrow().is_empty()
+    }
+
+    /// Cancels all jobs and waits for the runs in progress, including the ones
+    /// of previously cancelled jobs, to complete.
+    ///
+    /// No jobs can be registered afterwards.
+    pub async fn drain(&self) {
+        if self.drained.replace(true) {
+            return;
+        }
+        self.jobs.borrow_mut().clear();
+        self.gate.close().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as seastar;
+    use crate::{ManualClock, SteadyClock};
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    // Advances `ManualClock`, giving other tasks a chance to run before and after.
+    async fn advance(duration: Duration<ManualClock>) {
+        sleep(Duration::<SteadyClock>::from_millis(1)).await;
+        ManualClock::advance(duration);
+        sleep(Duration::<SteadyClock>::from_millis(1)).await;
+    }
+
+    #[seastar::test]
+    async fn test_spawn_periodic() {
+        let ticks = Rc::new(Cell::new(0));
+        let ticks_clone = ticks.clone();
+        let task = spawn_periodic(Duration::<ManualClock>::from_millis(10), move || {
+            let ticks = ticks_clone.clone();
+            async move { ticks.set(ticks.get() + 1) }
+        });
+
+        advance(Duration::from_millis(5)).await;
+        assert_eq!(ticks.get(), 0);
+        advance(Duration::from_millis(5)).await;
+        assert_eq!(ticks.get(), 1);
+        for expected in 2..=3 {
+            advance(Duration::from_millis(10)).await;
+            assert_eq!(ticks.get(), expected);
+        }
+
+        task.stop();
+        advance(Duration::from_millis(10)).await;
+        assert!(task.is_stopped());
+        advance(Duration::from_millis(10)).await;
+        assert_eq!(ticks.get(), 3);
+    }
+
+    #[seastar::test]
+    async fn test_scheduler() {
+        let scheduler = Scheduler::new();
+        let counter = |runs: &Rc<Cell<u32>>| {
+            let runs = runs.clone();
+            move || {
+                let runs = runs.clone();
+                async move { runs.set(runs.get() + 1) }
+            }
+        };
+        let fast = Rc::new(Cell::new(0));
+        let slow = Rc::new(Cell::new(0));
+        let period = |millis| Duration::<ManualClock>::from_millis(millis);
+        scheduler.every("fast", period(10), counter(&fast)).unwrap();
+        scheduler.every("slow", period(30), counter(&slow)).unwrap();
+        assert_eq!(scheduler.len(), 2);
+
+        for _ in 0..6 {
+            advance(Duration::from_millis(10)).await;
+        }
+        assert_eq!((fast.get(), slow.get()), (6, 2));
+
+        assert!(scheduler.cancel("fast"));
+        assert!(!scheduler.cancel("fast"));
+        assert!(!scheduler.contains("fast"));
+        for _ in 0..3 {
+            advance(Duration::from_millis(10)).await;
+        }
+        assert_eq!((fast.get(), slow.get()), (6, 3));
+
+        scheduler.drain().await;
+        assert!(scheduler.is_empty());
+        advance(Duration::from_millis(30)).await;
+        assert_eq!(slow.get(), 3);
+        assert!(scheduler.every("late", period(10), counter(&fast)).is_err());
+    }
+
+    #[seastar::test]
+    async fn test_scheduler_drain_waits_for_running_jobs() {
+        let scheduler = Scheduler::new();
+        let finished = Rc::new(Cell::new(false));
+        let finished_clone = finished.clone();
+        scheduler
+            .every("job", Duration::<ManualClock>::from_millis(10), move || {
+                let finished = finished_clone.clone();
+                async move {
+                    sleep(Duration::<SteadyClock>::from_millis(20)).await;
+                    finished.set(true);
+                }
+            })
+            .unwrap();
+
+        // Starts a run of the job, which is suspended in the sleep.
+        advance(Duration::from_millis(10)).await;
+        assert!(!finished.get());
+        scheduler.drain().await;
+        assert!(finished.get());
+    }
+}
@@ -1,3 +1,5 @@
+use crate::yield_now;
+
 #[cxx::bridge(namespace = "seastar")]
 mod ffi {
     unsafe extern "C++" {
@@ -11,6 +13,38 @@ mod ffi {
 
 pub use ffi::need_preempt;
 
+/// Applies `f` to every item of `iter`, yielding to the reactor whenever
+/// the current task exhausts its time quota (see [`need_preempt`]).
+///
+/// Meant for processing large in-memory collections without starving other tasks
+/// on the shard. `f` itself is synchronous, so it should be quick for a single item.
+///
+/// This function must be called from the context of a Seastar runtime.
+///
+/// # Example
+///
+/// ```rust
+/// #[seastar::test]
+/// async fn for_each_preemptible_example() {
+///     let mut sum = 0u64;
+///     seastar::for_each_preemptible(0..1_000_000u64, |i| sum += i).await;
+///     assert_eq!(sum, 499_999_500_000);
+/// }
+/// ```
+pub async fn for_each_preemptible<I, F>(iter: I, mut f: F)
+where
+    I: IntoIterator,
+    F: FnMut(I::Item),
+{
+    crate::assert_runtime_is_running();
+    for item in iter {
+        f(item);
+        if need_preempt() {
+            yield_now().await;
+        }
+    }
+}
+
 #[test]
 fn test_preempt_smoke_test() {
     // The need_preempt function "works" even if there is no Seastar runtime
@@ -20,3 +54,38 @@ fn test_preempt_smoke_test() {
     assert!(!need_preempt());
     assert!(!need_preempt());
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as seastar;
+    use crate::spawn;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    #[seastar::test]
+    async fn test_for_each_preemptible_lets_others_run() {
+        let progress = Rc::new(Cell::new(0));
+        let progress_clone = progress.clone();
+        let other = spawn(async move {
+            loop {
+                progress_clone.set(progress_clone.get() + 1);
+                yield_now().await;
+            }
+        });
+
+        // Busy-waits for ~100ms in total, way longer than the task quota.
+        let mut seen_progress = 0;
+        for_each_preemptible(0..10_000, |_| {
+            let start = std::time::Instant::now();
+            while start.elapsed() < std::time::Duration::from_micros(10) {
+                std::hint::spin_loop();
+            }
+            seen_progress = progress.get();
+        })
+        .await;
+        other.abort();
+
+        assert!(seen_progress > 0);
+    }
+}
@@ -0,0 +1,69 @@
+#include "queue.hh"
+
+namespace seastar_ffi {
+namespace queue {
+
+rust_item::rust_item(uint8_t* inner, rust::Fn<void(uint8_t*)> dropper)
+: _inner(inner), _dropper(dropper) {}
+
+rust_item::rust_item(rust_item&& other) noexcept
+: _inner(std::exchange(other._inner, nullptr)), _dropper(other._dropper) {}
+
+rust_item& rust_item::operator=(rust_item&& other) noexcept {
+    if (this != &other) {
+        if (_inner) {
+            _dropper(_inner);
+        }
+        _inner = std::exchange(other._inner, nullptr);
+        _dropper = other._dropper;
+    }
+    return *this;
+}
+
+rust_item::~rust_item() {
+    if (_inner) {
+        _dropper(_inner);
+    }
+}
+
+uint8_t* rust_item::release() {
+    return std::exchange(_inner, nullptr);
+}
+
+std::unique_ptr<queue> new_queue(size_t max_size) {
+    return std::make_unique<queue>(max_size);
+}
+
+bool try_push(const std::unique_ptr<queue>& q, uint8_t* item, rust::Fn<void(uint8_t*)> dropper) {
+    if (q->full()) {
+        // The caller keeps the ownership of the item.
+        return false;
+    }
+    return q->push(rust_item(item, dropper));
+}
+
+uint8_t* try_pop(const std::unique_ptr<queue>& q) {
+    if (q->empty()) {
+        return nullptr;
+    }
+    return q->pop().release();
+}
+
+VoidFuture not_empty(const std::unique_ptr<queue>& q) {
+    co_await q->not_empty();
+}
+
+VoidFuture not_full(const std::unique_ptr<queue>& q) {
+    co_await q->not_full();
+}
+
+size_t size(const std::unique_ptr<queue>& q) {
+    return q->size();
+}
+
+size_t max_size(const std::unique_ptr<queue>& q) {
+    return q->max_size();
+}
+
+} // namespace queue
+} // namespace seastar_ffi
@@ -0,0 +1,40 @@
+#pragma once
+
+#include "cxx_async_futures.hh"
+#include <seastar/core/queue.hh>
+
+namespace seastar_ffi {
+namespace queue {
+
+// Owns a boxed Rust item, dropping it unless it's released.
+class rust_item {
+private:
+    uint8_t* _inner;
+    rust::Fn<void(uint8_t*)> _dropper;
+public:
+    rust_item(uint8_t* inner, rust::Fn<void(uint8_t*)> dropper);
+    rust_item(rust_item&& other) noexcept;
+    rust_item& operator=(rust_item&& other) noexcept;
+    ~rust_item();
+
+    uint8_t* release();
+};
+
+using queue = seastar::queue<rust_item>;
+
+std::unique_ptr<queue> new_queue(size_t max_size);
+
+bool try_push(const std::unique_ptr<queue>& q, uint8_t* item, rust::Fn<void(uint8_t*)> dropper);
+
+uint8_t* try_pop(const std::unique_ptr<queue>& q);
+
+VoidFuture not_empty(const std::unique_ptr<queue>& q);
+
+VoidFuture not_full(const std::unique_ptr<queue>& q);
+
+size_t size(const std::unique_ptr<queue>& q);
+
+size_t max_size(const std::unique_ptr<queue>& q);
+
+} // namespace queue
+} // namespace seastar_ffi
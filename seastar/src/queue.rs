@@ -0,0 +1,181 @@
+use crate::ffi_utils::get_dropper_noarg;
+use cxx::UniquePtr;
+use std::marker::PhantomData;
+
+#[cxx::bridge(namespace = "seastar_ffi::queue")]
+mod ffi {
+    unsafe extern "C++" {
+        include!("seastar/src/queue.hh");
+
+        type queue;
+
+        #[namespace = "seastar_ffi"]
+        type VoidFuture = crate::cxx_async_futures::VoidFuture;
+
+        fn new_queue(max_size: usize) -> UniquePtr<queue>;
+        unsafe fn try_push(
+            q: &UniquePtr<queue>,
+            item: *mut u8,
+            dropper: unsafe fn(*mut u8),
+        ) -> bool;
+        fn try_pop(q: &UniquePtr<queue>) -> *mut u8;
+        fn not_empty(q: &UniquePtr<queue>) -> VoidFuture;
+        fn not_full(q: &UniquePtr<queue>) -> VoidFuture;
+        fn size(q: &UniquePtr<queue>) -> usize;
+        fn max_size(q: &UniquePtr<queue>) -> usize;
+    }
+}
+
+use ffi::*;
+
+/// Asynchronous single-producer single-consumer queue with limited capacity.
+///
+/// There can be at most one producer-side and at most one consumer-side operation active at any time.
+/// Operations returning a future are considered to be active until the future resolves.
+///
+/// The queue is local to the shard it was created on. To pass items between shards,
+/// move them with [`submit_to`](crate::submit_to).
+///
+/// Wraps `seastar::queue`.
+pub struct Queue<T: Send> {
+    inner: UniquePtr<queue>,
+    _ty: PhantomData<T>,
+}
+
+impl<T: Send> Queue<T> {
+    /// Creates a queue holding at most `max_size` items.
+    pub fn new(max_size: usize) -> Self {
+        Queue {
+            inner: new_queue(max_size),
+            _ty: PhantomData,
+        }
+    }
+
+    /// Pushes an item into the queue if there is room.
+    ///
+    /// Returns the item back if the queue is full.
+    pub fn try_push(&self, item: T) -> Result<(), T> {
+        let raw_item = Box::into_raw(Box::new(item));
+        let pushed =
+            unsafe { try_push(&self.inner, raw_item as *mut u8, get_dropper_noarg::<T>()) };
+        if pushed {
+            Ok(())
+        } else {
+            Err(*unsafe { Box::from_raw(raw_item) })
+        }
+    }
+
+    /// Pushes an item into the queue, waiting for room if it's full.
+    ///
+    /// Dropping the returned future before it completes drops the item.
+    pub async fn push(&self, mut item: T) {
+        crate::assert_runtime_is_running();
+        loop {
+            match self.try_push(item) {
+                Ok(()) => return,
+                Err(returned) => item = returned,
+            }
+            not_full(&self.inner).await.unwrap();
+        }
+    }
+
+    /// Pops an item from the queue if it isn't empty.
+    pub fn try_pop(&self) -> Option<T> {
+        let raw_item = try_pop(&self.inner);
+        if raw_item.is_null() {
+            None
+        } else {
+            Some(*unsafe { Box::from_raw(raw_item as *mut T) })
+        }
+    }
+
+    /// Pops an item from the queue, waiting for one if it's empty.
+    pub async fn pop(&self) -> T {
+        crate::assert_runtime_is_running();
+        loop {
+            if let Some(item) = self.try_pop() {
+                return item;
+            }
+            not_empty(&self.inner).await.unwrap();
+        }
+    }
+
+    /// Returns the number of items in the queue.
+    pub fn size(&self) -> usize {
+        size(&self.inner)
+    }
+
+    /// Returns the maximum number of items the queue can hold.
+    pub fn max_size(&self) -> usize {
+        max_size(&self.inner)
+    }
+
+    /// Checks whether the queue is empty.
+    pub fn empty(&self) -> bool {
+        self.size() == 0
+    }
+
+    /// Checks whether the queue is full.
+    pub fn full(&self) -> bool {
+        self.size() >= self.max_size()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as seastar;
+    use crate::{sleep, Duration, SteadyClock};
+    use futures::join;
+    use std::sync::Arc;
+
+    #[seastar::test]
+    async fn test_queue_try_push_pop() {
+        let queue = Queue::new(2);
+        assert!(queue.empty());
+        assert!(queue.try_push(1).is_ok());
+        assert!(queue.try_push(2).is_ok());
+        assert!(queue.full());
+        assert_eq!(queue.try_push(3), Err(3));
+        assert_eq!(queue.try_pop(), Some(1));
+        assert_eq!(queue.try_pop(), Some(2));
+        assert_eq!(queue.try_pop(), None);
+    }
+
+    #[seastar::test]
+    async fn test_queue_producer_consumer() {
+        let queue = Queue::new(3);
+
+        let producer = async {
+            for i in 0..20 {
+                queue.push(format!("item {i}")).await;
+                assert!(queue.size() <= 3);
+            }
+        };
+        let consumer = async {
+            let mut items = vec![];
+            for _ in 0..20 {
+                items.push(queue.pop().await);
+                sleep(Duration::<SteadyClock>::from_millis(1)).await;
+            }
+            items
+        };
+
+        let ((), items) = join!(producer, consumer);
+        assert_eq!(
+            items,
+            (0..20).map(|i| format!("item {i}")).collect::<Vec<_>>()
+        );
+        assert!(queue.empty());
+    }
+
+    #[seastar::test]
+    async fn test_queue_drops_remaining_items() {
+        let item = Arc::new(());
+        let queue = Queue::new(1);
+        queue.try_push(item.clone()).unwrap();
+        assert_eq!(Arc::strong_count(&item), 2);
+        drop(queue);
+        assert_eq!(Arc::strong_count(&item), 1);
+    }
+}
@@ -0,0 +1,77 @@
+#include "reactor.hh"
+#include "clocks.hh"
+#include <seastar/core/reactor.hh>
+#include <seastar/core/later.hh>
+#include <seastar/core/make_task.hh>
+#include <atomic>
+
+namespace seastar_ffi {
+namespace reactor {
+
+using seastar_ffi::clocks::to_nanos;
+
+VoidFuture yield_now() {
+    co_await seastar::yield();
+}
+
+uint64_t get_tasks_processed() {
+    return seastar::engine().get_sched_stats().tasks_processed;
+}
+
+// Resolves to whether no other task was ready to run when it was called.
+//
+// A marker task is scheduled at the end of the run queue. The reactor counts tasks after
+// they finish, so when the marker runs, the counter grew by exactly one (the caller's task)
+// if no other task ran in between.
+static seastar::future<bool> no_other_task_ran() {
+    auto scheduled_at = get_tasks_processed();
+    seastar::promise<uint64_t> p;
+    auto f = p.get_future();
+    seastar::schedule(seastar::make_task([p = std::move(p)] () mutable {
+        p.set_value(get_tasks_processed());
+    }));
+    auto run_at = co_await std::move(f);
+    co_return run_at - scheduled_at == 1;
+}
+
+VoidFuture run_until_idle(uint32_t max_passes) {
+    for (uint32_t i = 0; i < max_passes; ++i) {
+        if (co_await no_other_task_ran()) {
+            co_return;
+        }
+    }
+}
+
+int64_t get_total_busy_time() {
+    return to_nanos(seastar::engine().total_busy_time()).count();
+}
+
+int64_t get_total_idle_time() {
+    return to_nanos(seastar::engine().total_idle_time()).count();
+}
+
+int64_t get_total_steal_time() {
+    return to_nanos(seastar::engine().total_steal_time()).count();
+}
+
+int64_t get_stall_threshold() {
+    return to_nanos(seastar::engine().get_blocked_reactor_notify_ms()).count();
+}
+
+// Incremented from the stall detector's signal handler, which runs on the shard's thread
+// and interrupts the blocking task. Only lock-free atomics are safe to update from there.
+static thread_local std::atomic<uint64_t> stall_count{0};
+static_assert(std::atomic<uint64_t>::is_always_lock_free);
+
+uint64_t get_stall_count() {
+    return stall_count.load(std::memory_order_relaxed);
+}
+
+void count_stalls() {
+    seastar::engine().set_stall_detector_report_function([] {
+        stall_count.fetch_add(1, std::memory_order_relaxed);
+    });
+}
+
+} // namespace reactor
+} // namespace seastar_ffi
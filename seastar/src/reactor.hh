@@ -0,0 +1,28 @@
+#pragma once
+
+#include "cxx_async_futures.hh"
+#include <cstdint>
+
+namespace seastar_ffi {
+namespace reactor {
+
+VoidFuture yield_now();
+
+uint64_t get_tasks_processed();
+
+VoidFuture run_until_idle(uint32_t max_passes);
+
+int64_t get_total_busy_time();
+
+int64_t get_total_idle_time();
+
+int64_t get_total_steal_time();
+
+int64_t get_stall_threshold();
+
+uint64_t get_stall_count();
+
+void count_stalls();
+
+} // namespace reactor
+} // namespace seastar_ffi
@@ -0,0 +1,248 @@
+use crate::{Duration, SteadyClock};
+
+#[cxx::bridge(namespace = "seastar_ffi::reactor")]
+mod ffi {
+    unsafe extern "C++" {
+        include!("seastar/src/reactor.hh");
+
+        #[namespace = "seastar_ffi"]
+        type VoidFuture = crate::cxx_async_futures::VoidFuture;
+
+        fn yield_now() -> VoidFuture;
+
+        fn get_tasks_processed() -> u64;
+        fn run_until_idle(max_passes: u32) -> VoidFuture;
+        fn get_total_busy_time() -> i64;
+        fn get_total_idle_time() -> i64;
+        fn get_total_steal_time() -> i64;
+
+        fn get_stall_threshold() -> i64;
+        fn get_stall_count() -> u64;
+        fn count_stalls();
+    }
+}
+
+/// A snapshot of the statistics of the reactor running on the current shard.
+///
+/// Obtained through [`reactor_stats`].
+///
+/// The number of polls isn't included: `seastar::reactor` only counts them in a private
+/// member, exported as the `reactor_polls` metric, without a public accessor. Read it from
+/// the Prometheus endpoint instead (see [`Options::enable_prometheus`](crate::Options::enable_prometheus)).
+#[derive(Clone, Copy, Debug)]
+pub struct ReactorStats {
+    /// Total number of tasks processed by the reactor.
+    pub tasks_processed: u64,
+    /// Total time the reactor spent running tasks and polling.
+    pub busy_time: Duration<SteadyClock>,
+    /// Total time the reactor spent sleeping.
+    pub idle_time: Duration<SteadyClock>,
+    /// Total time stolen from the reactor by other processes or the hypervisor.
+    pub steal_time: Duration<SteadyClock>,
+    /// Fraction (between 0 and 1) of the reactor's lifetime it spent being busy.
+    pub load: f64,
+}
+
+/// Returns the statistics of the reactor running on the current shard.
+///
+/// The values are read directly from `seastar::reactor`, so no metrics
+/// endpoint is needed to observe them.
+///
+/// This function must be called from the context of a Seastar runtime.
+pub fn reactor_stats() -> ReactorStats {
+    crate::assert_runtime_is_running();
+
+    let busy_time = Duration::from_nanos(ffi::get_total_busy_time());
+    let idle_time = Duration::from_nanos(ffi::get_total_idle_time());
+    let total = busy_time.as_nanos() + idle_time.as_nanos();
+    let load = if total > 0 {
+        busy_time.as_nanos() as f64 / total as f64
+    } else {
+        0.0
+    };
+
+    ReactorStats {
+        tasks_processed: ffi::get_tasks_processed(),
+        busy_time,
+        idle_time,
+        steal_time: Duration::from_nanos(ffi::get_total_steal_time()),
+        load,
+    }
+}
+
+/// Returns the threshold above which a task blocking the reactor on the current shard
+/// is reported as a stall.
+///
+/// Configured with [`Options::set_blocked_reactor_notify`](crate::Options::set_blocked_reactor_notify).
+///
+/// This function must be called from the context of a Seastar runtime.
+pub fn reactor_stall_threshold() -> Duration<SteadyClock> {
+    crate::assert_runtime_is_running();
+    Duration::from_nanos(ffi::get_stall_threshold())
+}
+
+/// Makes the reactor on the current shard count its stalls, i.e. the tasks which run
+/// without yielding for longer than [`reactor_stall_threshold`].
+///
+/// Stalls are detected from a signal handler interrupting the blocking task, where
+/// no Rust code can run safely. This replaces Seastar's default stall report, which logs
+/// a backtrace, with one which only increments a counter. Read it with [`reactor_stall_count`],
+/// or wait for it to grow with [`reactor_stalled`]. Reports are rate limited like the default ones.
+///
+/// This function must be called from the context of a Seastar runtime.
+pub fn count_reactor_stalls() {
+    crate::assert_runtime_is_running();
+    ffi::count_stalls();
+}
+
+/// Returns the number of reactor stalls counted on the current shard.
+///
+/// Stalls are only counted after [`count_reactor_stalls`] is called on the shard.
+///
+/// This function must be called from the context of a Seastar runtime.
+pub fn reactor_stall_count() -> u64 {
+    crate::assert_runtime_is_running();
+    ffi::get_stall_count()
+}
+
+/// Waits until a new stall of the reactor on the current shard is counted,
+/// and returns the number of stalls counted so far.
+///
+/// The signal handler counting the stalls can't wake up tasks, so the counter is
+/// checked every [`reactor_stall_threshold`] instead. Stalls are only counted after
+/// [`count_reactor_stalls`] is called on the shard, otherwise this never returns.
+///
+/// This function must be called from the context of a Seastar runtime.
+pub async fn reactor_stalled() -> u64 {
+    crate::assert_runtime_is_running();
+    let seen = ffi::get_stall_count();
+    let interval = reactor_stall_threshold();
+    loop {
+        crate::sleep(interval).await;
+        let count = ffi::get_stall_count();
+        if count > seen {
+            return count;
+        }
+    }
+}
+
+/// Yields to the reactor, letting the tasks that are ready to run execute
+/// before the current task continues.
+///
+/// Equivalent of `seastar::yield`.
+pub async fn yield_now() {
+    crate::assert_runtime_is_running();
+    ffi::yield_now().await.unwrap();
+}
+
+// Upper bound on the number of passes over the run queue done by `run_until_idle`.
+const RUN_UNTIL_IDLE_MAX_PASSES: u32 = 10_000;
+
+/// Yields to the reactor until no other tasks on the current shard are ready to run.
+///
+/// Useful in tests, to let spawned tasks make progress without a real [`sleep`](crate::sleep).
+/// It complements [`ManualClock`](crate::ManualClock): advance the clock, then run
+/// until idle to let the tasks woken up by its timers finish.
+///
+/// Each pass schedules a task at the end of the run queue and checks whether any other
+/// task ran before it. The reactor is considered idle once none did, i.e. the run queue
+/// was empty. Tasks waiting for I/O or timers are not ready, so they are not waited for.
+/// If some task keeps rescheduling itself, `run_until_idle` gives up after a bounded
+/// number of passes.
+pub async fn run_until_idle() {
+    crate::assert_runtime_is_running();
+    ffi::run_until_idle(RUN_UNTIL_IDLE_MAX_PASSES)
+        .await
+        .unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as seastar;
+    use crate::spawn;
+
+    #[seastar::test]
+    async fn test_reactor_stats() {
+        for i in 0..10 {
+            assert_eq!(spawn(async move { i }).await, i);
+        }
+
+        let stats = reactor_stats();
+        assert!(stats.tasks_processed > 0);
+        assert!((0.0..=1.0).contains(&stats.load));
+    }
+
+    // The stall detector measures CPU time, so the reactor has to spin instead of sleeping.
+    fn stall_reactor() {
+        let spin = std::time::Duration::from_nanos(reactor_stall_threshold().as_nanos() as u64 * 4);
+        let start = std::time::Instant::now();
+        while start.elapsed() < spin {
+            std::hint::spin_loop();
+        }
+    }
+
+    #[seastar::test]
+    async fn test_reactor_stall_count() {
+        assert!(reactor_stall_threshold().as_millis() > 0);
+        count_reactor_stalls();
+
+        let before = reactor_stall_count();
+        stall_reactor();
+        assert!(reactor_stall_count() > before);
+    }
+
+    #[seastar::test]
+    async fn test_reactor_stalled() {
+        count_reactor_stalls();
+
+        let before = reactor_stall_count();
+        let (count, ()) = futures::join!(reactor_stalled(), async {
+            yield_now().await;
+            stall_reactor();
+        });
+        assert!(count > before);
+    }
+
+    #[seastar::test]
+    async fn test_run_until_idle_drains_spawned_tasks() {
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        let counter = Rc::new(Cell::new(0));
+        for _ in 0..5 {
+            let counter = counter.clone();
+            // Each task spawns a chain of further tasks.
+            let _ = spawn(async move {
+                for _ in 0..10 {
+                    let counter = counter.clone();
+                    spawn(async move { counter.set(counter.get() + 1) }).await;
+                    yield_now().await;
+                }
+            });
+        }
+
+        run_until_idle().await;
+        assert_eq!(counter.get(), 50);
+    }
+
+    #[seastar::test]
+    async fn test_run_until_idle_gives_up_on_busy_reactor() {
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        let stop = Rc::new(Cell::new(false));
+        let stop_clone = stop.clone();
+        let busy = spawn(async move {
+            while !stop_clone.get() {
+                yield_now().await;
+            }
+        });
+
+        let before = reactor_stats().tasks_processed;
+        run_until_idle().await;
+        assert!(reactor_stats().tasks_processed - before >= RUN_UNTIL_IDLE_MAX_PASSES as u64);
+        stop.set(true);
+        busy.await;
+    }
+}
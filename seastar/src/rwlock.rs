@@ -0,0 +1,215 @@
+use crate::{Semaphore, SemaphoreUnits};
+use std::cell::UnsafeCell;
+use std::ops::{Deref, DerefMut};
+
+// Readers take a single unit of the semaphore and writers take all of them,
+// like in `seastar::basic_rwlock`.
+const MAX_READERS: usize = isize::MAX as usize;
+
+/// An asynchronous reader-writer lock protecting a shard-local value.
+///
+/// Allows either any number of readers or a single writer at a time, also across await points.
+/// Waiters are served in FIFO order, so a waiting writer isn't starved by readers arriving
+/// after it. Suits read-heavy state, e.g. per-shard caches.
+///
+/// Built on a [`Semaphore`], like `seastar::rwlock`.
+pub struct RwLock<T> {
+    sem: Semaphore,
+    value: UnsafeCell<T>,
+}
+
+impl<T> RwLock<T> {
+    /// Creates an unlocked lock protecting `value`.
+    pub fn new(value: T) -> Self {
+        RwLock {
+            sem: Semaphore::new(MAX_READERS),
+            value: UnsafeCell::new(value),
+        }
+    }
+
+    /// Locks for reading, waiting until the writer holding the lock
+    /// and all the waiters queued before are done.
+    ///
+    /// Cancel safe: dropping the returned future before it completes withdraws the request,
+    /// like with [`Semaphore::wait`].
+    pub async fn read(&self) -> RwLockReadGuard<'_, T> {
+        RwLockReadGuard {
+            lock: self,
+            _units: self.sem.get_units(1).await,
+        }
+    }
+
+    /// Locks for writing, waiting until the readers or the writer holding the lock
+    /// and all the waiters queued before are done.
+    ///
+    /// Cancel safe, like [`RwLock::read`].
+    pub async fn write(&self) -> RwLockWriteGuard<'_, T> {
+        RwLockWriteGuard {
+            lock: self,
+            _units: self.sem.get_units(MAX_READERS).await,
+        }
+    }
+
+    /// Locks for reading if it's possible right away and nobody is waiting for the lock.
+    pub fn try_read(&self) -> Option<RwLockReadGuard<'_, T>> {
+        self.sem.try_get_units(1).map(|units| RwLockReadGuard {
+            lock: self,
+            _units: units,
+        })
+    }
+
+    /// Locks for writing if it's possible right away and nobody is waiting for the lock.
+    pub fn try_write(&self) -> Option<RwLockWriteGuard<'_, T>> {
+        self.sem
+            .try_get_units(MAX_READERS)
+            .map(|units| RwLockWriteGuard {
+                lock: self,
+                _units: units,
+            })
+    }
+
+    /// Returns a mutable reference to the value.
+    ///
+    /// Doesn't need to take the lock, as the exclusive borrow guarantees that it isn't held.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.value.get_mut()
+    }
+
+    /// Consumes the lock, returning the value.
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+}
+
+impl<T: Default> Default for RwLock<T> {
+    fn default() -> Self {
+        RwLock::new(T::default())
+    }
+}
+
+/// A read lock held on a [`RwLock`], giving shared access to the protected value.
+///
+/// The lock is released when the guard is dropped (RAII).
+pub struct RwLockReadGuard<'a, T> {
+    lock: &'a RwLock<T>,
+    _units: SemaphoreUnits<'a>,
+}
+
+impl<T> Deref for RwLockReadGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        // Writers can't hold the lock while any reader does.
+        unsafe { &*self.lock.value.get() }
+    }
+}
+
+/// A write lock held on a [`RwLock`], giving exclusive access to the protected value.
+///
+/// The lock is released when the guard is dropped (RAII).
+pub struct RwLockWriteGuard<'a, T> {
+    lock: &'a RwLock<T>,
+    _units: SemaphoreUnits<'a>,
+}
+
+impl<T> Deref for RwLockWriteGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        // Holding all of the semaphore's units guarantees exclusive access.
+        unsafe { &*self.lock.value.get() }
+    }
+}
+
+impl<T> DerefMut for RwLockWriteGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { &mut *self.lock.value.get() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as seastar;
+    use crate::{sleep, Duration, SteadyClock};
+    use futures::join;
+    use std::cell::{Cell, RefCell};
+
+    #[seastar::test]
+    async fn test_rwlock_try_read_write() {
+        let lock = RwLock::new(1);
+        let read1 = lock.try_read().unwrap();
+        let read2 = lock.try_read().unwrap();
+        assert_eq!(*read1 + *read2, 2);
+        assert!(lock.try_write().is_none());
+        drop((read1, read2));
+
+        let mut write = lock.try_write().unwrap();
+        *write = 2;
+        assert!(lock.try_read().is_none());
+        assert!(lock.try_write().is_none());
+        drop(write);
+        assert_eq!(lock.into_inner(), 2);
+    }
+
+    #[seastar::test]
+    async fn test_rwlock_concurrent_readers() {
+        let lock = RwLock::new(42);
+        let readers = Cell::new(0);
+        let max_readers = Cell::new(0);
+
+        let reader = || {
+            let (lock, readers, max_readers) = (&lock, &readers, &max_readers);
+            async move {
+                let guard = lock.read().await;
+                readers.set(readers.get() + 1);
+                max_readers.set(max_readers.get().max(readers.get()));
+                sleep(Duration::<SteadyClock>::from_millis(2)).await;
+                assert_eq!(*guard, 42);
+                readers.set(readers.get() - 1);
+            }
+        };
+
+        join!(reader(), reader(), reader());
+        assert_eq!(max_readers.get(), 3);
+    }
+
+    #[seastar::test]
+    async fn test_rwlock_writer_excludes_readers() {
+        let lock = RwLock::new(0);
+        let events = RefCell::new(Vec::new());
+
+        let writer = async {
+            let mut guard = lock.write().await;
+            events.borrow_mut().push("write start");
+            sleep(Duration::<SteadyClock>::from_millis(2)).await;
+            *guard = 1;
+            events.borrow_mut().push("write end");
+        };
+        let reader = async {
+            let guard = lock.read().await;
+            events.borrow_mut().push("read");
+            *guard
+        };
+
+        // join! polls the writer first, so it takes the lock before the reader.
+        let ((), read) = join!(writer, reader);
+        assert_eq!(read, 1);
+        assert_eq!(*events.borrow(), vec!["write start", "write end", "read"]);
+    }
+
+    #[seastar::test]
+    async fn test_rwlock_write_dropped_while_waiting() {
+        let lock = RwLock::new(0);
+        let read_guard = lock.read().await;
+        let mut write = Box::pin(lock.write());
+        assert!(futures::poll!(&mut write).is_pending());
+        // The queued writer holds back new readers.
+        assert!(lock.try_read().is_none());
+        drop(write);
+        assert!(lock.try_read().is_some());
+        drop(read_guard);
+        *lock.write().await = 1;
+        assert_eq!(*lock.read().await, 1);
+    }
+}
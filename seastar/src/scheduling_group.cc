@@ -0,0 +1,27 @@
+#include "scheduling_group.hh"
+
+namespace seastar_ffi {
+namespace scheduling_group {
+
+// Resolves to the index of the created group.
+IntFuture create_scheduling_group(rust::str name, float shares) {
+    seastar::sstring sg_name(name.data(), name.size());
+    auto sg = co_await seastar::create_scheduling_group(std::move(sg_name), shares);
+    co_return seastar::internal::scheduling_group_index(sg);
+}
+
+VoidFuture destroy_scheduling_group(uint32_t index) {
+    co_await seastar::destroy_scheduling_group(seastar::internal::scheduling_group_from_index(index));
+}
+
+rust::String scheduling_group_name(uint32_t index) {
+    const auto& name = seastar::internal::scheduling_group_from_index(index).name();
+    return rust::String(name.data(), name.size());
+}
+
+uint32_t current_scheduling_group() {
+    return seastar::internal::scheduling_group_index(seastar::current_scheduling_group());
+}
+
+} // namespace scheduling_group
+} // namespace seastar_ffi
@@ -0,0 +1,19 @@
+#pragma once
+
+#include "cxx_async_futures.hh"
+#include "rust/cxx.h"
+#include <seastar/core/scheduling.hh>
+
+namespace seastar_ffi {
+namespace scheduling_group {
+
+IntFuture create_scheduling_group(rust::str name, float shares);
+
+VoidFuture destroy_scheduling_group(uint32_t index);
+
+rust::String scheduling_group_name(uint32_t index);
+
+uint32_t current_scheduling_group();
+
+} // namespace scheduling_group
+} // namespace seastar_ffi
@@ -0,0 +1,188 @@
+use crate::submit_to;
+use ffi::*;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use thiserror::Error;
+
+#[cxx::bridge]
+mod ffi {
+    #[namespace = "seastar_ffi"]
+    unsafe extern "C++" {
+        type VoidFuture = crate::cxx_async_futures::VoidFuture;
+        type IntFuture = crate::cxx_async_futures::IntFuture;
+    }
+
+    #[namespace = "seastar_ffi::scheduling_group"]
+    unsafe extern "C++" {
+        include!("seastar/src/scheduling_group.hh");
+
+        fn create_scheduling_group(name: &str, shares: f32) -> IntFuture;
+        fn destroy_scheduling_group(index: u32) -> VoidFuture;
+        fn scheduling_group_name(index: u32) -> String;
+        fn current_scheduling_group() -> u32;
+    }
+}
+
+thread_local! {
+    // Groups created by this module, by name. Only accessed on shard 0,
+    // so that all shards see the same groups.
+    static GROUPS: RefCell<HashMap<String, SchedulingGroup>> = RefCell::new(HashMap::new());
+    // All groups created by this module, in creation order. Only accessed on shard 0.
+    static CREATED: RefCell<Vec<SchedulingGroup>> = RefCell::new(Vec::new());
+}
+
+/// Error returned when Seastar fails to create a scheduling group
+/// (e.g. because the limit of scheduling groups was reached).
+#[derive(Error, Debug)]
+#[error("SchedulingGroupError: {0}")]
+pub struct SchedulingGroupError(String);
+
+/// Identifies a group of tasks that share CPU time, in proportion to the group's shares.
+///
+/// Scheduling groups are global - a group created on any shard exists on all shards.
+///
+/// Equivalent of `seastar::scheduling_group`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SchedulingGroup {
+    index: u32,
+}
+
+impl SchedulingGroup {
+    /// Creates a new scheduling group with the given name and shares.
+    ///
+    /// A new group is created even if one with the same name already exists.
+    /// Use [`create_or_get`](SchedulingGroup::create_or_get) to share a group by name.
+    pub async fn create(name: &str, shares: f32) -> Result<Self, SchedulingGroupError> {
+        let group = Self::create_unregistered(name, shares).await?;
+        let name = name.to_owned();
+        submit_to(0, move || async move {
+            Self::register(name, group);
+            CREATED.with(|created| created.borrow_mut().push(group));
+        })
+        .await;
+        Ok(group)
+    }
+
+    /// Returns the scheduling group with the given name if one was created before,
+    /// otherwise creates it with the given shares.
+    ///
+    /// Only groups created with [`create`](SchedulingGroup::create) and `create_or_get`
+    /// are taken into account. If multiple groups with the same name exist,
+    /// the first one created is returned.
+    ///
+    /// Concurrent calls may create more groups than needed, the extra ones are destroyed.
+    /// Failing to destroy one is reported as an error too, even though the group with
+    /// the given name exists then.
+    pub async fn create_or_get(name: &str, shares: f32) -> Result<Self, SchedulingGroupError> {
+        let name = name.to_owned();
+        submit_to(0, move || async move {
+            let existing = GROUPS.with(|groups| groups.borrow().get(&name).copied());
+            if let Some(group) = existing {
+                return Ok(group);
+            }
+
+            let group = Self::create_unregistered(&name, shares).await?;
+            let registered = Self::register(name, group);
+            if registered != group {
+                // Another call created the group while we were creating ours.
+                destroy_scheduling_group(group.index)
+                    .await
+                    .map_err(|e| SchedulingGroupError(e.to_string()))?;
+            } else {
+                CREATED.with(|created| created.borrow_mut().push(group));
+            }
+            Ok(registered)
+        })
+        .await
+    }
+
+    /// Returns all existing scheduling groups: the main one, followed by the ones created with
+    /// [`create`](SchedulingGroup::create) and [`create_or_get`](SchedulingGroup::create_or_get)
+    /// in creation order.
+    ///
+    /// Groups created by Seastar itself or directly through the C++ API aren't included,
+    /// as Seastar doesn't allow to enumerate them.
+    pub async fn all() -> Vec<Self> {
+        submit_to(0, || async {
+            // The main group, `seastar::default_scheduling_group()`, always has index 0.
+            let main = SchedulingGroup { index: 0 };
+            let created = CREATED.with(|created| created.borrow().clone());
+            std::iter::once(main).chain(created).collect()
+        })
+        .await
+    }
+
+    /// Returns the scheduling group of the currently running task.
+    pub fn current() -> Self {
+        crate::assert_runtime_is_running();
+        Self {
+            index: current_scheduling_group(),
+        }
+    }
+
+    /// Returns the name of the scheduling group.
+    pub fn name(&self) -> String {
+        crate::assert_runtime_is_running();
+        scheduling_group_name(self.index)
+    }
+
+    pub(crate) fn index(&self) -> u32 {
+        self.index
+    }
+
+    async fn create_unregistered(name: &str, shares: f32) -> Result<Self, SchedulingGroupError> {
+        crate::assert_runtime_is_running();
+        match create_scheduling_group(name, shares).await {
+            Ok(index) => Ok(Self {
+                index: index as u32,
+            }),
+            Err(e) => Err(SchedulingGroupError(e.to_string())),
+        }
+    }
+
+    // Registers `group` under `name` unless a group with that name is already registered.
+    // Returns the registered group.
+    fn register(name: String, group: SchedulingGroup) -> SchedulingGroup {
+        GROUPS.with(|groups| *groups.borrow_mut().entry(name).or_insert(group))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as seastar;
+
+    #[seastar::test]
+    async fn test_create_or_get_returns_existing() {
+        let first = SchedulingGroup::create_or_get("bg", 100.0).await.unwrap();
+        let second = SchedulingGroup::create_or_get("bg", 200.0).await.unwrap();
+        assert_eq!(first, second);
+        assert_eq!(first.name(), "bg");
+    }
+
+    #[seastar::test]
+    async fn test_all() {
+        let first = SchedulingGroup::create("first", 100.0).await.unwrap();
+        let second = SchedulingGroup::create_or_get("second", 200.0)
+            .await
+            .unwrap();
+        // Getting an existing group doesn't create a new one.
+        SchedulingGroup::create_or_get("second", 200.0)
+            .await
+            .unwrap();
+
+        let all = SchedulingGroup::all().await;
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[0].name(), "main");
+        assert_eq!(all[0], SchedulingGroup::current());
+        assert_eq!(&all[1..], &[first, second]);
+    }
+
+    #[seastar::test]
+    async fn test_create_or_get_finds_created() {
+        let created = SchedulingGroup::create("fg", 100.0).await.unwrap();
+        assert_ne!(SchedulingGroup::create("fg", 100.0).await.unwrap(), created);
+        let got = SchedulingGroup::create_or_get("fg", 100.0).await.unwrap();
+        assert_eq!(got, created);
+    }
+}
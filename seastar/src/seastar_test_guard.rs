@@ -1,9 +1,23 @@
+use std::cell::Cell;
 use std::sync::{Mutex, MutexGuard};
 
 static RUNNING_TEST_WITH_SEASTAR: Mutex<()> = Mutex::new(());
 
+thread_local! {
+    static HOLDS_GUARD: Cell<bool> = Cell::new(false);
+}
+
 pub struct RunningTestWithSeastarGuard(MutexGuard<'static, ()>);
 
+const NESTED_RUNTIME_MESSAGE: &str =
+    "nested Seastar runtime: the thread already runs or waits for a Seastar test";
+
+impl Drop for RunningTestWithSeastarGuard {
+    fn drop(&mut self) {
+        HOLDS_GUARD.with(|holds| holds.set(false));
+    }
+}
+
 /// Acquires a global mutex for the purpose of running a test with the
 /// seastar runtime.
 ///
@@ -15,11 +29,63 @@ pub struct RunningTestWithSeastarGuard(MutexGuard<'static, ()>);
 ///
 /// The mutex should be taken by all tests that create a seastar runtime
 /// and held until the test finishes.
+///
+/// # Panics
+///
+/// Panics if the current thread already holds the mutex, e.g. when a test running
+/// a seastar runtime tries to start a nested one. Otherwise, it would deadlock.
 pub fn acquire_guard_for_seastar_test() -> RunningTestWithSeastarGuard {
+    if HOLDS_GUARD.with(|holds| holds.get()) {
+        panic!("{}", NESTED_RUNTIME_MESSAGE);
+    }
     // If a test panics, we assume that the runtime has been stopped
     // properly in that test, so we can ignore that the lock is poisoned.
     let guard = RUNNING_TEST_WITH_SEASTAR
         .lock()
         .unwrap_or_else(|err| err.into_inner());
+    HOLDS_GUARD.with(|holds| holds.set(true));
     RunningTestWithSeastarGuard(guard)
 }
+
+/// Panics if the current thread runs a Seastar runtime or holds the guard for a Seastar test.
+///
+/// `#[seastar::test]` runs the test on a new thread, which acquires the guard with
+/// [`acquire_guard_for_seastar_test`]. The guard's own check can't see that the calling thread
+/// is already running a test then, so this is checked on the calling thread before spawning.
+/// Otherwise, a nested test would deadlock on the guard.
+pub fn assert_not_in_seastar_test() {
+    if HOLDS_GUARD.with(|holds| holds.get()) || crate::is_reactor_thread() {
+        panic!("{}", NESTED_RUNTIME_MESSAGE);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as seastar;
+
+    #[test]
+    #[should_panic(expected = "nested Seastar runtime")]
+    fn test_nested_guard_panics() {
+        let _guard = acquire_guard_for_seastar_test();
+        let _nested = acquire_guard_for_seastar_test();
+    }
+
+    #[seastar::test]
+    #[allow(unnameable_test_items)]
+    async fn test_nested_seastar_test_panics() {
+        #[seastar::test]
+        async fn nested() {}
+
+        // The nested test panics on the calling thread, instead of deadlocking.
+        let panic = std::panic::catch_unwind(nested).unwrap_err();
+        let message = panic.downcast_ref::<String>().unwrap();
+        assert!(message.starts_with("nested Seastar runtime"));
+    }
+
+    #[test]
+    fn test_guard_can_be_reacquired_after_drop() {
+        drop(acquire_guard_for_seastar_test());
+        drop(acquire_guard_for_seastar_test());
+    }
+}
@@ -0,0 +1,68 @@
+#include "semaphore.hh"
+
+namespace seastar_ffi {
+namespace semaphore {
+
+std::shared_ptr<semaphore> new_semaphore(size_t count) {
+    return std::make_shared<semaphore>(count);
+}
+
+std::shared_ptr<waiter> new_waiter() {
+    return std::make_shared<waiter>();
+}
+
+// Keeps running after the Rust future is dropped, so it holds its own references
+// to the semaphore and the waiter.
+VoidFuture wait(std::shared_ptr<semaphore> sem, size_t units, std::shared_ptr<waiter> w) {
+    std::exception_ptr ex;
+    try {
+        co_await sem->wait(w->as, units);
+    } catch (...) {
+        ex = std::current_exception();
+    }
+    if (ex) {
+        if (w->abandoned) {
+            // Aborted by `abandon`, the waiter was removed from the queue.
+            co_return;
+        }
+        std::rethrow_exception(ex);
+    }
+    if (w->abandoned) {
+        // The units were granted after the Rust future was dropped, nobody holds them.
+        sem->signal(units);
+    } else {
+        w->completed = true;
+    }
+}
+
+void abandon(const std::shared_ptr<semaphore>& sem, size_t units, const std::shared_ptr<waiter>& w) {
+    if (w->completed) {
+        // The units were taken, but the Rust future was dropped before it resolved.
+        sem->signal(units);
+        return;
+    }
+    w->abandoned = true;
+    // Dequeues the waiter if the units weren't granted yet, does nothing otherwise.
+    if (!w->as.abort_requested()) {
+        w->as.request_abort();
+    }
+}
+
+bool try_wait(const std::shared_ptr<semaphore>& sem, size_t units) {
+    return sem->try_wait(units);
+}
+
+void signal(const std::shared_ptr<semaphore>& sem, size_t units) {
+    sem->signal(units);
+}
+
+ssize_t available_units(const std::shared_ptr<semaphore>& sem) {
+    return sem->available_units();
+}
+
+size_t waiters(const std::shared_ptr<semaphore>& sem) {
+    return sem->waiters();
+}
+
+} // namespace semaphore
+} // namespace seastar_ffi
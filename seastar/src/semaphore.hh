@@ -0,0 +1,38 @@
+#pragma once
+
+#include "cxx_async_futures.hh"
+#include <seastar/core/abort_source.hh>
+#include <seastar/core/semaphore.hh>
+
+namespace seastar_ffi {
+namespace semaphore {
+
+using semaphore = seastar::semaphore;
+
+// State of a single `wait` shared with the Rust future awaiting it.
+struct waiter {
+    seastar::abort_source as;
+    // Set when the Rust future is dropped before `wait` completes.
+    bool abandoned = false;
+    // Set when `wait` took the units, possibly before the Rust future learned about it.
+    bool completed = false;
+};
+
+std::shared_ptr<semaphore> new_semaphore(size_t count);
+
+std::shared_ptr<waiter> new_waiter();
+
+VoidFuture wait(std::shared_ptr<semaphore> sem, size_t units, std::shared_ptr<waiter> w);
+
+void abandon(const std::shared_ptr<semaphore>& sem, size_t units, const std::shared_ptr<waiter>& w);
+
+bool try_wait(const std::shared_ptr<semaphore>& sem, size_t units);
+
+void signal(const std::shared_ptr<semaphore>& sem, size_t units);
+
+ssize_t available_units(const std::shared_ptr<semaphore>& sem);
+
+size_t waiters(const std::shared_ptr<semaphore>& sem);
+
+} // namespace semaphore
+} // namespace seastar_ffi
@@ -0,0 +1,306 @@
+use cxx::SharedPtr;
+
+#[cxx::bridge(namespace = "seastar_ffi::semaphore")]
+mod ffi {
+    unsafe extern "C++" {
+        include!("seastar/src/semaphore.hh");
+
+        type semaphore;
+        type waiter;
+
+        #[namespace = "seastar_ffi"]
+        type VoidFuture = crate::cxx_async_futures::VoidFuture;
+
+        fn new_semaphore(count: usize) -> SharedPtr<semaphore>;
+        fn new_waiter() -> SharedPtr<waiter>;
+        fn wait(sem: SharedPtr<semaphore>, units: usize, w: SharedPtr<waiter>) -> VoidFuture;
+        fn abandon(sem: &SharedPtr<semaphore>, units: usize, w: &SharedPtr<waiter>);
+        fn try_wait(sem: &SharedPtr<semaphore>, units: usize) -> bool;
+        fn signal(sem: &SharedPtr<semaphore>, units: usize);
+        fn available_units(sem: &SharedPtr<semaphore>) -> isize;
+        fn waiters(sem: &SharedPtr<semaphore>) -> usize;
+    }
+}
+
+use ffi::*;
+
+/// Counted resource guard.
+///
+/// This is a standard computer science semaphore, adapted for futures. You can deposit units
+/// into a counter, or take them away. Taking units from the counter may wait if not enough
+/// units are available. Waiters are served in FIFO order.
+///
+/// To support exceptional conditions, a semaphore's counter can go negative
+/// (see [`Semaphore::available_units`]), e.g. after signalling fewer units than were consumed.
+///
+/// A semaphore is local to the shard it was created on.
+pub struct Semaphore {
+    // Shared with the pending waits, which may outlive the semaphore if their futures are dropped.
+    inner: SharedPtr<semaphore>,
+}
+
+// Withdraws a pending wait when the future awaiting it is dropped,
+// or deposits the units back if they were already taken.
+struct AbandonOnDrop<'a> {
+    sem: &'a SharedPtr<semaphore>,
+    units: usize,
+    waiter: SharedPtr<waiter>,
+    armed: bool,
+}
+
+impl Drop for AbandonOnDrop<'_> {
+    fn drop(&mut self) {
+        if self.armed {
+            abandon(self.sem, self.units, &self.waiter);
+        }
+    }
+}
+
+impl Semaphore {
+    /// Creates a semaphore with `count` available units.
+    pub fn new(count: usize) -> Self {
+        Semaphore {
+            inner: new_semaphore(count),
+        }
+    }
+
+    /// Waits until at least `units` units are available and takes them away from the counter.
+    ///
+    /// Cancel safe: dropping the returned future before it completes withdraws the request.
+    /// If the units were already granted to it, they are deposited back.
+    pub async fn wait(&self, units: usize) {
+        crate::assert_runtime_is_running();
+        let mut guard = AbandonOnDrop {
+            sem: &self.inner,
+            units,
+            waiter: new_waiter(),
+            armed: true,
+        };
+        wait(self.inner.clone(), units, guard.waiter.clone())
+            .await
+            .unwrap();
+        guard.armed = false;
+    }
+
+    /// Takes `units` units away from the counter if they are available right away
+    /// and nobody is waiting for them.
+    ///
+    /// Returns `true` if the units were taken.
+    pub fn try_wait(&self, units: usize) -> bool {
+        try_wait(&self.inner, units)
+    }
+
+    /// Deposits `units` units into the counter, waking up waiters that can now proceed.
+    pub fn signal(&self, units: usize) {
+        signal(&self.inner, units);
+    }
+
+    /// Returns the number of units available in the counter. Can be negative.
+    pub fn available_units(&self) -> isize {
+        available_units(&self.inner)
+    }
+
+    /// Returns the number of waiters.
+    pub fn waiters(&self) -> usize {
+        waiters(&self.inner)
+    }
+
+    /// Takes `units` units away from the counter, waiting if needed,
+    /// and returns a guard that deposits them back when dropped.
+    ///
+    /// Equivalent to `seastar::get_units`. Cancel safe, like [`Semaphore::wait`].
+    pub async fn get_units(&self, units: usize) -> SemaphoreUnits<'_> {
+        self.wait(units).await;
+        SemaphoreUnits { sem: self, units }
+    }
+
+    /// Takes `units` units away from the counter if they are available right away,
+    /// and returns a guard that deposits them back when dropped.
+    ///
+    /// Equivalent to `seastar::try_get_units`.
+    pub fn try_get_units(&self, units: usize) -> Option<SemaphoreUnits<'_>> {
+        self.try_wait(units)
+            .then_some(SemaphoreUnits { sem: self, units })
+    }
+}
+
+/// Units taken away from a [`Semaphore`], which are deposited back when dropped (RAII).
+///
+/// Obtained with [`Semaphore::get_units`] or [`Semaphore::try_get_units`].
+pub struct SemaphoreUnits<'a> {
+    sem: &'a Semaphore,
+    units: usize,
+}
+
+impl<'a> SemaphoreUnits<'a> {
+    /// Returns the number of units held.
+    pub fn count(&self) -> usize {
+        self.units
+    }
+
+    /// Moves `units` of the held units into a new guard.
+    ///
+    /// Both guards deposit their own units back when dropped.
+    /// Equivalent to `seastar::semaphore_units::split`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `units` exceeds the number of units held.
+    pub fn split(&mut self, units: usize) -> SemaphoreUnits<'a> {
+        assert!(
+            units <= self.units,
+            "cannot split more semaphore units than are held"
+        );
+        self.units -= units;
+        SemaphoreUnits {
+            sem: self.sem,
+            units,
+        }
+    }
+
+    /// Deposits `units` of the held units back into the semaphore before the guard is dropped.
+    ///
+    /// Equivalent to `seastar::semaphore_units::return_units`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `units` exceeds the number of units held.
+    pub fn return_units(&mut self, units: usize) {
+        assert!(
+            units <= self.units,
+            "cannot return more semaphore units than are held"
+        );
+        self.units -= units;
+        if units > 0 {
+            self.sem.signal(units);
+        }
+    }
+}
+
+impl Drop for SemaphoreUnits<'_> {
+    fn drop(&mut self) {
+        if self.units > 0 {
+            self.sem.signal(self.units);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as seastar;
+    use futures::{join, poll};
+    use std::cell::Cell;
+
+    #[seastar::test]
+    async fn test_semaphore_wait_signal() {
+        let sem = Semaphore::new(2);
+        sem.wait(2).await;
+        assert_eq!(sem.available_units(), 0);
+        assert!(!sem.try_wait(1));
+        sem.signal(3);
+        assert_eq!(sem.available_units(), 3);
+        assert!(sem.try_wait(3));
+    }
+
+    #[seastar::test]
+    async fn test_semaphore_units_released_on_drop() {
+        let sem = Semaphore::new(1);
+        let units = sem.get_units(1).await;
+        assert_eq!(units.count(), 1);
+        assert!(sem.try_get_units(1).is_none());
+        drop(units);
+        assert_eq!(sem.available_units(), 1);
+        assert!(sem.try_get_units(1).is_some());
+        assert_eq!(sem.available_units(), 1);
+    }
+
+    #[seastar::test]
+    async fn test_semaphore_units_split() {
+        let sem = Semaphore::new(5);
+        let mut units = sem.get_units(5).await;
+        let split = units.split(2);
+        assert_eq!(units.count(), 3);
+        assert_eq!(split.count(), 2);
+        assert_eq!(sem.available_units(), 0);
+        drop(split);
+        assert_eq!(sem.available_units(), 2);
+        drop(units);
+        assert_eq!(sem.available_units(), 5);
+    }
+
+    #[seastar::test]
+    async fn test_semaphore_units_return_units() {
+        let sem = Semaphore::new(3);
+        let mut units = sem.get_units(3).await;
+        units.return_units(1);
+        assert_eq!(units.count(), 2);
+        assert_eq!(sem.available_units(), 1);
+        drop(units);
+        assert_eq!(sem.available_units(), 3);
+    }
+
+    #[seastar::test]
+    async fn test_semaphore_waiter_woken_by_signal() {
+        let sem = Semaphore::new(0);
+        let woken = Cell::new(false);
+
+        let waiter = async {
+            sem.wait(1).await;
+            woken.set(true);
+        };
+        let signaller = async {
+            assert!(!woken.get());
+            assert_eq!(sem.waiters(), 1);
+            sem.signal(1);
+        };
+
+        // join! polls the waiter first, so it's already waiting when signalled.
+        join!(waiter, signaller);
+        assert!(woken.get());
+    }
+
+    #[seastar::test]
+    async fn test_semaphore_wait_dropped_while_queued() {
+        let sem = Semaphore::new(0);
+        let mut wait = Box::pin(sem.wait(1));
+        assert!(poll!(&mut wait).is_pending());
+        assert_eq!(sem.waiters(), 1);
+        drop(wait);
+        assert_eq!(sem.waiters(), 0);
+
+        sem.signal(1);
+        crate::run_until_idle().await;
+        assert_eq!(sem.available_units(), 1);
+    }
+
+    #[seastar::test]
+    async fn test_semaphore_wait_dropped_after_grant() {
+        let sem = Semaphore::new(0);
+        let mut get_units = Box::pin(sem.get_units(1));
+        assert!(poll!(&mut get_units).is_pending());
+        // Grants the unit to the waiter, before the future learns about it.
+        sem.signal(1);
+        assert_eq!(sem.available_units(), 0);
+        drop(get_units);
+
+        crate::run_until_idle().await;
+        assert_eq!(sem.available_units(), 1);
+        assert!(sem.try_wait(1));
+    }
+
+    #[seastar::test]
+    async fn test_semaphore_wait_dropped_after_completion() {
+        let sem = Semaphore::new(0);
+        let mut get_units = Box::pin(sem.get_units(1));
+        assert!(poll!(&mut get_units).is_pending());
+        sem.signal(1);
+        // Lets the wait take the unit, without polling the future again.
+        crate::run_until_idle().await;
+        assert_eq!(sem.available_units(), 0);
+        drop(get_units);
+
+        assert_eq!(sem.available_units(), 1);
+        assert!(sem.try_wait(1));
+    }
+}
@@ -0,0 +1,143 @@
+use crate::{get_count, this_shard_id};
+use std::cell::RefCell;
+
+/// Storage holding a separate instance of `T` on each shard.
+///
+/// A lighter alternative to [`Distributed`](crate::Distributed) for per-shard state
+/// which doesn't need to be started or stopped, e.g. counters or caches. Each shard's
+/// instance is created on its first access from that shard, and it's only ever accessed
+/// from that shard. Share the storage between shards with an `Arc`.
+///
+/// The instances are dropped together with the storage, on the shard that drops it.
+/// That's why `T` has to be `Send` for the storage to be shared.
+pub struct ShardLocal<T> {
+    slots: Box<[RefCell<Option<T>>]>,
+    init: Box<dyn Fn() -> T + Send + Sync>,
+}
+
+// Each slot is only accessed from its own shard, so the instances are never shared.
+unsafe impl<T: Send> Send for ShardLocal<T> {}
+unsafe impl<T: Send> Sync for ShardLocal<T> {}
+
+impl<T: Default> ShardLocal<T> {
+    /// Creates a storage whose instances are created with [`Default::default`].
+    ///
+    /// This function must be called from the context of a Seastar runtime.
+    pub fn new() -> Self {
+        Self::with_init(T::default)
+    }
+}
+
+impl<T: Default> Default for ShardLocal<T> {
+    fn default() -> Self {
+        ShardLocal::new()
+    }
+}
+
+impl<T> ShardLocal<T> {
+    /// Creates a storage whose instances are created with `init`.
+    ///
+    /// This function must be called from the context of a Seastar runtime.
+    pub fn with_init<F>(init: F) -> Self
+    where
+        F: Fn() -> T + Send + Sync + 'static,
+    {
+        crate::assert_runtime_is_running();
+        ShardLocal {
+            slots: (0..get_count()).map(|_| RefCell::new(None)).collect(),
+            init: Box::new(init),
+        }
+    }
+
+    /// Calls `f` with the current shard's instance, creating it first if needed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from outside of a reactor thread, or from within `f` on
+    /// the same storage, which would give a second mutable reference to the instance.
+    pub fn with<F, Ret>(&self, f: F) -> Ret
+    where
+        F: FnOnce(&mut T) -> Ret,
+    {
+        let mut slot = self.slot().borrow_mut();
+        f(slot.get_or_insert_with(&self.init))
+    }
+
+    /// Checks whether the current shard's instance has been created.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from outside of a reactor thread.
+    pub fn is_initialized(&self) -> bool {
+        self.slot().borrow().is_some()
+    }
+
+    // Only the shard's own reactor thread may access its slot, since the slots aren't synchronized.
+    fn slot(&self) -> &RefCell<Option<T>> {
+        crate::assert_runtime_is_running();
+        assert!(
+            crate::is_reactor_thread(),
+            "ShardLocal can only be accessed from a reactor thread"
+        );
+        &self.slots[this_shard_id() as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as seastar;
+    use crate::submit_to;
+    use futures::future::join_all;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+
+    #[seastar::test]
+    async fn test_shard_local_lazy_init() {
+        let created = Arc::new(AtomicU32::new(0));
+        let created_clone = created.clone();
+        let local = ShardLocal::with_init(move || {
+            created_clone.fetch_add(1, Ordering::SeqCst);
+            vec![this_shard_id()]
+        });
+        assert!(!local.is_initialized());
+        assert_eq!(created.load(Ordering::SeqCst), 0);
+
+        local.with(|v| v.push(42));
+        assert_eq!(local.with(|v| v.clone()), vec![this_shard_id(), 42]);
+        assert!(local.is_initialized());
+        assert_eq!(created.load(Ordering::SeqCst), 1);
+    }
+
+    #[seastar::test]
+    async fn test_shard_local_independent_instances() {
+        let local = Arc::new(ShardLocal::<u32>::new());
+
+        // Each shard increments its own counter a different number of times.
+        join_all((0..get_count()).map(|shard| {
+            let local = local.clone();
+            submit_to(shard, move || async move {
+                for _ in 0..=shard {
+                    local.with(|counter| *counter += 1);
+                }
+            })
+        }))
+        .await;
+
+        let counters = join_all((0..get_count()).map(|shard| {
+            let local = local.clone();
+            submit_to(shard, move || async move { local.with(|counter| *counter) })
+        }))
+        .await;
+        assert_eq!(counters, (1..=get_count()).collect::<Vec<_>>());
+    }
+
+    #[seastar::test]
+    async fn test_shard_local_outside_reactor_thread() {
+        let local = Arc::new(ShardLocal::<u32>::new());
+        let local_clone = local.clone();
+        let result = std::thread::spawn(move || local_clone.with(|counter| *counter += 1)).join();
+        assert!(result.is_err());
+        assert!(!local.is_initialized());
+    }
+}
@@ -0,0 +1,152 @@
+use crate::{get_count, Distributed, Service};
+use std::cell::RefCell;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+// The part of a `ShardedMap` stored on a single shard.
+struct MapShard<K, V>(RefCell<HashMap<K, V>>);
+
+impl<K, V> Service for MapShard<K, V> {}
+
+/// A hash map partitioned amongst all shards of a Seastar app.
+///
+/// Each key is owned by a single shard, chosen by hashing the key.
+/// All operations are routed to the owning shard, so they can be called from any shard.
+///
+/// Like [`Distributed`], the map **must** be stopped with [`stop`](ShardedMap::stop)
+/// when it is no longer used.
+///
+/// # Examples
+///
+/// ```rust
+/// use seastar::ShardedMap;
+///
+/// #[seastar::test]
+/// async fn sharded_map_example() {
+///     let map = ShardedMap::start().await;
+///     map.insert("key".to_string(), 42).await;
+///     assert_eq!(map.get(&"key".to_string()).await, Some(42));
+///     map.stop().await;
+/// }
+/// ```
+pub struct ShardedMap<K, V> {
+    distr: Distributed<MapShard<K, V>>,
+}
+
+impl<K, V> ShardedMap<K, V>
+where
+    K: Hash + Eq + Send + 'static,
+    V: Send + 'static,
+{
+    /// Starts an empty map, creating its part on each shard.
+    pub async fn start() -> Self {
+        let distr = Distributed::start(|| MapShard(RefCell::new(HashMap::new()))).await;
+        Self { distr }
+    }
+
+    /// Stops the map on all shards, dropping its contents.
+    pub async fn stop(&self) {
+        self.distr.stop().await;
+    }
+
+    /// Returns the id of the shard owning `key`.
+    pub fn owning_shard(key: &K) -> u32 {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        (hasher.finish() % get_count() as u64) as u32
+    }
+
+    /// Inserts a key-value pair into the map.
+    ///
+    /// Returns the value previously stored under `key`, if any.
+    pub async fn insert(&self, key: K, value: V) -> Option<V> {
+        self.distr
+            .map_single(Self::owning_shard(&key), move |pss| async move {
+                pss.instance.0.borrow_mut().insert(key, value)
+            })
+            .await
+    }
+
+    /// Returns a copy of the value stored under `key`, if any.
+    pub async fn get(&self, key: &K) -> Option<V>
+    where
+        K: Clone,
+        V: Clone,
+    {
+        let key = key.clone();
+        self.distr
+            .map_single(Self::owning_shard(&key), move |pss| async move {
+                pss.instance.0.borrow().get(&key).cloned()
+            })
+            .await
+    }
+
+    /// Removes `key` from the map.
+    ///
+    /// Returns the value that was stored under `key`, if any.
+    pub async fn remove(&self, key: &K) -> Option<V>
+    where
+        K: Clone,
+    {
+        let key = key.clone();
+        self.distr
+            .map_single(Self::owning_shard(&key), move |pss| async move {
+                pss.instance.0.borrow_mut().remove(&key)
+            })
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as seastar;
+
+    #[seastar::test]
+    async fn test_sharded_map_insert_get() {
+        let map = ShardedMap::start().await;
+        for i in 0..100u32 {
+            assert_eq!(map.insert(i, i.to_string()).await, None);
+        }
+        assert_eq!(
+            map.insert(0, "zero".to_string()).await,
+            Some("0".to_string())
+        );
+        assert_eq!(map.get(&0).await, Some("zero".to_string()));
+        for i in 1..100u32 {
+            assert_eq!(map.get(&i).await, Some(i.to_string()));
+        }
+        assert_eq!(map.get(&100).await, None);
+        map.stop().await;
+    }
+
+    #[seastar::test]
+    async fn test_sharded_map_keys_on_owning_shard() {
+        let map = ShardedMap::start().await;
+        for i in 0..100u32 {
+            map.insert(i, ()).await;
+        }
+        for i in 0..100u32 {
+            let owner = ShardedMap::<u32, ()>::owning_shard(&i);
+            let stored = map
+                .distr
+                .map_single(owner, move |pss| async move {
+                    pss.instance.0.borrow().contains_key(&i)
+                })
+                .await;
+            assert!(stored);
+        }
+        map.stop().await;
+    }
+
+    #[seastar::test]
+    async fn test_sharded_map_remove() {
+        let map = ShardedMap::start().await;
+        map.insert("key".to_string(), 1).await;
+        assert_eq!(map.remove(&"key".to_string()).await, Some(1));
+        assert_eq!(map.remove(&"key".to_string()).await, None);
+        assert_eq!(map.get(&"key".to_string()).await, None);
+        map.stop().await;
+    }
+}
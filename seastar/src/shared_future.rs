@@ -0,0 +1,104 @@
+use futures::future::{FutureExt, LocalBoxFuture, Shared};
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// A future whose result can be awaited by multiple consumers.
+///
+/// Clones of a `SharedFuture` refer to the same computation. It's run once, when one
+/// of the clones is first polled, and its result is cached, so every consumer gets
+/// a clone of it. Handy when e.g. several connection handlers need the same
+/// lazily loaded configuration.
+///
+/// A `SharedFuture` is local to the shard it was created on.
+///
+/// Analogous to `seastar::shared_future`.
+///
+/// # Example
+///
+/// ```rust
+/// use seastar::SharedFuture;
+///
+/// #[seastar::test]
+/// async fn shared_future_example() {
+///     let config = SharedFuture::new(async { String::from("loaded") });
+///     let other = config.clone();
+///     assert_eq!(config.await, "loaded");
+///     assert_eq!(other.await, "loaded");
+/// }
+/// ```
+pub struct SharedFuture<T: Clone> {
+    inner: Shared<LocalBoxFuture<'static, T>>,
+}
+
+impl<T: Clone + 'static> SharedFuture<T> {
+    /// Creates a shared future running `fut`.
+    pub fn new<Fut>(fut: Fut) -> Self
+    where
+        Fut: Future<Output = T> + 'static,
+    {
+        SharedFuture {
+            inner: fut.boxed_local().shared(),
+        }
+    }
+}
+
+impl<T: Clone> SharedFuture<T> {
+    /// Returns the result of the computation if it has already completed.
+    pub fn peek(&self) -> Option<&T> {
+        self.inner.peek()
+    }
+
+    /// Checks whether the computation has already completed.
+    ///
+    /// Equivalent of `seastar::shared_future::available`.
+    pub fn available(&self) -> bool {
+        self.peek().is_some()
+    }
+}
+
+impl<T: Clone> Clone for SharedFuture<T> {
+    fn clone(&self) -> Self {
+        SharedFuture {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T: Clone> Future for SharedFuture<T> {
+    type Output = T;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        Pin::new(&mut self.inner).poll(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as seastar;
+    use crate::{sleep, spawn, Duration, SteadyClock};
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    #[seastar::test]
+    async fn test_shared_future_runs_once() {
+        let runs = Rc::new(Cell::new(0));
+        let runs_cloned = runs.clone();
+        let config = SharedFuture::new(async move {
+            runs_cloned.set(runs_cloned.get() + 1);
+            sleep(Duration::<SteadyClock>::from_millis(10)).await;
+            vec![1, 2, 3]
+        });
+        assert!(!config.available());
+
+        let first = spawn(config.clone());
+        let second = spawn(config.clone());
+        assert_eq!(first.await, vec![1, 2, 3]);
+        assert_eq!(second.await, vec![1, 2, 3]);
+        assert_eq!(runs.get(), 1);
+        assert_eq!(config.peek(), Some(&vec![1, 2, 3]));
+        assert_eq!(config.await, vec![1, 2, 3]);
+        assert_eq!(runs.get(), 1);
+    }
+}
@@ -50,4 +50,15 @@ mod tests {
         assert!(after_sleep - before_sleep >= duration);
         advance_clock_future.await;
     }
+
+    #[seastar::test]
+    async fn test_manual_clock_sleep_auto_advance() {
+        let duration = Duration::from_millis(100);
+        let _guard = ManualClock::auto_advance(Duration::from_millis(10), Duration::from_millis(1));
+
+        let before_sleep = ManualClock::now();
+        sleep::<ManualClock>(duration).await;
+        let after_sleep = ManualClock::now();
+        assert!(after_sleep - before_sleep >= duration);
+    }
 }
@@ -19,10 +19,107 @@ mod ffi {
 
 pub use ffi::{get_count, this_shard_id};
 
+use crate::{submit_to, with_timeout, Clock, Instant, TimedOutError};
+use futures::future::join_all;
+use std::future::Future;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+
+// The number of shards can't change while an app is running,
+// so it's cached after the first call. 0 means that it wasn't cached yet.
+static CACHED_COUNT: AtomicU32 = AtomicU32::new(0);
+
+/// Returns the total number of shards, like [`get_count`].
+///
+/// The value is cached after the first call within a run of an app,
+/// so it's cheaper than [`get_count`] in hot paths (e.g. when hashing keys to shards).
+pub fn cached_count() -> u32 {
+    match CACHED_COUNT.load(Ordering::Relaxed) {
+        0 => {
+            let count = get_count();
+            CACHED_COUNT.store(count, Ordering::Relaxed);
+            count
+        }
+        count => count,
+    }
+}
+
+// Called when an app starts, as its number of shards may differ from the previous one's.
+pub(crate) fn reset_cached_count() {
+    CACHED_COUNT.store(0, Ordering::Relaxed);
+}
+
+/// Asserts that the current shard is `expected`.
+///
+/// Helps to catch cross-shard misuse of shard-local data (e.g. raw pointers).
+/// Like [`debug_assert!`], it's only checked in builds with debug assertions enabled.
+#[track_caller]
+pub fn assert_on_shard(expected: u32) {
+    debug_assert_eq!(
+        this_shard_id(),
+        expected,
+        "expected to be running on shard {}",
+        expected
+    );
+}
+
+/// Runs a function `func` on every shard and gathers the results.
+///
+/// The returned vector is ordered by shard id, i.e. its `i`-th element is the result
+/// of `func` on shard `i`. The functions run concurrently on all shards.
+///
+/// # Example
+///
+/// ```rust
+/// use seastar::{get_count, smp, this_shard_id};
+///
+/// #[seastar::test]
+/// async fn submit_to_all_example() {
+///     let ids = smp::submit_to_all(|| async { this_shard_id() }).await;
+///     assert_eq!(ids, (0..get_count()).collect::<Vec<_>>());
+/// }
+/// ```
+pub fn submit_to_all<Func, Fut, Ret>(func: Func) -> impl Future<Output = Vec<Ret>>
+where
+    Func: Fn() -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Ret> + 'static,
+    Ret: Send + 'static,
+{
+    crate::assert_runtime_is_running();
+
+    let func = Arc::new(func);
+    join_all((0..get_count()).map(|shard_id| {
+        let func = func.clone();
+        submit_to(shard_id, move || func())
+    }))
+}
+
+/// Runs a function `func` on a `shard_id` shard, waiting for its result until a deadline.
+///
+/// Returns the result of `func` if it completes before `deadline`, [`TimedOutError`] otherwise.
+///
+/// Timing out only abandons waiting for the result: `func` is not cancelled, and it may
+/// still run to completion on the target shard, its result being dropped there.
+pub async fn submit_to_timeout<ClockType, Func, Fut, Ret>(
+    shard_id: u32,
+    deadline: Instant<ClockType>,
+    func: Func,
+) -> Result<Ret, TimedOutError>
+where
+    ClockType: Clock,
+    Func: FnOnce() -> Fut + Send + 'static,
+    Fut: Future<Output = Ret> + 'static,
+    Ret: Send + 'static,
+{
+    with_timeout(deadline, submit_to(shard_id, func)).await
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate as seastar;
+    use crate::{sleep, Duration, SteadyClock};
+    use std::sync::atomic::AtomicBool;
 
     #[test]
     fn test_this_shard_id() {
@@ -39,4 +136,48 @@ mod tests {
         // but this is not tested here, as there's no control over the order of tests,
         // and Seastar doesn't clean up the variable that stores the cpu count (`seastar::smp::count`).
     }
+
+    #[seastar::test]
+    async fn test_cached_count() {
+        assert_eq!(cached_count(), get_count());
+        assert_eq!(cached_count(), get_count());
+    }
+
+    #[seastar::test]
+    async fn test_submit_to_all() {
+        let ids = submit_to_all(|| async { this_shard_id() }).await;
+        assert_eq!(ids.len(), get_count() as usize);
+        for (i, id) in ids.into_iter().enumerate() {
+            assert_eq!(id, i as u32);
+        }
+    }
+
+    #[seastar::test]
+    async fn test_submit_to_timeout() {
+        let deadline = SteadyClock::now() + Duration::from_secs(10);
+        let ret = submit_to_timeout(1, deadline, || async { this_shard_id() }).await;
+        assert_eq!(ret.unwrap(), 1);
+
+        let finished = Arc::new(AtomicBool::new(false));
+        let finished_clone = finished.clone();
+        let deadline = SteadyClock::now() + Duration::from_millis(10);
+        let ret = submit_to_timeout(1, deadline, move || async move {
+            sleep(Duration::<SteadyClock>::from_millis(100)).await;
+            finished_clone.store(true, Ordering::SeqCst);
+        })
+        .await;
+        assert!(ret.is_err());
+        assert!(!finished.load(Ordering::SeqCst));
+
+        // The closure isn't cancelled by the timeout.
+        sleep(Duration::<SteadyClock>::from_millis(200)).await;
+        assert!(finished.load(Ordering::SeqCst));
+    }
+
+    #[seastar::test]
+    async fn test_assert_on_shard() {
+        assert_on_shard(this_shard_id());
+        #[cfg(debug_assertions)]
+        assert!(std::panic::catch_unwind(|| assert_on_shard(get_count())).is_err());
+    }
 }
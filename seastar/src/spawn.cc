@@ -13,8 +13,12 @@ VoidFuture cpp_spawn(VoidFuture future) {
     auto f = p.get_future();
     auto t = seastar::make_task([&]() -> seastar::future<> {
         auto local_p = std::move(p);
-        co_await std::move(future);
-        local_p.set_value(); 
+        try {
+            co_await std::move(future);
+            local_p.set_value();
+        } catch (...) {
+            local_p.set_exception(std::current_exception());
+        }
     });
     seastar::schedule(t);
     co_await std::move(f);
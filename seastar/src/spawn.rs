@@ -1,9 +1,18 @@
 use crate as seastar;
 use crate::cxx_async_local_future::IntoCxxAsyncLocalFuture;
 use core::cell::Cell;
+use cxx_async::CxxAsyncException;
 use ffi::*;
+use futures::future::{abortable, AbortHandle};
+use futures::FutureExt;
+use pin_project::pin_project;
+use std::any::Any;
 use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::pin::Pin;
 use std::rc::Rc;
+use std::task::{Context, Poll};
+use thiserror::Error;
 
 #[cxx::bridge]
 mod ffi {
@@ -20,39 +29,248 @@ mod ffi {
     }
 }
 
-/// Spawns a new asynchronous task, returning an `Ret`.
+/// Error returned by awaiting a [`TryJoinHandle`] when the task didn't complete.
+#[derive(Error, Debug)]
+pub enum SpawnError {
+    /// The task failed. Carries the message of the exception the underlying Seastar future
+    /// failed with. Panics inside the task are reported as such exceptions.
+    #[error("SpawnError: {0}")]
+    Failed(String),
+    /// The task was aborted (see [`JoinHandle::abort`]) before it completed.
+    #[error("SpawnError: task was aborted")]
+    Aborted,
+}
+
+impl SpawnError {
+    /// Returns the message of the exception the task failed with,
+    /// or a description of the error if it wasn't caused by an exception.
+    pub fn what(&self) -> &str {
+        match self {
+            SpawnError::Failed(message) => message,
+            SpawnError::Aborted => "task was aborted",
+        }
+    }
+
+    /// Returns `true` if the task was aborted.
+    pub fn is_aborted(&self) -> bool {
+        matches!(self, SpawnError::Aborted)
+    }
+}
+
+/// An owned permission to await the result of a task spawned with [`spawn`].
+///
+/// Awaiting the handle yields the task's output. Dropping the handle detaches
+/// the task - it keeps running in the background.
+#[pin_project]
+pub struct JoinHandle<Ret> {
+    #[pin]
+    fut: VoidFuture,
+    result: Rc<Cell<Option<Ret>>>,
+    finished: Rc<Cell<bool>>,
+    abort_handle: AbortHandle,
+}
+
+impl<Ret> JoinHandle<Ret> {
+    /// Requests the task to stop.
+    ///
+    /// Seastar futures cannot be interrupted mid-step, so the task is only
+    /// stopped (by dropping its future) at its next suspension point.
+    /// Aborting a task that has already finished does nothing.
+    ///
+    /// Awaiting the handle of an aborted task that didn't manage to finish panics.
+    /// Convert the handle with [`JoinHandle::try_join`] to get a [`SpawnError::Aborted`] instead.
+    pub fn abort(&self) {
+        self.abort_handle.abort();
+    }
+
+    /// Returns `true` if the task has finished, either by running to completion
+    /// or by being aborted.
+    pub fn is_finished(&self) -> bool {
+        self.finished.get()
+    }
+
+    /// Converts the handle into one whose awaiting yields a [`SpawnError`] instead of panicking,
+    /// e.g. if the task may be aborted.
+    pub fn try_join(self) -> TryJoinHandle<Ret> {
+        TryJoinHandle { inner: self }
+    }
+
+    fn poll_result(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<Ret, SpawnError>> {
+        let this = self.project();
+        match this.fut.poll(cx) {
+            Poll::Pending => Poll::Pending,
+            // The task's future resolves without a result only if it was aborted.
+            Poll::Ready(Ok(_)) => Poll::Ready(this.result.take().ok_or(SpawnError::Aborted)),
+            Poll::Ready(Err(e)) => Poll::Ready(Err(SpawnError::Failed(e.what().to_owned()))),
+        }
+    }
+}
+
+impl<Ret> Future for JoinHandle<Ret> {
+    type Output = Ret;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.poll_result(cx) {
+            Poll::Pending => Poll::Pending,
+            Poll::Ready(Ok(ret)) => Poll::Ready(ret),
+            Poll::Ready(Err(SpawnError::Aborted)) => panic!("awaited a task that was aborted"),
+            Poll::Ready(Err(e)) => panic!("spawned task failed: {}", e.what()),
+        }
+    }
+}
+
+/// An owned permission to await the result of a task spawned with [`try_spawn`].
+///
+/// Works like [`JoinHandle`], but awaiting it yields a [`SpawnError`]
+/// instead of panicking if the task failed or was aborted.
+#[pin_project]
+pub struct TryJoinHandle<Ret> {
+    #[pin]
+    inner: JoinHandle<Ret>,
+}
+
+impl<Ret> TryJoinHandle<Ret> {
+    /// Requests the task to stop. See [`JoinHandle::abort`].
+    pub fn abort(&self) {
+        self.inner.abort();
+    }
+
+    /// Returns `true` if the task has finished. See [`JoinHandle::is_finished`].
+    pub fn is_finished(&self) -> bool {
+        self.inner.is_finished()
+    }
+}
+
+impl<Ret> Future for TryJoinHandle<Ret> {
+    type Output = Result<Ret, SpawnError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.project().inner.poll_result(cx)
+    }
+}
+
+/// Spawns a new asynchronous task, returning a [`JoinHandle`] for it.
 ///
 /// The provided future will start running in the background immediately
 /// when `spawn` is called.
 ///
 /// Spawning a task enables the task to execute concurrently to other tasks.
+/// The task can be cancelled with [`JoinHandle::abort`].
+///
+/// The task runs on the current shard and never moves to another thread,
+/// so neither the future nor its output have to be `Send`. To run a task
+/// on another shard, use [`submit_to`](crate::submit_to).
 ///
 /// This function must be called from the context of a Seastar runtime.
-pub fn spawn<T, Ret: 'static>(future: T) -> impl Future<Output = Ret>
+///
+/// # Example
+///
+/// ```rust
+/// use std::cell::RefCell;
+/// use std::rc::Rc;
+///
+/// #[seastar::test]
+/// async fn spawn_example() {
+///     let shared = Rc::new(RefCell::new(vec![]));
+///     let shared_clone = shared.clone();
+///     seastar::spawn(async move { shared_clone.borrow_mut().push(1) }).await;
+///     assert_eq!(*shared.borrow(), vec![1]);
+/// }
+/// ```
+pub fn spawn<T, Ret: 'static>(future: T) -> JoinHandle<Ret>
 where
     T: Future<Output = Ret> + 'static,
 {
     seastar::assert_runtime_is_running();
 
-    let x: Rc<Cell<Option<Ret>>> = Default::default();
+    let result: Rc<Cell<Option<Ret>>> = Default::default();
+    let finished: Rc<Cell<bool>> = Default::default();
+    let (future, abort_handle) = abortable(future);
 
-    let x_clone = x.clone();
+    let result_clone = result.clone();
+    let finished_clone = finished.clone();
     let fut = cpp_spawn(VoidFuture::infallible_local(async move {
-        x_clone.set(Some(future.await));
+        if let Ok(ret) = future.await {
+            result_clone.set(Some(ret));
+        }
+        finished_clone.set(true);
     }));
 
-    async move {
-        let result = fut.await;
-        match result {
-            Ok(_) => x.take().unwrap(),
-            Err(_) => panic!(),
+    JoinHandle {
+        fut,
+        result,
+        finished,
+        abort_handle,
+    }
+}
+
+/// Spawns a new asynchronous task on the current shard, returning a [`JoinHandle`] for it.
+///
+/// An alias of [`spawn`], named after `tokio::task::spawn_local`.
+/// All Seastar tasks are local to their shard, so it works exactly the same.
+pub fn spawn_local<T, Ret: 'static>(future: T) -> JoinHandle<Ret>
+where
+    T: Future<Output = Ret> + 'static,
+{
+    spawn(future)
+}
+
+/// Spawns a new asynchronous task, returning a [`TryJoinHandle`] for it.
+///
+/// Works like [`spawn`], but if the task fails, awaiting the handle yields
+/// a [`SpawnError`] with the exception message instead of panicking.
+/// A panic inside `future` fails the task with the panic message.
+///
+/// This function must be called from the context of a Seastar runtime.
+pub fn try_spawn<T, Ret: 'static>(future: T) -> TryJoinHandle<Ret>
+where
+    T: Future<Output = Ret> + 'static,
+{
+    seastar::assert_runtime_is_running();
+
+    let result: Rc<Cell<Option<Ret>>> = Default::default();
+    let finished: Rc<Cell<bool>> = Default::default();
+    let (future, abort_handle) = abortable(future);
+
+    let result_clone = result.clone();
+    let finished_clone = finished.clone();
+    let fut = cpp_spawn(VoidFuture::fallible_local(async move {
+        let res = AssertUnwindSafe(future).catch_unwind().await;
+        finished_clone.set(true);
+        match res {
+            Ok(Ok(ret)) => {
+                result_clone.set(Some(ret));
+                Ok(())
+            }
+            Ok(Err(_aborted)) => Ok(()),
+            Err(panic) => Err(CxxAsyncException::new(panic_message(&*panic).into())),
         }
+    }));
+
+    TryJoinHandle {
+        inner: JoinHandle {
+            fut,
+            result,
+            finished,
+            abort_handle,
+        },
+    }
+}
+
+fn panic_message(panic: &(dyn Any + Send)) -> &str {
+    if let Some(message) = panic.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = panic.downcast_ref::<String>() {
+        message
+    } else {
+        "task panicked"
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{sleep, yield_now, Duration, SteadyClock};
 
     #[seastar::test]
     async fn test_empty_spawn_void() {
@@ -90,6 +308,56 @@ mod tests {
         assert!(matches!(res, 2));
     }
 
+    #[seastar::test]
+    async fn test_spawn_is_finished() {
+        let handle = spawn(async move { 42 });
+        assert!(!handle.is_finished());
+        sleep(Duration::<SteadyClock>::from_millis(1)).await;
+        assert!(handle.is_finished());
+        assert_eq!(handle.await, 42);
+    }
+
+    #[seastar::test]
+    async fn test_spawn_abort() {
+        let counter = Rc::new(Cell::new(0));
+        let counter_clone = counter.clone();
+        let handle = spawn(async move {
+            loop {
+                counter_clone.set(counter_clone.get() + 1);
+                yield_now().await;
+            }
+        });
+
+        for _ in 0..10 {
+            yield_now().await;
+        }
+        assert!(counter.get() > 0);
+        handle.abort();
+        // The task is dropped at its next suspension point.
+        for _ in 0..10 {
+            yield_now().await;
+        }
+        assert!(handle.is_finished());
+
+        let after_abort = counter.get();
+        for _ in 0..10 {
+            yield_now().await;
+        }
+        assert_eq!(counter.get(), after_abort);
+        assert!(handle.try_join().await.unwrap_err().is_aborted());
+    }
+
+    #[seastar::test]
+    async fn test_try_spawn_abort() {
+        let handle = try_spawn(async {
+            loop {
+                yield_now().await;
+            }
+        });
+        handle.abort();
+        assert!(handle.await.unwrap_err().is_aborted());
+    }
+
     #[seastar::test]
     async fn test_spawn_without_await() {
         let (tx, rx) = futures::channel::oneshot::channel::<i32>();
@@ -99,4 +367,33 @@ mod tests {
         });
         assert!(matches!(rx.await.unwrap(), 2));
     }
+
+    #[seastar::test]
+    async fn test_spawn_non_send_future() {
+        let shared = Rc::new(std::cell::RefCell::new(0));
+        let shared_clone = shared.clone();
+        let handle = spawn_local(async move {
+            sleep(Duration::<SteadyClock>::from_millis(1)).await;
+            *shared_clone.borrow_mut() += 1;
+            shared_clone
+        });
+        let returned = handle.await;
+        assert_eq!(*shared.borrow(), 1);
+        assert!(Rc::ptr_eq(&shared, &returned));
+    }
+
+    #[seastar::test]
+    async fn test_try_spawn_ok() {
+        assert_eq!(try_spawn(async { 42 }).await.unwrap(), 42);
+    }
+
+    #[seastar::test]
+    async fn test_try_spawn_surfaces_exception_message() {
+        let handle = try_spawn(async {
+            sleep(Duration::<SteadyClock>::from_millis(1)).await;
+            panic!("background task failed");
+        });
+        let err = handle.await.unwrap_err();
+        assert_eq!(err.what(), "background task failed");
+    }
 }
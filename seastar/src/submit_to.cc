@@ -1,5 +1,6 @@
 #include "submit_to.hh"
 #include <seastar/core/smp.hh>
+#include <seastar/core/with_scheduling_group.hh>
 
 namespace seastar_ffi {
 
@@ -11,6 +12,25 @@ VoidFuture submit_to(const uint32_t shard_id, uint8_t* closure, rust::Fn<VoidFut
     });
 }
 
+// Not a lambda, so that the arguments live in the coroutine frame
+// rather than in a lambda object that may be destroyed before the coroutine finishes.
+static seastar::future<> call_closure(uint8_t* closure, rust::Fn<VoidFuture(uint8_t*)> caller) {
+    co_await caller(closure);
+}
+
+VoidFuture submit_to_in(const uint32_t shard_id, const uint32_t sg_index, uint8_t* closure, rust::Fn<VoidFuture(uint8_t*)> caller) {
+    auto sg = seastar::internal::scheduling_group_from_index(sg_index);
+    auto func = [closure, caller] {
+        return call_closure(closure, caller);
+    };
+    if (shard_id == seastar::this_shard_id()) {
+        // `smp::submit_to` runs local functions in the current scheduling group.
+        co_await seastar::with_scheduling_group(sg, std::move(func));
+    } else {
+        co_await seastar::smp::submit_to(shard_id, seastar::smp_submit_to_options(sg), std::move(func));
+    }
+}
+
 } // submit_to
 
 } // seastar_ffi
@@ -12,6 +12,8 @@ using fn_once_caller_t = VoidFuture (*)(const uint8_t*);
 
 VoidFuture submit_to(const uint32_t shard_id, uint8_t* closure, rust::Fn<VoidFuture(uint8_t*)> caller);
 
+VoidFuture submit_to_in(const uint32_t shard_id, const uint32_t sg_index, uint8_t* closure, rust::Fn<VoidFuture(uint8_t*)> caller);
+
 } // submit_to
 
 } // seastar_ffi
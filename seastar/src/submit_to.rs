@@ -1,5 +1,7 @@
 use crate::ffi_utils::{get_dropper, get_fn_once_caller};
+use crate::{spawn, this_shard_id, SchedulingGroup};
 use ffi::*;
+use futures::future::Either;
 use std::future::Future;
 
 use crate::cxx_async_local_future::IntoCxxAsyncLocalFuture;
@@ -20,11 +22,21 @@ mod ffi {
             closure: *mut u8,
             caller: unsafe fn(*mut u8) -> VoidFuture,
         ) -> VoidFuture;
+
+        unsafe fn submit_to_in(
+            shard_id: u32,
+            sg_index: u32,
+            closure: *mut u8,
+            caller: unsafe fn(*mut u8) -> VoidFuture,
+        ) -> VoidFuture;
     }
 }
 
 /// Runs a function `func` on a `shard_id` shard.
 ///
+/// If `shard_id` is the current shard, `func` is spawned locally, without
+/// the overhead of a cross-shard message.
+///
 /// # Example
 ///
 /// ```rust
@@ -42,6 +54,60 @@ where
 {
     crate::assert_runtime_is_running();
 
+    if shard_id == this_shard_id() {
+        return Either::Left(spawn(func()));
+    }
+
+    Either::Right(submit_boxed(func, |closure, caller| unsafe {
+        ffi::submit_to(shard_id, closure, caller)
+    }))
+}
+
+/// Runs a function `func` on a `shard_id` shard, in the `sg` scheduling group.
+///
+/// Works like [`submit_to`], but `func` and the future it returns run under `sg`
+/// instead of the scheduling group current on the target shard. Useful for
+/// prioritizing cross-shard work.
+///
+/// # Example
+///
+/// ```rust
+/// use seastar::SchedulingGroup;
+///
+/// #[seastar::test]
+/// async fn submit_to_in_example() {
+///     let sg = SchedulingGroup::create_or_get("background", 100.0).await.unwrap();
+///     let ret = submit_to_in(1, &sg, || async { SchedulingGroup::current() }).await;
+///     assert_eq!(ret, sg);
+/// }
+/// ```
+pub fn submit_to_in<Func, Fut, Ret>(
+    shard_id: u32,
+    sg: &SchedulingGroup,
+    func: Func,
+) -> impl Future<Output = Ret>
+where
+    Func: FnOnce() -> Fut + Send + 'static,
+    Fut: Future<Output = Ret> + 'static,
+    Ret: Send + 'static,
+{
+    crate::assert_runtime_is_running();
+
+    let sg_index = sg.index();
+    submit_boxed(func, move |closure, caller| unsafe {
+        ffi::submit_to_in(shard_id, sg_index, closure, caller)
+    })
+}
+
+// Boxes `func` and hands it over to `submit`, which makes C++ code call it
+// (on another shard) with the given caller.
+fn submit_boxed<Func, Fut, Ret, Submit>(func: Func, submit: Submit) -> impl Future<Output = Ret>
+where
+    Func: FnOnce() -> Fut + Send + 'static,
+    Fut: Future<Output = Ret> + 'static,
+    Ret: Send + 'static,
+    Submit: FnOnce(*mut u8, fn(*mut u8) -> VoidFuture) -> VoidFuture,
+{
     let (tx, rx) = futures::channel::oneshot::channel::<Ret>();
 
     let closure = move || {
@@ -54,15 +120,13 @@ where
     let dropper = get_dropper(&closure);
     let boxed_closure = Box::into_raw(Box::new(closure)) as *mut u8;
 
-    unsafe {
-        let fut = ffi::submit_to(shard_id, boxed_closure, closure_caller);
-        async move {
-            match fut.await {
-                Ok(_) => rx.await.unwrap(),
-                Err(_) => {
-                    dropper(boxed_closure);
-                    panic!()
-                }
+    let fut = submit(boxed_closure, closure_caller);
+    async move {
+        match fut.await {
+            Ok(_) => rx.await.unwrap(),
+            Err(_) => {
+                dropper(boxed_closure);
+                panic!()
             }
         }
     }
@@ -99,6 +163,34 @@ mod tests {
         assert!(matches!(ret, 42));
     }
 
+    #[seastar::test]
+    async fn test_submit_to_local() {
+        let shard_id = this_shard_id();
+        let ret = submit_to(shard_id, || async { this_shard_id() }).await;
+        assert_eq!(ret, shard_id);
+        let ret = submit_to(shard_id, || async {
+            submit_to(this_shard_id(), || async { 42 }).await
+        })
+        .await;
+        assert_eq!(ret, 42);
+    }
+
+    #[seastar::test]
+    async fn test_submit_to_in() {
+        let sg = SchedulingGroup::create_or_get("submit_to_in", 200.0)
+            .await
+            .unwrap();
+        assert_ne!(SchedulingGroup::current(), sg);
+        for shard_id in [this_shard_id(), 1] {
+            let ret = submit_to_in(shard_id, &sg, || async {
+                (SchedulingGroup::current(), this_shard_id())
+            })
+            .await;
+            assert_eq!(ret, (sg, shard_id));
+        }
+        assert_ne!(SchedulingGroup::current(), sg);
+    }
+
     #[seastar::test]
     async fn test_submit_to_no_await() {
         let (tx, rx) = futures::channel::oneshot::channel::<i32>();
@@ -0,0 +1,26 @@
+#include "thread.hh"
+#include <seastar/core/thread.hh>
+
+namespace seastar_ffi {
+namespace thread {
+
+VoidFuture run_in_thread(size_t stack_size, uint8_t* closure, rust::Fn<void(uint8_t*)> caller) {
+    seastar::thread_attributes attr;
+    if (stack_size != 0) {
+        attr.stack_size = stack_size;
+    }
+    co_await seastar::async(std::move(attr), [closure, caller] {
+        caller(closure);
+    });
+}
+
+void maybe_yield() {
+    seastar::thread::maybe_yield();
+}
+
+bool running_in_thread() {
+    return seastar::thread::running_in_thread();
+}
+
+} // namespace thread
+} // namespace seastar_ffi
@@ -0,0 +1,17 @@
+#pragma once
+
+#include "cxx_async_futures.hh"
+#include "rust/cxx.h"
+
+namespace seastar_ffi {
+namespace thread {
+
+// Runs `caller(closure)` in a new `seastar::thread`. `stack_size` of 0 means the default size.
+VoidFuture run_in_thread(size_t stack_size, uint8_t* closure, rust::Fn<void(uint8_t*)> caller);
+
+void maybe_yield();
+
+bool running_in_thread();
+
+} // namespace thread
+} // namespace seastar_ffi
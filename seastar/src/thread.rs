@@ -0,0 +1,166 @@
+//! Stackful threads, running blocking-style code without returning to the reactor.
+//!
+//! Equivalent of the `seastar::thread` namespace.
+
+use crate::ffi_utils::{get_dropper, get_fn_once_caller};
+use std::cell::Cell;
+use std::panic::{self, AssertUnwindSafe};
+use std::rc::Rc;
+
+#[cxx::bridge]
+mod ffi {
+    #[namespace = "seastar_ffi"]
+    unsafe extern "C++" {
+        type VoidFuture = crate::cxx_async_futures::VoidFuture;
+    }
+
+    #[namespace = "seastar_ffi::thread"]
+    unsafe extern "C++" {
+        include!("seastar/src/thread.hh");
+
+        unsafe fn run_in_thread(
+            stack_size: usize,
+            closure: *mut u8,
+            caller: unsafe fn(*mut u8),
+        ) -> VoidFuture;
+
+        fn maybe_yield();
+
+        fn running_in_thread() -> bool;
+    }
+}
+
+/// A stackful thread of execution, running on the current shard.
+///
+/// Unlike a future, a thread can be suspended anywhere in its code, not just at `.await` points.
+/// This helps porting imperative code, which can run in a thread without being rewritten
+/// to `async` - it only has to call [`maybe_yield`] now and then, so that it doesn't
+/// hog the reactor.
+///
+/// Threads have their own stacks, so they are more expensive than futures.
+///
+/// Wraps `seastar::thread`.
+pub struct SeastarThread;
+
+impl SeastarThread {
+    /// Runs `func` in a new thread, returning its result once it finishes.
+    ///
+    /// `stack_size` is the size of the thread's stack (in bytes),
+    /// Seastar's default is used if it's `None`.
+    /// If `func` panics, the panic is resumed when the returned future is polled.
+    /// Dropping the returned future doesn't stop the thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics with the error message if Seastar fails to run the thread,
+    /// e.g. if its stack can't be allocated.
+    ///
+    /// Equivalent of `seastar::async`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use seastar::thread::{maybe_yield, SeastarThread};
+    ///
+    /// #[seastar::test]
+    /// async fn thread_example() {
+    ///     let sum = SeastarThread::run(None, || {
+    ///         let mut sum = 0u64;
+    ///         for i in 0..1_000_000 {
+    ///             sum += i;
+    ///             maybe_yield();
+    ///         }
+    ///         sum
+    ///     })
+    ///     .await;
+    ///     assert_eq!(sum, 499_999_500_000);
+    /// }
+    /// ```
+    pub async fn run<Func, Ret>(stack_size: Option<usize>, func: Func) -> Ret
+    where
+        Func: FnOnce() -> Ret + 'static,
+        Ret: 'static,
+    {
+        crate::assert_runtime_is_running();
+
+        let result = Rc::new(Cell::new(None));
+        let thread_result = result.clone();
+        // Set once the closure is called, which consumes (and frees) it.
+        let called = Rc::new(Cell::new(false));
+        let thread_called = called.clone();
+        // Panics mustn't unwind through C++ code, so they're passed back to the caller.
+        let closure = move || {
+            thread_called.set(true);
+            thread_result.set(Some(panic::catch_unwind(AssertUnwindSafe(func))));
+        };
+
+        let caller = get_fn_once_caller(&closure);
+        let dropper = get_dropper(&closure);
+        let boxed_closure = Box::into_raw(Box::new(closure)) as *mut u8;
+
+        match unsafe { ffi::run_in_thread(stack_size.unwrap_or(0), boxed_closure, caller) }.await {
+            Ok(_) => match result.take().unwrap() {
+                Ok(ret) => ret,
+                Err(payload) => panic::resume_unwind(payload),
+            },
+            Err(e) => {
+                // If the thread failed before calling the closure, it has to be freed here.
+                if !called.get() {
+                    dropper(boxed_closure);
+                }
+                panic!("failed to run a SeastarThread: {}", e.what())
+            }
+        }
+    }
+}
+
+/// Yields to other tasks if the current thread exhausted its time quota.
+///
+/// Equivalent of `seastar::thread::maybe_yield`.
+///
+/// # Panics
+///
+/// Panics if not called from a [`SeastarThread`].
+pub fn maybe_yield() {
+    assert!(
+        running_in_thread(),
+        "maybe_yield must be called from a SeastarThread"
+    );
+    ffi::maybe_yield();
+}
+
+/// Checks whether the caller runs in a [`SeastarThread`].
+///
+/// Equivalent of `seastar::thread::running_in_thread`.
+pub fn running_in_thread() -> bool {
+    ffi::running_in_thread()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as seastar;
+
+    #[seastar::test]
+    async fn test_thread_run() {
+        assert!(!running_in_thread());
+        let ret = SeastarThread::run(Some(64 << 10), || {
+            assert!(running_in_thread());
+            for _ in 0..1000 {
+                maybe_yield();
+            }
+            42
+        })
+        .await;
+        assert_eq!(ret, 42);
+    }
+
+    #[seastar::test]
+    async fn test_thread_panic_is_resumed() {
+        let thread = SeastarThread::run(None, || panic!("panic in a thread"));
+        let err = futures::FutureExt::catch_unwind(AssertUnwindSafe(thread))
+            .await
+            .unwrap_err();
+        assert_eq!(*err.downcast::<&str>().unwrap(), "panic in a thread");
+    }
+}
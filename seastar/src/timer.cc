@@ -203,5 +203,64 @@ int64_t mct_get_timeout(const manual_clock_timer& timer) {
 
 } // namespace manual_clock
 
+namespace system_clock {
+
+using seastar_ffi::clocks::steady_clock_now;
+using seastar_ffi::clocks::system_clock_now;
+using seastar_ffi::clocks::to_sc_duration;
+using seastar_ffi::clocks::to_sc_time_point;
+
+int64_t system_clock_timer::to_steady(int64_t at) {
+    _offset = system_clock_now() - steady_clock_now();
+    return at - _offset;
+}
+
+int64_t system_clock_timer::to_system(int64_t at) const {
+    return at + _offset;
+}
+
+std::unique_ptr<system_clock_timer> new_sysct() {
+    return std::make_unique<system_clock_timer>();
+}
+
+void sysct_set_callback(
+    system_clock_timer& timer,
+    uint8_t* callback,
+    rust::Fn<void(uint8_t*)> caller,
+    rust::Fn<void(uint8_t*)> dropper
+) {
+    timer.timer().set_callback(callback_object(callback, caller, dropper));
+}
+
+void sysct_arm_at(system_clock_timer& timer, int64_t at) {
+    timer.timer().arm(to_sc_time_point(timer.to_steady(at)));
+}
+
+void sysct_arm_at_periodic(system_clock_timer& timer, int64_t at, int64_t period) {
+    timer.timer().arm(to_sc_time_point(timer.to_steady(at)), std::optional(to_sc_duration(period)));
+}
+
+void sysct_rearm_at(system_clock_timer& timer, int64_t at) {
+    timer.timer().rearm(to_sc_time_point(timer.to_steady(at)));
+}
+
+void sysct_rearm_at_periodic(system_clock_timer& timer, int64_t at, int64_t period) {
+    timer.timer().rearm(to_sc_time_point(timer.to_steady(at)), std::optional(to_sc_duration(period)));
+}
+
+bool sysct_armed(const system_clock_timer& timer) {
+    return timer.timer().armed();
+}
+
+bool sysct_cancel(system_clock_timer& timer) {
+    return timer.timer().cancel();
+}
+
+int64_t sysct_get_timeout(const system_clock_timer& timer) {
+    return timer.to_system(to_nanos(timer.timer().get_timeout().time_since_epoch()).count());
+}
+
+} // namespace system_clock
+
 } // namespace timer
 } // namespace seastar_ffi
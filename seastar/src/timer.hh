@@ -94,5 +94,55 @@ int64_t mct_get_timeout(const manual_clock_timer& timer);
 
 } // namespace manual_clock
 
+namespace system_clock {
+
+// Seastar's reactor can't run timers on `std::chrono::system_clock`, so a system clock timer
+// is a steady clock timer armed at the steady time point corresponding to the given
+// system time point at the moment of arming.
+class system_clock_timer {
+    seastar::timer<seastar::steady_clock_type> _timer;
+    // Difference between the system and the steady clock (in nanoseconds) when the timer was last armed.
+    int64_t _offset = 0;
+
+public:
+    seastar::timer<seastar::steady_clock_type>& timer() {
+        return _timer;
+    }
+
+    const seastar::timer<seastar::steady_clock_type>& timer() const {
+        return _timer;
+    }
+
+    // Converts a system time point to a steady one, remembering the current clocks' offset.
+    int64_t to_steady(int64_t at);
+
+    int64_t to_system(int64_t at) const;
+};
+
+std::unique_ptr<system_clock_timer> new_sysct();
+
+void sysct_set_callback(
+    system_clock_timer& timer,
+    uint8_t* callback, // uint8_t is a substitute for void that isn't supported by cxx.
+    rust::Fn<void(uint8_t*)> caller,
+    rust::Fn<void(uint8_t*)> dropper
+);
+
+void sysct_arm_at(system_clock_timer& timer, int64_t at);
+
+void sysct_arm_at_periodic(system_clock_timer& timer, int64_t at, int64_t period);
+
+void sysct_rearm_at(system_clock_timer& timer, int64_t at);
+
+void sysct_rearm_at_periodic(system_clock_timer& timer, int64_t at, int64_t period);
+
+bool sysct_armed(const system_clock_timer& timer);
+
+bool sysct_cancel(system_clock_timer& timer);
+
+int64_t sysct_get_timeout(const system_clock_timer& timer);
+
+} // namespace system_clock
+
 } // namespace timer
 } // namespace seastar_ffi
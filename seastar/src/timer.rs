@@ -8,10 +8,11 @@ use std::marker::PhantomData;
 /// Timer callbacks should execute quickly. If involved processing is required,
 /// a timer can launch a continuation.
 ///
-/// Timer has 3 specializations:
+/// Timer has 4 specializations:
 /// - `Timer<SteadyClock>` − has relatively high accuracy but is quite expensive.
 /// - `Timer<LowresClock>` − has very coarse resolution (~10 ms) but is quite efficient.
 /// - `Timer<ManualClock>` − used mainly for testing.
+/// - `Timer<SystemClock>` − armed at wall-clock instants, see [`SystemClock`](crate::SystemClock).
 ///
 /// # Examples
 ///
@@ -214,7 +215,7 @@ mod tests {
     use super::*;
     use crate as seastar;
     use crate::sleep;
-    use crate::{LowresClock, ManualClock, SteadyClock};
+    use crate::{LowresClock, ManualClock, SteadyClock, SystemClock};
     use std::cell::RefCell;
     use std::rc::Rc;
 
@@ -230,6 +231,10 @@ mod tests {
         ManualClock::advance(duration);
     }
 
+    async fn system_clock_timer_wait(duration: Duration<SystemClock>) {
+        sleep(duration).await;
+    }
+
     // Macro used to generate tests for the timer specializations.
     // - `Clock` - name of the corresponding clock type,
     // - `timer` - infix used to define test names,
@@ -401,4 +406,6 @@ mod tests {
     test_timer!(LowresClock, lowres_clock_timer, lowres_clock_timer_wait);
 
     test_timer!(ManualClock, manual_clock_timer, manual_clock_timer_wait);
+
+    test_timer!(SystemClock, system_clock_timer, system_clock_timer_wait);
 }
@@ -0,0 +1,175 @@
+use futures::future::{select, Either};
+use futures::pin_mut;
+use std::future::Future;
+
+#[doc(hidden)]
+pub use futures::join as __join;
+#[doc(hidden)]
+pub use futures::try_join as __try_join;
+
+/// Waits for multiple futures of possibly different types to complete.
+///
+/// Returns a future resolving to a tuple with the outputs of the futures, in the order
+/// they were passed in. The futures are polled concurrently on the current shard,
+/// so they don't need to be `Send`. To wait for a collection of futures of the same
+/// type, use [`futures::future::join_all`] instead.
+///
+/// Analogous to `seastar::when_all`. To stop waiting as soon as one of the futures
+/// fails, use [`when_all_succeed!`](crate::when_all_succeed!).
+///
+/// # Example
+///
+/// ```rust
+/// #[seastar::test]
+/// async fn when_all_example() {
+///     let (number, text) = seastar::when_all!(async { 42 }, async { "forty-two" }).await;
+///     assert_eq!(number, 42);
+///     assert_eq!(text, "forty-two");
+/// }
+/// ```
+#[macro_export]
+macro_rules! when_all {
+    ($($fut:expr),+ $(,)?) => {
+        async { $crate::__join!($($fut),+) }
+    };
+}
+
+/// Waits for multiple fallible futures of possibly different types to complete successfully.
+///
+/// All futures must resolve to a `Result` with the same error type.
+/// Returns a future resolving to `Ok` with a tuple of the successful values,
+/// in the order the futures were passed in, or to the first error.
+/// As soon as one of the futures fails, the remaining ones are dropped
+/// without waiting for them to complete.
+///
+/// Equivalent of `seastar::when_all_succeed`.
+///
+/// # Example
+///
+/// ```rust
+/// #[seastar::test]
+/// async fn when_all_succeed_example() {
+///     let ok = seastar::when_all_succeed!(async { Ok::<_, ()>(42) }, async { Ok(true) }).await;
+///     assert_eq!(ok, Ok((42, true)));
+///
+///     let err = seastar::when_all_succeed!(async { Ok::<_, &str>(42) }, async { Err::<bool, _>("fail") }).await;
+///     assert_eq!(err, Err("fail"));
+/// }
+/// ```
+#[macro_export]
+macro_rules! when_all_succeed {
+    ($($fut:expr),+ $(,)?) => {
+        async { $crate::__try_join!($($fut),+) }
+    };
+}
+
+/// Waits for the first of two futures to complete.
+///
+/// Returns a future resolving to [`Either::Left`] with the output of `a` if it completes first,
+/// or to [`Either::Right`] with the output of `b` otherwise. The other future is dropped then,
+/// which cancels it at its current suspension point. If both futures are ready at the same time,
+/// `a` wins. The futures are polled on the current shard, so they don't need to be `Send`.
+///
+/// # Example
+///
+/// ```rust
+/// use futures::future::Either;
+/// use seastar::{race, sleep, Duration, SteadyClock};
+///
+/// #[seastar::test]
+/// async fn race_example() {
+///     let fast = sleep(Duration::<SteadyClock>::from_millis(1));
+///     let slow = async {
+///         sleep(Duration::<SteadyClock>::from_secs(10)).await;
+///         42
+///     };
+///     assert!(matches!(race(fast, slow).await, Either::Left(())));
+/// }
+/// ```
+pub async fn race<A, B>(a: A, b: B) -> Either<A::Output, B::Output>
+where
+    A: Future,
+    B: Future,
+{
+    pin_mut!(a, b);
+    match select(a, b).await {
+        Either::Left((ret, _)) => Either::Left(ret),
+        Either::Right((ret, _)) => Either::Right(ret),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as seastar;
+    use crate::{get_count, sleep, submit_to, Duration, SteadyClock};
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    struct SetOnDrop(Rc<Cell<bool>>);
+
+    impl Drop for SetOnDrop {
+        fn drop(&mut self) {
+            self.0.set(true);
+        }
+    }
+
+    #[seastar::test]
+    async fn test_when_all_submit_to_and_sleep() {
+        let shard = get_count() - 1;
+        let (ret, ()) = seastar::when_all!(
+            submit_to(shard, || async { 42 }),
+            sleep(Duration::<SteadyClock>::from_millis(10)),
+        )
+        .await;
+        assert_eq!(ret, 42);
+    }
+
+    #[seastar::test]
+    async fn test_when_all_succeed_ok() {
+        let res = seastar::when_all_succeed!(async { Ok::<_, &str>(42) }, async {
+            sleep(Duration::<SteadyClock>::from_millis(10)).await;
+            Ok("forty-two")
+        },)
+        .await;
+        assert_eq!(res, Ok((42, "forty-two")));
+    }
+
+    #[seastar::test]
+    async fn test_when_all_succeed_short_circuits() {
+        let dropped = Rc::new(Cell::new(false));
+        let guard = SetOnDrop(dropped.clone());
+        let slow = async move {
+            let _guard = guard;
+            // Never completes, so the test would hang if the error didn't short-circuit.
+            futures::future::pending::<()>().await;
+            Ok::<_, &str>(42)
+        };
+        let failing = async {
+            sleep(Duration::<SteadyClock>::from_millis(10)).await;
+            Err::<u32, _>("failed")
+        };
+
+        let res = seastar::when_all_succeed!(slow, failing).await;
+        assert_eq!(res, Err("failed"));
+        assert!(dropped.get());
+    }
+
+    #[seastar::test]
+    async fn test_race_drops_slower() {
+        let dropped = Rc::new(Cell::new(false));
+        let guard = SetOnDrop(dropped.clone());
+        let fast = async {
+            sleep(Duration::<SteadyClock>::from_millis(1)).await;
+            "fast"
+        };
+        let slow = async move {
+            let _guard = guard;
+            sleep(Duration::<SteadyClock>::from_secs(10)).await;
+        };
+
+        let res = race(slow, fast).await;
+        assert!(matches!(res, Either::Right("fast")));
+        assert!(dropped.get());
+    }
+}
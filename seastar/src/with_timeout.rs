@@ -0,0 +1,116 @@
+use crate::{race, sleep, Clock, Instant};
+use futures::future::Either;
+use std::future::Future;
+use thiserror::Error;
+
+/// Error returned by [`with_timeout`] when the deadline passes before the future completes.
+#[derive(Error, Debug)]
+#[error("TimedOutError: timedout")]
+pub struct TimedOutError;
+
+/// Waits for a future to complete until a deadline.
+///
+/// Returns the output of `fut` if it completes before `deadline`, [`TimedOutError`] otherwise.
+/// In the latter case `fut` is dropped.
+///
+/// Equivalent of `seastar::with_timeout`.
+pub async fn with_timeout<ClockType, Fut>(
+    deadline: Instant<ClockType>,
+    fut: Fut,
+) -> Result<Fut::Output, TimedOutError>
+where
+    ClockType: Clock,
+    Fut: Future,
+{
+    crate::assert_runtime_is_running();
+    let timeout = sleep(deadline - ClockType::now());
+    match race(fut, timeout).await {
+        Either::Left(ret) => Ok(ret),
+        Either::Right(()) => Err(TimedOutError),
+    }
+}
+
+/// Waits for a future to complete until a deadline, falling back to a default value.
+///
+/// Returns the output of `fut` if it completes before `deadline`, `default` otherwise.
+/// Handy for best-effort operations, like cache lookups.
+pub async fn with_timeout_or<ClockType, Fut, T>(
+    deadline: Instant<ClockType>,
+    fut: Fut,
+    default: T,
+) -> T
+where
+    ClockType: Clock,
+    Fut: Future<Output = T>,
+{
+    with_timeout(deadline, fut).await.unwrap_or(default)
+}
+
+/// Waits for a future to complete until a deadline, falling back to a computed value.
+///
+/// Works like [`with_timeout_or`], but the fallback value is computed by `default`,
+/// which is only called if the deadline passes.
+pub async fn with_timeout_or_else<ClockType, Fut, T, F>(
+    deadline: Instant<ClockType>,
+    fut: Fut,
+    default: F,
+) -> T
+where
+    ClockType: Clock,
+    Fut: Future<Output = T>,
+    F: FnOnce() -> T,
+{
+    with_timeout(deadline, fut)
+        .await
+        .unwrap_or_else(|_| default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as seastar;
+    use crate::{spawn, Duration, ManualClock, SteadyClock};
+
+    // Advances `ManualClock` past `deadline`, giving other tasks a chance to run first.
+    async fn advance_past(deadline: Instant<ManualClock>) {
+        sleep(Duration::<SteadyClock>::from_millis(10)).await;
+        ManualClock::advance(deadline - ManualClock::now() + Duration::from_millis(1));
+    }
+
+    #[seastar::test]
+    async fn test_with_timeout_completes() {
+        let deadline = ManualClock::now() + Duration::from_millis(10);
+        assert_eq!(with_timeout(deadline, async { 42 }).await.unwrap(), 42);
+        assert_eq!(with_timeout_or(deadline, async { 42 }, 0).await, 42);
+    }
+
+    #[seastar::test]
+    async fn test_with_timeout_or_misses_deadline() {
+        let deadline = ManualClock::now() + Duration::from_millis(10);
+        let advance = spawn(advance_past(deadline));
+        let slow = sleep(Duration::<ManualClock>::from_millis(100));
+        let ret = with_timeout_or(
+            deadline,
+            async move {
+                slow.await;
+                42
+            },
+            0,
+        )
+        .await;
+        assert_eq!(ret, 0);
+        advance.await;
+    }
+
+    #[seastar::test]
+    async fn test_with_timeout_or_else_misses_deadline() {
+        let deadline = ManualClock::now() + Duration::from_millis(10);
+        let advance = spawn(advance_past(deadline));
+        let ret = with_timeout_or_else(deadline, futures::future::pending(), || 7).await;
+        assert_eq!(ret, 7);
+        assert!(with_timeout(deadline, futures::future::pending::<()>())
+            .await
+            .is_err());
+        advance.await;
+    }
+}
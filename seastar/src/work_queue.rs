@@ -0,0 +1,153 @@
+use crate::{spawn, Gate, JoinHandle, Semaphore};
+use std::cell::RefCell;
+use std::future::Future;
+use std::rc::Rc;
+
+/// A shard-local queue of background jobs, running at most a fixed number of them at a time.
+///
+/// Jobs are started in the order they were submitted. Useful for rate-limiting background work,
+/// e.g. bounding the number of concurrently handled connections.
+pub struct WorkQueue {
+    sem: Rc<Semaphore>,
+    concurrency: usize,
+    // Entered by every job as soon as it's submitted. `drain` replaces it with a fresh gate
+    // and closes the old one to wait for the jobs submitted before.
+    jobs: RefCell<Rc<Gate>>,
+}
+
+impl WorkQueue {
+    /// Creates a queue running at most `concurrency` jobs at a time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `concurrency` is `0`.
+    pub fn new(concurrency: usize) -> Self {
+        assert!(concurrency > 0, "WorkQueue concurrency must be positive");
+        WorkQueue {
+            sem: Rc::new(Semaphore::new(concurrency)),
+            concurrency,
+            jobs: RefCell::new(Rc::new(Gate::new())),
+        }
+    }
+
+    /// Returns the maximum number of jobs running at a time.
+    pub fn concurrency(&self) -> usize {
+        self.concurrency
+    }
+
+    /// Submits a job to the queue, returning a [`JoinHandle`] for it.
+    ///
+    /// The job starts once fewer than `concurrency` jobs submitted before it are running.
+    /// Dropping the handle doesn't cancel the job.
+    ///
+    /// This function must be called from the context of a Seastar runtime.
+    pub fn submit<Fut>(&self, fut: Fut) -> JoinHandle<Fut::Output>
+    where
+        Fut: Future + 'static,
+        Fut::Output: 'static,
+    {
+        let sem = self.sem.clone();
+        // The job is tracked right away, even though the spawned task starts later.
+        let holder = self.jobs.borrow().enter_owned().unwrap();
+        spawn(async move {
+            let _holder = holder;
+            let _units = sem.get_units(1).await;
+            fut.await
+        })
+    }
+
+    /// Waits until all jobs submitted before the call have finished.
+    ///
+    /// The jobs are determined when `drain` is called, not when the returned future
+    /// is first polled. Jobs submitted later are not waited for, and may run in the meantime.
+    pub fn drain(&self) -> impl Future<Output = ()> {
+        let jobs = Rc::new(Gate::new());
+        // A later drain waits for this one, and so for the jobs it waits for.
+        let holder = jobs.enter_owned().unwrap();
+        let submitted = self.jobs.replace(jobs);
+        async move {
+            submitted.close().await;
+            drop(holder);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as seastar;
+    use crate::{sleep, Duration, SteadyClock};
+    use std::cell::{Cell, RefCell};
+
+    #[seastar::test]
+    async fn test_work_queue_bounds_concurrency() {
+        let queue = WorkQueue::new(2);
+        let running = Rc::new(Cell::new(0));
+        let max_running = Rc::new(Cell::new(0));
+        let finished = Rc::new(Cell::new(0));
+
+        for _ in 0..10 {
+            let running = running.clone();
+            let max_running = max_running.clone();
+            let finished = finished.clone();
+            queue.submit(async move {
+                running.set(running.get() + 1);
+                max_running.set(max_running.get().max(running.get()));
+                sleep(Duration::<SteadyClock>::from_millis(2)).await;
+                running.set(running.get() - 1);
+                finished.set(finished.get() + 1);
+            });
+        }
+
+        queue.drain().await;
+        assert_eq!(finished.get(), 10);
+        assert_eq!(max_running.get(), 2);
+    }
+
+    #[seastar::test]
+    async fn test_work_queue_fifo() {
+        let queue = WorkQueue::new(1);
+        let order = Rc::new(RefCell::new(vec![]));
+
+        let handles: Vec<_> = (0..5)
+            .map(|i| {
+                let order = order.clone();
+                queue.submit(async move {
+                    sleep(Duration::<SteadyClock>::from_millis(1)).await;
+                    order.borrow_mut().push(i);
+                    i
+                })
+            })
+            .collect();
+
+        for (i, handle) in handles.into_iter().enumerate() {
+            assert_eq!(handle.await, i);
+        }
+        assert_eq!(*order.borrow(), (0..5).collect::<Vec<_>>());
+    }
+
+    #[seastar::test]
+    async fn test_work_queue_drain_waits_for_submitted_jobs() {
+        let queue = WorkQueue::new(4);
+        let finished = Rc::new(Cell::new(0));
+        let submit = |ms| {
+            let finished = finished.clone();
+            // The handle is dropped right away, the job isn't started yet when draining.
+            queue.submit(async move {
+                sleep(Duration::<SteadyClock>::from_millis(ms)).await;
+                finished.set(finished.get() + 1);
+            });
+        };
+
+        submit(5);
+        let first_drain = queue.drain();
+        submit(1);
+        let second_drain = queue.drain();
+        // The second drain also waits for the job submitted before the first one.
+        second_drain.await;
+        assert_eq!(finished.get(), 2);
+        first_drain.await;
+
+        queue.drain().await;
+    }
+}